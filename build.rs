@@ -0,0 +1,83 @@
+//! With the `glslang` feature, compiles the kit's GLSL shaders to SPIR-V with
+//! `glslangValidator` into `OUT_DIR`, whenever a shader source is newer than
+//! its compiled module, and the kit embeds those modules. Compiler errors
+//! fail the build. Without the feature, the committed `.spv` files are used
+//! as-is, and nothing is compiled without the `kit` feature.
+//!
+//! The source tree is never written to, so that building from a read-only
+//! checkout or the registry works.
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SHADERS: &str = "src/kit/data";
+
+fn main() {
+    println!("cargo:rerun-if-changed={}", SHADERS);
+
+    if std::env::var_os("CARGO_FEATURE_GLSLANG").is_none()
+        || std::env::var_os("CARGO_FEATURE_KIT").is_none()
+    {
+        return;
+    }
+
+    // Modules are written to the same relative path as the committed ones,
+    // eg. `$OUT_DIR/data/sprite.vert.spv`.
+    let out =
+        PathBuf::from(env::var_os("OUT_DIR").expect("fatal: OUT_DIR is not set")).join("data");
+    fs::create_dir_all(&out).expect("fatal: can't create shader output directory");
+
+    for entry in fs::read_dir(SHADERS).expect("fatal: can't read shader directory") {
+        let src = entry.expect("fatal: can't read shader directory").path();
+
+        match src.extension().and_then(|e| e.to_str()) {
+            Some("vert") | Some("frag") | Some("comp") => {}
+            _ => continue,
+        }
+        println!("cargo:rerun-if-changed={}", src.display());
+
+        let dst = out.join(format!(
+            "{}.spv",
+            src.file_name().unwrap().to_string_lossy()
+        ));
+        if is_fresh(&src, &dst) {
+            continue;
+        }
+        compile(&src, &dst);
+    }
+}
+
+/// Whether `dst` was modified after `src`.
+fn is_fresh(src: &Path, dst: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+
+    match (modified(src), modified(dst)) {
+        (Some(s), Some(d)) => d >= s,
+        _ => false,
+    }
+}
+
+fn compile(src: &Path, dst: &Path) {
+    let output = Command::new("glslangValidator")
+        .arg("-V")
+        .arg(src)
+        .arg("-o")
+        .arg(dst)
+        .output()
+        .unwrap_or_else(|e| {
+            panic!(
+                "fatal: can't run glslangValidator, which the `glslang` feature requires: {}",
+                e
+            )
+        });
+
+    if !output.status.success() {
+        panic!(
+            "fatal: failed to compile {}:\n{}{}",
+            src.display(),
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
@@ -49,6 +49,7 @@ fn main() {
         texture.rect(),
         Rect::new(0., 0., size.width as f32, size.height as f32),
         Rgba::new(0.5, 0.6, 0.8, 1.0),
+        sprite2d::Tint::NONE,
         1.0,
         kit::Repeat::new(24. * (size.width / size.height) as f32, 24.),
     )
@@ -174,6 +174,7 @@ fn main() {
         rect,
         Rect::origin(sw as f32, sh as f32),
         Rgba::TRANSPARENT,
+        sprite2d::Tint::NONE,
         1.0,
         Repeat::default(),
     );
@@ -0,0 +1,27 @@
+#![deny(clippy::all)]
+
+use rgx::core::Gpu;
+use rgx::kit::bench::{Bench, Scene};
+
+fn main() {
+    env_logger::init();
+
+    let frames = std::env::args()
+        .nth(1)
+        .and_then(|a| a.parse().ok())
+        .unwrap_or(120);
+
+    let gpu = Gpu::new();
+    let mut bench = Bench::new(&gpu, 1024, 768);
+
+    for scene in &[
+        Scene::Sprites(1_000),
+        Scene::Sprites(10_000),
+        Scene::Shapes(1_000),
+        Scene::Shapes(10_000),
+        Scene::Uploads(16),
+        Scene::Uploads(256),
+    ] {
+        println!("{}", bench.run(*scene, frames));
+    }
+}
@@ -1,13 +1,24 @@
 #![deny(clippy::all, clippy::use_self)]
 #![allow(clippy::cast_lossless)]
 
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::convert::TryFrom;
 use std::fmt;
-use std::ops::Range;
+use std::ops::{Add, Mul, Range};
+use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
 
 use raw_window_handle::RawWindowHandle;
 
+pub use smallvec::SmallVec;
+
 use crate::math;
+
+mod names;
 use crate::math::{Point2, Vector2};
 
 ///////////////////////////////////////////////////////////////////////////
@@ -16,6 +27,7 @@ use crate::math::{Point2, Vector2};
 
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgba8 {
     pub r: u8,
     pub g: u8,
@@ -60,10 +72,101 @@ impl Rgba8 {
         b: 0xff,
         a: 0xff,
     };
+    pub const YELLOW: Self = Self {
+        r: 0xff,
+        g: 0xff,
+        b: 0,
+        a: 0xff,
+    };
+    pub const CYAN: Self = Self {
+        r: 0,
+        g: 0xff,
+        b: 0xff,
+        a: 0xff,
+    };
+    pub const MAGENTA: Self = Self {
+        r: 0xff,
+        g: 0,
+        b: 0xff,
+        a: 0xff,
+    };
+    pub const GREY: Self = Self {
+        r: 0x80,
+        g: 0x80,
+        b: 0x80,
+        a: 0xff,
+    };
+    pub const LIGHT_GREY: Self = Self {
+        r: 0xd3,
+        g: 0xd3,
+        b: 0xd3,
+        a: 0xff,
+    };
+    /// Darker than CSS `darkgrey`, which is lighter than `grey`.
+    pub const DARK_GREY: Self = Self {
+        r: 0x40,
+        g: 0x40,
+        b: 0x40,
+        a: 0xff,
+    };
 
     pub const fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
         Self { r, g, b, a }
     }
+
+    /// Look up a CSS named color, eg. `"cornflowerblue"`, ignoring case.
+    /// Both the `gray` and `grey` spellings are supported, as well as
+    /// `transparent`.
+    ///
+    /// ```
+    /// use rgx::core::Rgba8;
+    ///
+    /// assert_eq!(Rgba8::from_name("cornflowerblue"), Some(Rgba8::new(0x64, 0x95, 0xed, 0xff)));
+    /// assert_eq!(Rgba8::from_name("DarkSlateGrey"), Rgba8::from_name("darkslategray"));
+    /// assert_eq!(Rgba8::from_name("magenta"), Some(Rgba8::MAGENTA));
+    /// assert_eq!(Rgba8::from_name("transparent"), Some(Rgba8::TRANSPARENT));
+    /// assert_eq!(Rgba8::from_name("notacolor"), None);
+    /// ```
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+
+        if name == "transparent" {
+            return Some(Self::TRANSPARENT);
+        }
+        names::NAMED_COLORS
+            .binary_search_by(|(n, _)| (*n).cmp(name.as_str()))
+            .ok()
+            .map(|i| {
+                let rgb = names::NAMED_COLORS[i].1;
+                Self::new((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8, 0xff)
+            })
+    }
+
+    /// Mix two colors, by interpolating their sRGB-encoded channels. This is
+    /// cheap, but darkens midpoints; see [`Rgba8::mix_linear`].
+    ///
+    /// ```
+    /// use rgx::core::Rgba8;
+    ///
+    /// assert_eq!(Rgba8::BLACK.mix(Rgba8::WHITE, 0.5), Rgba8::new(0x80, 0x80, 0x80, 0xff));
+    /// ```
+    pub fn mix(self, other: Self, t: f32) -> Self {
+        Rgba::from(self).lerp(Rgba::from(other), t).into()
+    }
+
+    /// Mix two colors in linear space, which preserves perceived brightness
+    /// better than [`Rgba8::mix`]. Alpha is interpolated as-is.
+    ///
+    /// ```
+    /// use rgx::core::Rgba8;
+    ///
+    /// assert_eq!(Rgba8::BLACK.mix_linear(Rgba8::WHITE, 0.5), Rgba8::new(0xbc, 0xbc, 0xbc, 0xff));
+    /// assert_eq!(Rgba8::RED.mix_linear(Rgba8::BLUE, 0.), Rgba8::RED);
+    /// ```
+    pub fn mix_linear(self, other: Self, t: f32) -> Self {
+        let (a, b) = (Rgba::from(self).to_linear(), Rgba::from(other).to_linear());
+        a.lerp(b, t).to_srgb().into()
+    }
 }
 
 impl fmt::Display for Rgba8 {
@@ -93,24 +196,81 @@ impl From<u32> for Rgba8 {
     }
 }
 
+/// An error returned when parsing a [`Rgba8`] color code fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorParseError {
+    /// The code doesn't have 3, 6 or 8 hex digits.
+    Length(usize),
+    /// The code contains a character which isn't a hex digit.
+    Digit(char),
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Length(n) => write!(
+                f,
+                "invalid color code length {}, expected 3, 6 or 8 hex digits",
+                n
+            ),
+            Self::Digit(c) => write!(f, "invalid hex digit '{}' in color code", c),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
 impl FromStr for Rgba8 {
-    type Err = std::num::ParseIntError;
+    type Err = ColorParseError;
 
-    /// Parse a color code of the form '#ffffff' into an
-    /// instance of 'Rgba8'. The alpha is always 0xff.
+    /// Parse a color code of the form `#rgb`, `#rrggbb` or `#rrggbbaa` into
+    /// an instance of `Rgba8`. The leading `#` is optional, and the alpha is
+    /// `0xff` unless specified.
+    ///
+    /// ```
+    /// use rgx::core::{ColorParseError, Rgba8};
+    ///
+    /// assert_eq!("#f80".parse(), Ok(Rgba8::new(0xff, 0x88, 0x00, 0xff)));
+    /// assert_eq!("ff8000".parse(), Ok(Rgba8::new(0xff, 0x80, 0x00, 0xff)));
+    /// assert_eq!("#ff800080".parse(), Ok(Rgba8::new(0xff, 0x80, 0x00, 0x80)));
+    ///
+    /// assert_eq!("#ff".parse::<Rgba8>(), Err(ColorParseError::Length(2)));
+    /// assert_eq!("#ff80zz".parse::<Rgba8>(), Err(ColorParseError::Digit('z')));
+    ///
+    /// let c = Rgba8::new(1, 2, 3, 4);
+    /// assert_eq!(c.to_string().parse(), Ok(c));
+    /// ```
     fn from_str(hex_code: &str) -> Result<Self, Self::Err> {
-        let r: u8 = u8::from_str_radix(&hex_code[1..3], 16)?;
-        let g: u8 = u8::from_str_radix(&hex_code[3..5], 16)?;
-        let b: u8 = u8::from_str_radix(&hex_code[5..7], 16)?;
-        let a: u8 = 0xff;
-
-        Ok(Rgba8 { r, g, b, a })
+        let code = hex_code.strip_prefix('#').unwrap_or(hex_code);
+        let digits = code
+            .chars()
+            .map(|c| {
+                c.to_digit(16)
+                    .map(|d| d as u8)
+                    .ok_or(ColorParseError::Digit(c))
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        match *digits.as_slice() {
+            [r, g, b] => Ok(Self::new(r * 0x11, g * 0x11, b * 0x11, 0xff)),
+            [r1, r0, g1, g0, b1, b0] => {
+                Ok(Self::new(r1 << 4 | r0, g1 << 4 | g0, b1 << 4 | b0, 0xff))
+            }
+            [r1, r0, g1, g0, b1, b0, a1, a0] => Ok(Self::new(
+                r1 << 4 | r0,
+                g1 << 4 | g0,
+                b1 << 4 | b0,
+                a1 << 4 | a0,
+            )),
+            _ => Err(ColorParseError::Length(digits.len())),
+        }
     }
 }
 
 /// A BGRA color, used when dealing with framebuffers.
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Bgra8 {
     pub b: u8,
     pub g: u8,
@@ -146,11 +306,120 @@ impl Into<Rgba8> for Bgra8 {
     }
 }
 
+/// A color with 16-bit floating point channels, stored as raw half-float
+/// bits, used when dealing with `Rgba16Float` textures. Channels aren't
+/// limited to `[0, 1]`, eg. for bloom.
+///
+/// ```
+/// use rgx::core::{Rgba, Rgba16F};
+///
+/// let hdr = Rgba::new(4., 0.5, 0.25, 1.);
+/// assert_eq!(Rgba::from(Rgba16F::from(hdr)), hdr);
+///
+/// // Values beyond the half-float range become infinite.
+/// assert_eq!(Rgba16F::new(1e6, 0., 0., 1.).r, 0x7c00);
+/// ```
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub struct Rgba16F {
+    pub r: u16,
+    pub g: u16,
+    pub b: u16,
+    pub a: u16,
+}
+
+impl Rgba16F {
+    pub fn new(r: f32, g: f32, b: f32, a: f32) -> Self {
+        Self {
+            r: f32_to_f16(r),
+            g: f32_to_f16(g),
+            b: f32_to_f16(b),
+            a: f32_to_f16(a),
+        }
+    }
+}
+
+impl From<Rgba> for Rgba16F {
+    fn from(rgba: Rgba) -> Self {
+        Self::new(rgba.r, rgba.g, rgba.b, rgba.a)
+    }
+}
+
+impl From<Rgba16F> for Rgba {
+    fn from(rgba: Rgba16F) -> Self {
+        Self {
+            r: f16_to_f32(rgba.r),
+            g: f16_to_f32(rgba.g),
+            b: f16_to_f32(rgba.b),
+            a: f16_to_f32(rgba.a),
+        }
+    }
+}
+
+/// Convert a float to half-float bits, rounding to nearest even.
+fn f32_to_f16(v: f32) -> u16 {
+    let bits = v.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xff) as i32;
+    let man = bits & 0x7f_ffff;
+
+    // Infinity and NaN.
+    if exp == 0xff {
+        return sign | 0x7c00 | if man != 0 { 0x200 } else { 0 };
+    }
+    let e = exp - 127 + 15;
+
+    let round = |half: u32, rem: u32, mid: u32| {
+        if rem > mid || (rem == mid && half & 1 == 1) {
+            half + 1
+        } else {
+            half
+        }
+    };
+
+    if e >= 0x1f {
+        sign | 0x7c00
+    } else if e <= 0 {
+        // Subnormal, or too small to be represented.
+        if e < -10 {
+            return sign;
+        }
+        let m = man | 0x80_0000;
+        let shift = (14 - e) as u32;
+
+        sign | round(m >> shift, m & ((1 << shift) - 1), 1 << (shift - 1)) as u16
+    } else {
+        // Rounding may carry into the exponent, which is still correct.
+        sign | round(((e as u32) << 10) | (man >> 13), man & 0x1fff, 0x1000) as u16
+    }
+}
+
+/// Convert half-float bits to a float.
+fn f16_to_f32(h: u16) -> f32 {
+    let sign = ((h & 0x8000) as u32) << 16;
+    let exp = ((h >> 10) & 0x1f) as u32;
+    let man = (h & 0x3ff) as u32;
+
+    match exp {
+        0 => {
+            let v = man as f32 / (1 << 24) as f32;
+            if sign != 0 {
+                -v
+            } else {
+                v
+            }
+        }
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (man << 13)),
+        _ => f32::from_bits(sign | ((exp + 112) << 23) | (man << 13)),
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 /// Rect
 ///////////////////////////////////////////////////////////////////////////////
 
 #[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rect<T> {
     pub x1: T,
     pub y1: T,
@@ -182,6 +451,24 @@ impl<T> Rect<T> {
         Self::new(T::zero(), T::zero(), w, h)
     }
 
+    /// Create a rectangle with its minimum corner at `p`, of the given size.
+    pub fn from_origin_size(p: Point2<T>, size: Size2D<T>) -> Self
+    where
+        T: std::ops::Add<Output = T> + Copy,
+    {
+        Self::new(p.x, p.y, p.x + size.width, p.y + size.height)
+    }
+
+    /// Return the size of the rectangle, whatever its orientation.
+    pub fn size(&self) -> Size2D<T>
+    where
+        T: Copy + PartialOrd + std::ops::Sub<Output = T>,
+    {
+        let extent = |a: T, b: T| if b < a { a - b } else { b - a };
+
+        Size2D::new(extent(self.x1, self.x2), extent(self.y1, self.y2))
+    }
+
     pub fn map<F, S>(self, f: F) -> Rect<S>
     where
         F: Fn(T) -> S,
@@ -365,6 +652,168 @@ impl<T> Rect<T> {
         self.y2 > other.y1 && self.y1 < other.y2 && self.x1 < other.x2 && self.x2 > other.x1
     }
 
+    /// Return the rectangle grown by `d` on every side. The orientation of
+    /// the rectangle is kept, so this also works with non-normalized
+    /// rectangles. Coordinates aren't clamped, so with unsigned coordinates,
+    /// growing a side past zero underflows, as with [`Rect::with_margin`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// assert_eq!(Rect::new(1, 1, 3, 3).expand(1), Rect::new(0, 0, 4, 4));
+    /// assert_eq!(Rect::new(3, 3, 1, 1).expand(1), Rect::new(4, 4, 0, 0));
+    /// ```
+    pub fn expand(&self, d: T) -> Self
+    where
+        T: PartialOrd + Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+    {
+        self.with_margin(d, d, d, d)
+    }
+
+    /// Return the rectangle shrunk by `d` on every side, eg. for padding.
+    /// Sides which would cross collapse to the center of the rectangle
+    /// instead. The orientation of the rectangle is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// assert_eq!(Rect::new(0, 0, 4, 8).inset(1), Rect::new(1, 1, 3, 7));
+    /// assert_eq!(Rect::new(4, 8, 0, 0).inset(1), Rect::new(3, 7, 1, 1));
+    /// assert_eq!(Rect::new(0, 0, 4, 8).inset(3), Rect::new(2, 3, 2, 5));
+    /// assert_eq!(Rect::new(0u32, 0, 4, 8).inset(1), Rect::new(1, 1, 3, 7));
+    /// ```
+    pub fn inset(&self, d: T) -> Self
+    where
+        T: PartialOrd
+            + Copy
+            + math::One
+            + std::ops::Add<Output = T>
+            + std::ops::Sub<Output = T>
+            + std::ops::Div<Output = T>,
+    {
+        let shrink = |a: T, b: T| {
+            let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+            let (lo, hi) = if hi - lo < d + d {
+                let mid = lo + (hi - lo) / (T::one() + T::one());
+                (mid, mid)
+            } else {
+                (lo + d, hi - d)
+            };
+            if a <= b {
+                (lo, hi)
+            } else {
+                (hi, lo)
+            }
+        };
+        let (x1, x2) = shrink(self.x1, self.x2);
+        let (y1, y2) = shrink(self.y1, self.y2);
+
+        Self::new(x1, y1, x2, y2)
+    }
+
+    /// Return the rectangle grown by a margin on each side, or shrunk by
+    /// negative margins. `left` and `top` apply to the sides with the
+    /// smaller coordinates, as in screen space, whatever the orientation
+    /// of the rectangle.
+    ///
+    /// Coordinates aren't clamped, so with unsigned coordinates, a margin
+    /// taking a side below zero underflows, which panics in debug builds.
+    /// Convert the rectangle to a signed type first if that can happen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::new(10, 10, 20, 20).with_margin(1, 2, 3, 4);
+    /// assert_eq!(r, Rect::new(9, 8, 23, 24));
+    ///
+    /// let r = Rect::new(20, 20, 10, 10).with_margin(1, 2, 3, 4);
+    /// assert_eq!(r, Rect::new(23, 24, 9, 8));
+    /// ```
+    pub fn with_margin(&self, left: T, top: T, right: T, bottom: T) -> Self
+    where
+        T: PartialOrd + Copy + std::ops::Add<Output = T> + std::ops::Sub<Output = T>,
+    {
+        let grow = |a: T, b: T, lo: T, hi: T| {
+            if a <= b {
+                (a - lo, b + hi)
+            } else {
+                (a + hi, b - lo)
+            }
+        };
+        let (x1, x2) = grow(self.x1, self.x2, left, right);
+        let (y1, y2) = grow(self.y1, self.y2, top, bottom);
+
+        Self::new(x1, y1, x2, y2)
+    }
+
+    /// Return the smallest rectangle containing both rectangles, which
+    /// should be normalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::new(0, 0, 2, 2).union(Rect::new(1, -1, 4, 1));
+    /// assert_eq!(r, Rect::new(0, -1, 4, 2));
+    ///
+    /// let r = Rect::new(0., 0., 1., 1.).union(Rect::new(3., 3., 4., 4.));
+    /// assert_eq!(r, Rect::new(0., 0., 4., 4.));
+    /// ```
+    pub fn union(&self, other: Self) -> Self
+    where
+        T: PartialOrd + Copy,
+    {
+        let min = |a: T, b: T| if b < a { b } else { a };
+        let max = |a: T, b: T| if b > a { b } else { a };
+
+        Self::new(
+            min(self.x1, other.x1),
+            min(self.y1, other.y1),
+            max(self.x2, other.x2),
+            max(self.y2, other.y2),
+        )
+    }
+
+    /// Return the overlap of two normalized rectangles, or `None` if they
+    /// don't intersect. Rectangles which only share an edge don't
+    /// intersect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::new(0, 0, 2, 2).intersection(Rect::new(1, -1, 4, 1));
+    /// assert_eq!(r, Some(Rect::new(1, 0, 2, 1)));
+    ///
+    /// let r = Rect::new(0., 0., 1., 1.).intersection(Rect::new(1., 0., 2., 1.));
+    /// assert_eq!(r, None);
+    /// ```
+    pub fn intersection(&self, other: Self) -> Option<Self>
+    where
+        T: PartialOrd + Copy,
+    {
+        if !self.intersects(other) {
+            return None;
+        }
+        let min = |a: T, b: T| if b < a { b } else { a };
+        let max = |a: T, b: T| if b > a { b } else { a };
+
+        Some(Self::new(
+            max(self.x1, other.x1),
+            max(self.y1, other.y1),
+            min(self.x2, other.x2),
+            min(self.y2, other.y2),
+        ))
+    }
+
     /// Return the normalized rectangle.
     ///
     /// # Examples
@@ -419,71 +868,250 @@ impl<T> Rect<T> {
             T::min(self.y2, bounds.y2),
         )
     }
-}
-
-impl<T> std::ops::Add<Vector2<T>> for Rect<T>
-where
-    T: std::ops::Add<Output = T> + Copy,
-{
-    type Output = Self;
 
-    fn add(self, vec: Vector2<T>) -> Self {
-        Self {
-            x1: self.x1 + vec.x,
-            y1: self.y1 + vec.y,
-            x2: self.x2 + vec.x,
-            y2: self.y2 + vec.y,
-        }
+    /// Cast the rectangle to another numeric type. Returns `None` if any
+    /// of the coordinates can't be represented in the target type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::new(1.5f32, 2., 3., 4.).cast::<i32>();
+    /// assert_eq!(r, Some(Rect::new(1, 2, 3, 4)));
+    ///
+    /// let r = Rect::new(-1, 0, 1, 1).cast::<u32>();
+    /// assert_eq!(r, None);
+    /// ```
+    pub fn cast<S>(self) -> Option<Rect<S>>
+    where
+        T: num_traits::NumCast,
+        S: num_traits::NumCast,
+    {
+        Some(Rect {
+            x1: S::from(self.x1)?,
+            y1: S::from(self.y1)?,
+            x2: S::from(self.x2)?,
+            y2: S::from(self.y2)?,
+        })
     }
 }
 
-impl<T> std::ops::AddAssign<Vector2<T>> for Rect<T>
-where
-    T: std::ops::AddAssign<T> + Copy,
-{
-    fn add_assign(&mut self, vec: Vector2<T>) {
-        self.x1 += vec.x;
-        self.y1 += vec.y;
-        self.x2 += vec.x;
-        self.y2 += vec.y;
+impl Rect<u32> {
+    /// Convert the rectangle to floating point coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::<u32>::origin(8, 16).into_f32();
+    /// assert_eq!(r, Rect::new(0., 0., 8., 16.));
+    /// ```
+    pub fn into_f32(self) -> Rect<f32> {
+        self.into()
     }
-}
 
-impl<T> std::ops::Sub<Vector2<T>> for Rect<T>
-where
-    T: std::ops::Sub<Output = T> + Copy,
-{
-    type Output = Self;
+    /// Iterate over the pixels covered by the rectangle, row by row. The
+    /// maximum edges are exclusive, so an `8x8` rectangle at the origin
+    /// covers pixels `0` through `7` on each axis.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    /// use rgx::math::Point2;
+    ///
+    /// let pixels: Vec<_> = Rect::new(3u32, 1, 1, 2).pixels().collect();
+    /// assert_eq!(pixels, vec![Point2::new(1, 1), Point2::new(2, 1)]);
+    /// ```
+    pub fn pixels(&self) -> impl Iterator<Item = Point2<u32>> {
+        let r = self.normalized();
 
-    fn sub(self, vec: Vector2<T>) -> Self {
-        Self {
-            x1: self.x1 - vec.x,
-            y1: self.y1 - vec.y,
-            x2: self.x2 - vec.x,
-            y2: self.y2 - vec.y,
-        }
+        (r.y1..r.y2).flat_map(move |y| (r.x1..r.x2).map(move |x| Point2::new(x, y)))
     }
 }
 
-impl<T> std::ops::SubAssign<Vector2<T>> for Rect<T>
-where
-    T: std::ops::SubAssign<T> + Copy,
-{
-    fn sub_assign(&mut self, vec: Vector2<T>) {
-        self.x1 -= vec.x;
-        self.y1 -= vec.y;
-        self.x2 -= vec.x;
-        self.y2 -= vec.y;
+impl Rect<i32> {
+    /// Convert the rectangle to floating point coordinates.
+    pub fn into_f32(self) -> Rect<f32> {
+        self.into()
     }
-}
-
-impl<T> std::ops::Mul<T> for Rect<T>
-where
-    T: std::ops::Mul<Output = T> + Copy,
-{
-    type Output = Self;
 
-    fn mul(self, s: T) -> Self {
+    /// Try to convert the rectangle to unsigned coordinates. Fails if any
+    /// of the coordinates are negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// assert_eq!(Rect::new(0, 0, 4, 4).try_into_u32(), Ok(Rect::new(0, 0, 4, 4)));
+    /// assert!(Rect::new(-1, 0, 4, 4).try_into_u32().is_err());
+    /// ```
+    pub fn try_into_u32(self) -> Result<Rect<u32>, std::num::TryFromIntError> {
+        Rect::try_from(self)
+    }
+
+    /// Iterate over the integer points covered by the rectangle, row by
+    /// row. The maximum edges are exclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    /// use rgx::math::Point2;
+    ///
+    /// let r = Rect::new(-1, -1, 1, 1);
+    /// let points: Vec<_> = r.points().collect();
+    ///
+    /// assert_eq!(points.len(), 4);
+    /// assert_eq!(points[0], Point2::new(-1, -1));
+    /// assert_eq!(points[1], Point2::new(0, -1));
+    /// assert_eq!(points[3], Point2::new(0, 0));
+    /// ```
+    pub fn points(&self) -> impl Iterator<Item = Point2<i32>> {
+        let r = self.normalized();
+
+        (r.y1..r.y2).flat_map(move |y| (r.x1..r.x2).map(move |x| Point2::new(x, y)))
+    }
+}
+
+impl Rect<f32> {
+    /// Round each coordinate to the nearest integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::new(0.4, 0.5, 7.6, -2.5);
+    ///
+    /// assert_eq!(r.round(), Rect::new(0, 1, 8, -3));
+    /// assert_eq!(r.floor(), Rect::new(0, 0, 7, -3));
+    /// assert_eq!(r.ceil(), Rect::new(1, 1, 8, -2));
+    /// ```
+    pub fn round(&self) -> Rect<i32> {
+        self.map(|n| n.round() as i32)
+    }
+
+    /// Round each coordinate down.
+    pub fn floor(&self) -> Rect<i32> {
+        self.map(|n| n.floor() as i32)
+    }
+
+    /// Round each coordinate up.
+    pub fn ceil(&self) -> Rect<i32> {
+        self.map(|n| n.ceil() as i32)
+    }
+
+    /// Snap each coordinate to the nearest physical pixel boundary, for
+    /// logical coordinates at the given `scale` factor, eg. for blits and
+    /// scissor rectangles.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    ///
+    /// let r = Rect::new(10.3, 4.1, 20.2, 8.9);
+    ///
+    /// assert_eq!(r.snap_to_pixel(1.0), Rect::new(10., 4., 20., 9.));
+    /// assert_eq!(r.snap_to_pixel(2.0), Rect::new(10.5, 4., 20., 9.));
+    /// ```
+    pub fn snap_to_pixel(&self, scale: f64) -> Self {
+        self.map(|n| ((n as f64 * scale).round() / scale) as f32)
+    }
+}
+
+impl From<Rect<u32>> for Rect<f32> {
+    fn from(r: Rect<u32>) -> Self {
+        r.map(|n| n as f32)
+    }
+}
+
+impl From<Rect<i32>> for Rect<f32> {
+    fn from(r: Rect<i32>) -> Self {
+        r.map(|n| n as f32)
+    }
+}
+
+impl TryFrom<Rect<i32>> for Rect<u32> {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(r: Rect<i32>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            x1: u32::try_from(r.x1)?,
+            y1: u32::try_from(r.y1)?,
+            x2: u32::try_from(r.x2)?,
+            y2: u32::try_from(r.y2)?,
+        })
+    }
+}
+
+impl<T> std::ops::Add<Vector2<T>> for Rect<T>
+where
+    T: std::ops::Add<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn add(self, vec: Vector2<T>) -> Self {
+        Self {
+            x1: self.x1 + vec.x,
+            y1: self.y1 + vec.y,
+            x2: self.x2 + vec.x,
+            y2: self.y2 + vec.y,
+        }
+    }
+}
+
+impl<T> std::ops::AddAssign<Vector2<T>> for Rect<T>
+where
+    T: std::ops::AddAssign<T> + Copy,
+{
+    fn add_assign(&mut self, vec: Vector2<T>) {
+        self.x1 += vec.x;
+        self.y1 += vec.y;
+        self.x2 += vec.x;
+        self.y2 += vec.y;
+    }
+}
+
+impl<T> std::ops::Sub<Vector2<T>> for Rect<T>
+where
+    T: std::ops::Sub<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn sub(self, vec: Vector2<T>) -> Self {
+        Self {
+            x1: self.x1 - vec.x,
+            y1: self.y1 - vec.y,
+            x2: self.x2 - vec.x,
+            y2: self.y2 - vec.y,
+        }
+    }
+}
+
+impl<T> std::ops::SubAssign<Vector2<T>> for Rect<T>
+where
+    T: std::ops::SubAssign<T> + Copy,
+{
+    fn sub_assign(&mut self, vec: Vector2<T>) {
+        self.x1 -= vec.x;
+        self.y1 -= vec.y;
+        self.x2 -= vec.x;
+        self.y2 -= vec.y;
+    }
+}
+
+impl<T> std::ops::Mul<T> for Rect<T>
+where
+    T: std::ops::Mul<Output = T> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, s: T) -> Self {
         Self {
             x1: self.x1 * s,
             y1: self.y1 * s,
@@ -493,6 +1121,73 @@ where
     }
 }
 
+/// A width and height, eg. of a texture or render target. Passing sizes
+/// rather than separate `w` and `h` arguments keeps them from being
+/// transposed.
+///
+/// # Examples
+///
+/// ```
+/// use rgx::core::{Rect, Size2D};
+/// use rgx::math::Point2;
+///
+/// let r = Rect::from_origin_size(Point2::new(2, 4), Size2D::new(8, 6));
+///
+/// assert_eq!(r, Rect::new(2, 4, 10, 10));
+/// assert_eq!(r.size(), Size2D::new(8, 6));
+/// assert_eq!(Rect::new(10u32, 10, 2, 4).size(), Size2D::new(8, 6));
+/// assert_eq!(Size2D::from((8, 6)).area(), 48);
+/// ```
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Size2D<T> {
+    pub width: T,
+    pub height: T,
+}
+
+impl<T> Size2D<T> {
+    pub const fn new(width: T, height: T) -> Self {
+        Self { width, height }
+    }
+
+    pub fn area(&self) -> T
+    where
+        T: std::ops::Mul<Output = T> + Copy,
+    {
+        self.width * self.height
+    }
+
+    /// Whether either dimension is zero.
+    pub fn is_empty(&self) -> bool
+    where
+        T: math::Zero,
+    {
+        self.width.is_zero() || self.height.is_zero()
+    }
+
+    pub fn map<F, S>(self, f: F) -> Size2D<S>
+    where
+        F: Fn(T) -> S,
+    {
+        Size2D {
+            width: f(self.width),
+            height: f(self.height),
+        }
+    }
+}
+
+impl<T> From<(T, T)> for Size2D<T> {
+    fn from((width, height): (T, T)) -> Self {
+        Self { width, height }
+    }
+}
+
+impl<T> From<Size2D<T>> for (T, T) {
+    fn from(s: Size2D<T>) -> Self {
+        (s.width, s.height)
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 /// Draw
 ///////////////////////////////////////////////////////////////////////////////
@@ -507,6 +1202,7 @@ pub trait Draw {
 
 #[repr(C)]
 #[derive(Copy, Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Rgba {
     pub r: f32,
     pub g: f32,
@@ -538,6 +1234,160 @@ impl Rgba {
         Self { r, g, b, a }
     }
 
+    /// Return the color with its channels multiplied by its alpha, eg. to
+    /// clear targets with premultiplied alpha.
+    ///
+    /// ```
+    /// use rgx::core::Rgba;
+    ///
+    /// assert_eq!(Rgba::new(1.0, 0.5, 0.0, 0.5).premultiply(), Rgba::new(0.5, 0.25, 0.0, 0.5));
+    /// ```
+    pub fn premultiply(self) -> Self {
+        Self::new(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    /// Linearly interpolate between two colors, channel by channel. `t` is
+    /// not clamped.
+    ///
+    /// ```
+    /// use rgx::core::Rgba;
+    ///
+    /// let c = Rgba::new(0.0, 0.5, 1.0, 1.0).lerp(Rgba::new(1.0, 0.5, 0.0, 0.0), 0.25);
+    /// assert_eq!(c, Rgba::new(0.25, 0.5, 0.75, 0.75));
+    /// ```
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Self::new(
+            self.r + (other.r - self.r) * t,
+            self.g + (other.g - self.g) * t,
+            self.b + (other.b - self.b) * t,
+            self.a + (other.a - self.a) * t,
+        )
+    }
+
+    /// Composite the color over `dst`, with the "source-over" operator, eg.
+    /// for CPU-side previews. Neither color is premultiplied.
+    ///
+    /// ```
+    /// use rgx::core::Rgba;
+    ///
+    /// let red = Rgba::new(1.0, 0.0, 0.0, 0.5);
+    /// assert_eq!(red.blend_over(Rgba::WHITE), Rgba::new(1.0, 0.5, 0.5, 1.0));
+    /// assert_eq!(red.blend_over(Rgba::TRANSPARENT), red);
+    /// ```
+    pub fn blend_over(self, dst: Self) -> Self {
+        let a = self.a + dst.a * (1. - self.a);
+
+        if a <= 0. {
+            return Self::TRANSPARENT;
+        }
+        let channel = |s: f32, d: f32| (s * self.a + d * dst.a * (1. - self.a)) / a;
+
+        Self::new(
+            channel(self.r, dst.r),
+            channel(self.g, dst.g),
+            channel(self.b, dst.b),
+            a,
+        )
+    }
+
+    /// Decode the sRGB-encoded color channels to linear space. Alpha is
+    /// unchanged.
+    ///
+    /// ```
+    /// use rgx::core::Rgba;
+    ///
+    /// let c = Rgba::new(0.5, 0.0, 1.0, 0.5).to_linear();
+    /// assert!((c.r - 0.214).abs() < 1e-3);
+    /// assert_eq!((c.g, c.b, c.a), (0.0, 1.0, 0.5));
+    /// assert!((c.to_srgb().r - 0.5).abs() < 1e-6);
+    /// ```
+    pub fn to_linear(self) -> Self {
+        Self::new(
+            srgb_to_linear(self.r),
+            srgb_to_linear(self.g),
+            srgb_to_linear(self.b),
+            self.a,
+        )
+    }
+
+    /// Encode linear color channels with the sRGB transfer function. Alpha
+    /// is unchanged.
+    pub fn to_srgb(self) -> Self {
+        Self::new(
+            linear_to_srgb(self.r),
+            linear_to_srgb(self.g),
+            linear_to_srgb(self.b),
+            self.a,
+        )
+    }
+
+    /// The luminance of the color, using the Rec. 709 channel weights.
+    /// This is the relative luminance for linear colors; sRGB-encoded colors
+    /// should be converted with [`Rgba::to_linear`] first for accuracy.
+    ///
+    /// ```
+    /// use rgx::core::Rgba;
+    ///
+    /// assert_eq!(Rgba::WHITE.luminance(), 1.0);
+    /// assert!(Rgba::new(0., 1., 0., 1.).luminance() > Rgba::new(1., 0., 0., 1.).luminance());
+    /// ```
+    pub fn luminance(&self) -> f32 {
+        0.2126 * self.r + 0.7152 * self.g + 0.0722 * self.b
+    }
+
+    /// A gray with the color's luminance and alpha.
+    ///
+    /// ```
+    /// use rgx::core::Rgba;
+    ///
+    /// let gray = Rgba::new(0.2, 0.4, 0.6, 0.5).to_grayscale();
+    /// assert_eq!((gray.r, gray.a), (gray.b, 0.5));
+    /// ```
+    pub fn to_grayscale(self) -> Self {
+        let l = self.luminance();
+        Self::new(l, l, l, self.a)
+    }
+
+    /// The largest and smallest of the color channels.
+    fn extrema(&self) -> (f32, f32) {
+        (
+            self.r.max(self.g).max(self.b),
+            self.r.min(self.g).min(self.b),
+        )
+    }
+
+    /// The hue of the color in degrees, given its channel extrema.
+    fn hue(&self, max: f32, min: f32) -> f32 {
+        let d = max - min;
+
+        if d <= 0. {
+            0.
+        } else if max == self.r {
+            60. * ((self.g - self.b) / d).rem_euclid(6.)
+        } else if max == self.g {
+            60. * ((self.b - self.r) / d + 2.)
+        } else {
+            60. * ((self.r - self.g) / d + 4.)
+        }
+    }
+
+    /// A color from a hue in degrees, a chroma `c` and a lightness offset
+    /// `m` added to every channel.
+    fn from_hue(h: f32, c: f32, m: f32, a: f32) -> Self {
+        let h = h.rem_euclid(360.) / 60.;
+        let x = c * (1. - (h % 2. - 1.).abs());
+
+        let (r, g, b) = match h as u32 {
+            0 => (c, x, 0.),
+            1 => (x, c, 0.),
+            2 => (0., c, x),
+            3 => (0., x, c),
+            4 => (x, 0., c),
+            _ => (c, 0., x),
+        };
+        Self::new(r + m, g + m, b + m, a)
+    }
+
     fn to_wgpu(&self) -> wgpu::Color {
         wgpu::Color {
             r: self.r as f64,
@@ -559,158 +1409,484 @@ impl From<Rgba8> for Rgba {
     }
 }
 
-///////////////////////////////////////////////////////////////////////////////
-/// Shaders
-///////////////////////////////////////////////////////////////////////////////
+/// Channel-wise addition, including alpha. Channels aren't clamped.
+///
+/// ```
+/// use rgx::core::Rgba;
+///
+/// let c = Rgba::new(0.25, 0.5, 0.5, 0.5) + Rgba::new(0.25, 0.75, 0.0, 0.5);
+/// assert_eq!(c, Rgba::new(0.5, 1.25, 0.5, 1.0));
+/// ```
+impl Add for Rgba {
+    type Output = Self;
 
-pub struct Shader {
-    module: wgpu::ShaderModule,
+    fn add(self, other: Self) -> Self {
+        Self::new(
+            self.r + other.r,
+            self.g + other.g,
+            self.b + other.b,
+            self.a + other.a,
+        )
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum ShaderStage {
-    Vertex,
-    Fragment,
-    Compute,
+/// Scale every channel, including alpha, eg. to weigh colors before adding
+/// them.
+///
+/// ```
+/// use rgx::core::Rgba;
+///
+/// assert_eq!(Rgba::WHITE * 0.5, Rgba::new(0.5, 0.5, 0.5, 0.5));
+/// ```
+impl Mul<f32> for Rgba {
+    type Output = Self;
+
+    fn mul(self, s: f32) -> Self {
+        Self::new(self.r * s, self.g * s, self.b * s, self.a * s)
+    }
 }
 
-impl ShaderStage {
-    fn to_wgpu(&self) -> wgpu::ShaderStage {
-        match self {
-            ShaderStage::Vertex => wgpu::ShaderStage::VERTEX,
-            ShaderStage::Fragment => wgpu::ShaderStage::FRAGMENT,
-            ShaderStage::Compute => wgpu::ShaderStage::COMPUTE,
-        }
+/// Channel-wise multiplication, eg. to tint a color.
+///
+/// ```
+/// use rgx::core::Rgba;
+///
+/// let tint = Rgba::new(1.0, 0.5, 0.0, 1.0);
+/// assert_eq!(Rgba::WHITE * tint, tint);
+/// ```
+impl Mul for Rgba {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        Self::new(
+            self.r * other.r,
+            self.g * other.g,
+            self.b * other.b,
+            self.a * other.a,
+        )
+    }
+}
+
+/// Decode an sRGB-encoded channel to linear space.
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// Encode a linear channel with the sRGB transfer function.
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1. / 2.4) - 0.055
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Canvas
+/// Hsv & Hsl
 ///////////////////////////////////////////////////////////////////////////////
 
-pub trait Canvas {
-    fn clear(&self, color: Rgba, device: &mut Device, encoder: &mut wgpu::CommandEncoder);
-    fn fill(&self, buf: &[u8], device: &mut Device, encoder: &mut wgpu::CommandEncoder);
-    fn transfer(
-        &self,
-        buf: &[u8],
-        w: u32,
-        h: u32,
-        tw: u32,
-        th: u32,
-        device: &mut Device,
-        encoder: &mut wgpu::CommandEncoder,
-    );
-    fn blit(&self, from: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder);
+/// A color in the HSV (hue, saturation, value) model, eg. for color pickers.
+///
+/// The hue is in degrees, from `0` to `360`. Saturation, value and alpha
+/// range from `0` to `1`. Conversions from [`Rgba8`] round-trip exactly.
+///
+/// ```
+/// use rgx::core::{Hsv, Rgba8};
+///
+/// let hsv = Hsv::from(Rgba8::new(0xff, 0x80, 0x00, 0xff));
+/// assert_eq!(hsv.h.round(), 30.);
+///
+/// for c in (0..=255).step_by(15) {
+///     let rgba = Rgba8::new(c as u8, 255 - c as u8, (c * 7 % 256) as u8, 0x80);
+///     assert_eq!(Rgba8::from(Hsv::from(rgba)), rgba);
+/// }
+///
+/// let blue = Hsv::from(Rgba8::RED).hue_shift(240.);
+/// assert_eq!(Rgba8::from(blue), Rgba8::BLUE);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+    pub a: f32,
 }
 
-///////////////////////////////////////////////////////////////////////////////
-/// BindingGroup
-///////////////////////////////////////////////////////////////////////////////
+impl Hsv {
+    pub const fn new(h: f32, s: f32, v: f32, a: f32) -> Self {
+        Self { h, s, v, a }
+    }
 
-/// A group of bindings.
-pub struct BindingGroup {
-    wgpu: wgpu::BindGroup,
-    set_index: u32,
+    /// Rotate the hue by `degrees`, wrapping around.
+    pub fn hue_shift(self, degrees: f32) -> Self {
+        Self {
+            h: (self.h + degrees).rem_euclid(360.),
+            ..self
+        }
+    }
+
+    /// Add `amount` to the saturation, clamping it. Negative amounts
+    /// desaturate.
+    pub fn saturate(self, amount: f32) -> Self {
+        Self {
+            s: (self.s + amount).clamp(0., 1.),
+            ..self
+        }
+    }
+
+    /// Add `amount` to the value, clamping it. Negative amounts darken.
+    pub fn lighten(self, amount: f32) -> Self {
+        Self {
+            v: (self.v + amount).clamp(0., 1.),
+            ..self
+        }
+    }
 }
 
-impl BindingGroup {
-    fn new(set_index: u32, wgpu: wgpu::BindGroup) -> Self {
-        Self { set_index, wgpu }
+impl From<Rgba> for Hsv {
+    fn from(rgba: Rgba) -> Self {
+        let (max, min) = rgba.extrema();
+        let s = if max > 0. { (max - min) / max } else { 0. };
+
+        Self::new(rgba.hue(max, min), s, max, rgba.a)
     }
 }
 
-/// The layout of a 'BindingGroup'.
-pub struct BindingGroupLayout {
-    wgpu: wgpu::BindGroupLayout,
-    size: usize,
-    set_index: u32,
+impl From<Hsv> for Rgba {
+    fn from(hsv: Hsv) -> Self {
+        let c = hsv.v * hsv.s;
+        Self::from_hue(hsv.h, c, hsv.v - c, hsv.a)
+    }
 }
 
-impl BindingGroupLayout {
-    fn new(set_index: u32, layout: wgpu::BindGroupLayout, size: usize) -> Self {
+impl From<Rgba8> for Hsv {
+    fn from(rgba8: Rgba8) -> Self {
+        Rgba::from(rgba8).into()
+    }
+}
+
+impl From<Hsv> for Rgba8 {
+    fn from(hsv: Hsv) -> Self {
+        Rgba::from(hsv).into()
+    }
+}
+
+/// A color in the HSL (hue, saturation, lightness) model.
+///
+/// The hue is in degrees, from `0` to `360`. Saturation, lightness and alpha
+/// range from `0` to `1`. Conversions from [`Rgba8`] round-trip exactly.
+///
+/// ```
+/// use rgx::core::{Hsl, Rgba8};
+///
+/// let hsl = Hsl::from(Rgba8::new(0x80, 0x80, 0xff, 0xff));
+/// assert_eq!(hsl.h, 240.);
+/// assert!((hsl.s - 1.).abs() < 1e-6);
+///
+/// for c in (0..=255).step_by(15) {
+///     let rgba = Rgba8::new((c * 3 % 256) as u8, c as u8, 255 - c as u8, 0xff);
+///     assert_eq!(Rgba8::from(Hsl::from(rgba)), rgba);
+/// }
+///
+/// assert_eq!(Rgba8::from(Hsl::from(Rgba8::RED).lighten(0.5)), Rgba8::WHITE);
+/// ```
+#[derive(Copy, Clone, PartialEq, Debug)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+    pub a: f32,
+}
+
+impl Hsl {
+    pub const fn new(h: f32, s: f32, l: f32, a: f32) -> Self {
+        Self { h, s, l, a }
+    }
+
+    /// Rotate the hue by `degrees`, wrapping around.
+    pub fn hue_shift(self, degrees: f32) -> Self {
         Self {
-            wgpu: layout,
-            size,
-            set_index,
+            h: (self.h + degrees).rem_euclid(360.),
+            ..self
+        }
+    }
+
+    /// Add `amount` to the saturation, clamping it. Negative amounts
+    /// desaturate.
+    pub fn saturate(self, amount: f32) -> Self {
+        Self {
+            s: (self.s + amount).clamp(0., 1.),
+            ..self
+        }
+    }
+
+    /// Add `amount` to the lightness, clamping it. Negative amounts darken.
+    pub fn lighten(self, amount: f32) -> Self {
+        Self {
+            l: (self.l + amount).clamp(0., 1.),
+            ..self
         }
     }
 }
 
-/// A trait representing a resource that can be bound.
-pub trait Bind {
-    fn binding(&self, index: u32) -> wgpu::Binding;
+impl From<Rgba> for Hsl {
+    fn from(rgba: Rgba) -> Self {
+        let (max, min) = rgba.extrema();
+        let l = (max + min) / 2.;
+        let s = if max > min {
+            (max - min) / (1. - (2. * l - 1.).abs())
+        } else {
+            0.
+        };
+
+        Self::new(rgba.hue(max, min), s, l, rgba.a)
+    }
 }
 
-///////////////////////////////////////////////////////////////////////////////
-/// Uniforms
-///////////////////////////////////////////////////////////////////////////////
+impl From<Hsl> for Rgba {
+    fn from(hsl: Hsl) -> Self {
+        let c = (1. - (2. * hsl.l - 1.).abs()) * hsl.s;
+        Self::from_hue(hsl.h, c, hsl.l - c / 2., hsl.a)
+    }
+}
 
-/// A uniform buffer that can be bound in a 'BindingGroup'.
-pub struct UniformBuffer {
-    wgpu: wgpu::Buffer,
-    size: usize,
-    count: usize,
+impl From<Rgba8> for Hsl {
+    fn from(rgba8: Rgba8) -> Self {
+        Rgba::from(rgba8).into()
+    }
 }
 
-impl Bind for UniformBuffer {
-    fn binding(&self, index: u32) -> wgpu::Binding {
-        wgpu::Binding {
-            binding: index as u32,
-            resource: wgpu::BindingResource::Buffer {
-                buffer: &self.wgpu,
-                range: 0..(self.size as wgpu::BufferAddress),
-            },
-        }
+impl From<Hsl> for Rgba8 {
+    fn from(hsl: Hsl) -> Self {
+        Rgba::from(hsl).into()
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Framebuffer
+/// Shaders
 ///////////////////////////////////////////////////////////////////////////////
 
-#[allow(dead_code)]
-pub struct Framebuffer {
-    pub texture: Texture,
+pub struct Shader {
+    module: wgpu::ShaderModule,
+    bindings: Vec<ShaderBinding>,
 }
 
-impl Framebuffer {
-    pub fn size(&self) -> usize {
-        (self.texture.w * self.texture.h) as usize
+/// A resource binding declared in a shader, eg.
+/// `layout(set = 2, binding = 0) uniform texture2D tex;`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShaderBinding {
+    /// The debug name of the resource, if the module wasn't stripped of it.
+    pub name: Option<String>,
+    pub set: u32,
+    pub binding: u32,
+}
+
+impl ShaderBinding {
+    const OP_NAME: u32 = 5;
+    const OP_DECORATE: u32 = 71;
+    const DECORATION_BINDING: u32 = 33;
+    const DECORATION_DESCRIPTOR_SET: u32 = 34;
+
+    /// Extract the bindings from a SPIR-V module, from their set and binding
+    /// decorations. Resources without a debug name, such as uniform blocks
+    /// declared without an instance name, or any resource of a stripped
+    /// module, have no name.
+    fn reflect(spv: &[u32]) -> Vec<Self> {
+        use std::collections::BTreeMap;
+
+        let mut names: BTreeMap<u32, String> = BTreeMap::new();
+        let mut sets: BTreeMap<u32, u32> = BTreeMap::new();
+        let mut bindings: BTreeMap<u32, u32> = BTreeMap::new();
+
+        // Skip the five word header.
+        let mut words = spv.get(5..).unwrap_or(&[]);
+
+        while let Some(&head) = words.first() {
+            let (count, opcode) = ((head >> 16) as usize, head & 0xffff);
+            if count == 0 || count > words.len() {
+                break;
+            }
+            let operands = &words[1..count];
+
+            match (opcode, operands) {
+                (Self::OP_NAME, [target, name @ ..]) => {
+                    let bytes: Vec<u8> = name
+                        .iter()
+                        .flat_map(|w| w.to_le_bytes().to_vec())
+                        .take_while(|b| *b != 0)
+                        .collect();
+                    names.insert(*target, String::from_utf8_lossy(&bytes).into_owned());
+                }
+                (Self::OP_DECORATE, [target, Self::DECORATION_BINDING, value]) => {
+                    bindings.insert(*target, *value);
+                }
+                (Self::OP_DECORATE, [target, Self::DECORATION_DESCRIPTOR_SET, value]) => {
+                    sets.insert(*target, *value);
+                }
+                _ => {}
+            }
+            words = &words[count..];
+        }
+
+        bindings
+            .iter()
+            .map(|(id, binding)| Self {
+                name: names.get(id).filter(|n| !n.is_empty()).cloned(),
+                set: sets.get(id).cloned().unwrap_or(0),
+                binding: *binding,
+            })
+            .collect()
     }
+}
 
-    pub fn width(&self) -> u32 {
-        self.texture.w
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ShaderStage {
+    Vertex,
+    Fragment,
+    Compute,
+}
+
+impl ShaderStage {
+    fn to_wgpu(self) -> wgpu::ShaderStage {
+        match self {
+            Self::Vertex => wgpu::ShaderStage::VERTEX,
+            Self::Fragment => wgpu::ShaderStage::FRAGMENT,
+            Self::Compute => wgpu::ShaderStage::COMPUTE,
+        }
     }
+}
 
-    pub fn height(&self) -> u32 {
-        self.texture.h
+impl ShaderStage {
+    /// The stage of a GLSL shader, from its file extension: `.vert`, `.frag`
+    /// or `.comp`.
+    ///
+    /// ```
+    /// use rgx::core::ShaderStage;
+    ///
+    /// assert_eq!(ShaderStage::from_path("data/sprite.frag"), Some(ShaderStage::Fragment));
+    /// assert_eq!(ShaderStage::from_path("data/sprite.glsl"), None);
+    /// ```
+    pub const fn from_path(path: &str) -> Option<Self> {
+        let b = path.as_bytes();
+        let n = b.len();
+
+        if n < 5 || b[n - 5] != b'.' {
+            return None;
+        }
+        match [b[n - 4], b[n - 3], b[n - 2], b[n - 1]] {
+            [b'v', b'e', b'r', b't'] => Some(Self::Vertex),
+            [b'f', b'r', b'a', b'g'] => Some(Self::Fragment),
+            [b'c', b'o', b'm', b'p'] => Some(Self::Compute),
+            _ => None,
+        }
+    }
+
+    /// The SPIR-V execution model of entry points for this stage.
+    const fn execution_model(self) -> u32 {
+        match self {
+            Self::Vertex => 0,
+            Self::Fragment => 4,
+            Self::Compute => 5,
+        }
     }
 }
 
-impl Bind for Framebuffer {
-    fn binding(&self, index: u32) -> wgpu::Binding {
-        wgpu::Binding {
-            binding: index as u32,
-            resource: wgpu::BindingResource::TextureView(&self.texture.view),
+/// An error returned by [`validate_spirv`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SpirvError {
+    /// The module doesn't start with the SPIR-V magic number.
+    Magic,
+    /// The module size isn't a multiple of four bytes.
+    Length,
+    /// An instruction extends past the end of the module.
+    Truncated,
+    /// The module has no entry point for the expected stage.
+    Stage,
+}
+
+impl fmt::Display for SpirvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Magic => write!(f, "not a SPIR-V module"),
+            Self::Length => write!(f, "module size is not a whole number of words"),
+            Self::Truncated => write!(f, "module is truncated"),
+            Self::Stage => write!(f, "module has no entry point for this shader stage"),
         }
     }
 }
 
-impl Canvas for Framebuffer {
-    fn clear(&self, color: Rgba, device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
-        Texture::clear(
-            &self.texture,
-            Bgra8::from(Rgba8::from(color)),
-            device,
-            encoder,
-        );
+impl std::error::Error for SpirvError {}
+
+/// Check that `bytes` is a well-formed SPIR-V module with an entry point for
+/// the given stage. This is a `const fn`, so that embedded shaders can be
+/// checked at compile time, see [`include_shader`].
+///
+/// ```
+/// use rgx::core::{validate_spirv, ShaderStage, SpirvError};
+///
+/// assert_eq!(validate_spirv(&[0, 1, 2], ShaderStage::Vertex), Err(SpirvError::Length));
+/// assert_eq!(validate_spirv(&[0; 20], ShaderStage::Vertex), Err(SpirvError::Magic));
+/// ```
+///
+/// [`include_shader`]: crate::include_shader
+pub const fn validate_spirv(bytes: &[u8], stage: ShaderStage) -> Result<(), SpirvError> {
+    const MAGIC: u32 = 0x0723_0203;
+    const HEADER_WORDS: usize = 5;
+    const OP_ENTRY_POINT: u32 = 15;
+
+    const fn word(bytes: &[u8], i: usize) -> u32 {
+        u32::from_le_bytes([
+            bytes[i * 4],
+            bytes[i * 4 + 1],
+            bytes[i * 4 + 2],
+            bytes[i * 4 + 3],
+        ])
     }
 
-    fn fill(&self, buf: &[u8], device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
-        Texture::fill(&self.texture, buf, device, encoder);
+    if !bytes.len().is_multiple_of(4) {
+        return Err(SpirvError::Length);
+    }
+    let words = bytes.len() / 4;
+
+    if words < HEADER_WORDS || word(bytes, 0) != MAGIC {
+        return Err(SpirvError::Magic);
+    }
+
+    let mut i = HEADER_WORDS;
+    let mut found = false;
+
+    while i < words {
+        let w = word(bytes, i);
+        let (count, opcode) = ((w >> 16) as usize, w & 0xffff);
+
+        if count == 0 || i + count > words {
+            return Err(SpirvError::Truncated);
+        }
+        if opcode == OP_ENTRY_POINT && count > 1 && word(bytes, i + 1) == stage.execution_model() {
+            found = true;
+        }
+        i += count;
     }
 
+    if found {
+        Ok(())
+    } else {
+        Err(SpirvError::Stage)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Canvas
+///////////////////////////////////////////////////////////////////////////////
+
+pub trait Canvas {
+    fn clear(&self, color: Rgba, device: &mut Device, encoder: &mut wgpu::CommandEncoder);
+    fn fill(&self, buf: &[u8], device: &mut Device, encoder: &mut wgpu::CommandEncoder);
+    #[allow(clippy::too_many_arguments)]
     fn transfer(
         &self,
         buf: &[u8],
@@ -720,893 +1896,4525 @@ impl Canvas for Framebuffer {
         th: u32,
         device: &mut Device,
         encoder: &mut wgpu::CommandEncoder,
+    );
+    fn blit(&self, from: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder);
+
+    /// Fill a single layer of a texture array. Canvases without layers only
+    /// have layer `0`.
+    fn fill_layer(
+        &self,
+        layer: u32,
+        buf: &[u8],
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
     ) {
-        Texture::transfer(&self.texture, buf, w, h, tw, th, device, encoder);
+        assert_eq!(layer, 0, "fatal: canvas has no layer {}", layer);
+        self.fill(buf, device, encoder);
     }
 
-    fn blit(&self, from: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder) {
-        Texture::blit(&self.texture, from, dst, encoder);
+    /// Transfer texels to a single layer of a texture array. Canvases
+    /// without layers only have layer `0`.
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_layer(
+        &self,
+        layer: u32,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        tw: u32,
+        th: u32,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        assert_eq!(layer, 0, "fatal: canvas has no layer {}", layer);
+        self.transfer(buf, w, h, tw, th, device, encoder);
     }
-}
 
-impl TextureView for Framebuffer {
-    fn texture_view(&self) -> &wgpu::TextureView {
-        &self.texture.view
+    /// Transfer `w` by `h` texels to the `dst` region of the canvas.
+    /// Canvases which can't be written to at an offset only accept regions
+    /// starting at `(0, 0)`.
+    fn transfer_at(
+        &self,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        dst: Rect<u32>,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        assert_eq!(
+            (dst.x1, dst.y1),
+            (0, 0),
+            "fatal: canvas can't be transferred to at an offset"
+        );
+        self.transfer(buf, w, h, dst.x2, dst.y2, device, encoder);
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Texturing
+/// BindingGroup
 ///////////////////////////////////////////////////////////////////////////////
 
-#[allow(dead_code)]
-pub struct Texture {
-    wgpu: wgpu::Texture,
-    view: wgpu::TextureView,
-    extent: wgpu::Extent3d,
-
-    pub w: u32,
-    pub h: u32,
+/// A group of bindings. Cloning it is cheap, and yields a handle to the
+/// same group.
+#[derive(Clone)]
+pub struct BindingGroup {
+    wgpu: Arc<wgpu::BindGroup>,
+    set_index: u32,
+    /// Identifiers of the texture views bound in this group.
+    views: SmallVec<[usize; 2]>,
 }
 
-impl Texture {
-    pub fn rect(&self) -> Rect<f32> {
-        Rect {
-            x1: 0.0,
-            y1: 0.0,
-            x2: self.w as f32,
-            y2: self.h as f32,
+impl BindingGroup {
+    fn new(set_index: u32, wgpu: wgpu::BindGroup, views: SmallVec<[usize; 2]>) -> Self {
+        Self {
+            set_index,
+            wgpu: Arc::new(wgpu),
+            views,
         }
     }
+}
 
-    fn clear<T>(
-        texture: &Texture,
-        color: T,
-        device: &mut Device,
-        encoder: &mut wgpu::CommandEncoder,
-    ) where
-        T: Into<Rgba8> + Clone,
-    {
-        let mut texels: Vec<T> = Vec::with_capacity(texture.w as usize * texture.h as usize);
-        texels.resize(texture.w as usize * texture.h as usize, color);
-
-        let (head, body, tail) = unsafe { texels.align_to::<u8>() };
-        assert!(head.is_empty());
-        assert!(tail.is_empty());
+/// The layout of a 'BindingGroup'.
+pub struct BindingGroupLayout {
+    wgpu: wgpu::BindGroupLayout,
+    size: usize,
+    set_index: u32,
+    types: Vec<BindingType>,
+}
 
-        Self::fill(texture, body, device, encoder);
-    }
-
-    fn fill(
-        texture: &Texture,
-        texels: &[u8],
-        device: &mut Device,
-        encoder: &mut wgpu::CommandEncoder,
-    ) {
-        assert_eq!(
-            texels.len() as u32,
-            texture.w * texture.h * 4,
-            "fatal: incorrect length for texel buffer"
-        );
+impl BindingGroupLayout {
+    fn new(set_index: u32, layout: wgpu::BindGroupLayout, types: Vec<BindingType>) -> Self {
+        Self {
+            wgpu: layout,
+            size: types.len(),
+            set_index,
+            types,
+        }
+    }
+}
 
-        let buf = device
-            .device
-            .create_buffer_mapped(texels.len(), wgpu::BufferUsage::COPY_SRC)
-            .fill_from_slice(&texels);
+/// Identifies a binding of a set: by the name declared in the shader, or by
+/// its index, eg. for modules stripped of their debug names.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BindingKey<'n> {
+    Name(&'n str),
+    Index(u32),
+}
 
-        Self::copy(
-            &texture.wgpu,
-            texture.w,
-            texture.h,
-            texture.extent,
-            &buf,
-            encoder,
-        );
+impl<'n> From<&'n str> for BindingKey<'n> {
+    fn from(name: &'n str) -> Self {
+        Self::Name(name)
     }
+}
 
-    fn transfer(
-        texture: &Texture,
-        texels: &[u8],
-        width: u32,
-        height: u32,
-        transfer_w: u32,
-        transfer_h: u32,
-        device: &mut Device,
-        encoder: &mut wgpu::CommandEncoder,
-    ) {
-        assert_eq!(
-            (texels.len() as u32 / 4),
-            width * height,
-            "fatal: incorrect length for texel buffer"
-        );
-        assert!(
-            transfer_w * transfer_h <= texture.w * texture.h,
-            "fatal: transfer size must be <= texture size"
-        );
+impl From<u32> for BindingKey<'_> {
+    fn from(index: u32) -> Self {
+        Self::Index(index)
+    }
+}
 
-        let buf = device
-            .device
-            .create_buffer_mapped(texels.len(), wgpu::BufferUsage::COPY_SRC)
-            .fill_from_slice(&texels);
+/// An error returned when building a binding group with a
+/// [`BindingGroupBuilder`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BindingError {
+    /// The set has no binding with the given name. Modules stripped of their
+    /// debug names can only be bound by index.
+    Name { set: u32, name: String },
+    /// The binding index is out of range for the set.
+    Range { set: u32, binding: u32, size: usize },
+    /// The resource doesn't match the type of the binding.
+    Type {
+        set: u32,
+        binding: u32,
+        expected: BindingType,
+        found: BindingType,
+    },
+    /// The binding was left unbound.
+    Unbound { set: u32, binding: u32 },
+}
 
-        let extent = wgpu::Extent3d {
-            width: transfer_w,
-            height: transfer_h,
-            depth: 1,
-        };
-        Self::copy(&texture.wgpu, width, height, extent, &buf, encoder);
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name { set, name } => write!(f, "set {} has no binding named '{}'", set, name),
+            Self::Range { set, binding, size } => write!(
+                f,
+                "binding {} is out of range for set {} of size {}",
+                binding, set, size
+            ),
+            Self::Type {
+                set,
+                binding,
+                expected,
+                found,
+            } => write!(
+                f,
+                "binding {} of set {} is a {:?}, not a {:?}",
+                binding, set, expected, found
+            ),
+            Self::Unbound { set, binding } => {
+                write!(f, "binding {} of set {} was not bound", binding, set)
+            }
+        }
     }
+}
 
-    fn blit(&self, src: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder) {
-        assert_eq!(
-            src.width(),
-            dst.width(),
-            "source and destination rectangles must be of the same size"
-        );
-        assert_eq!(
-            src.height(),
-            dst.height(),
-            "source and destination rectangles must be of the same size"
-        );
+impl std::error::Error for BindingError {}
 
-        encoder.copy_texture_to_texture(
-            wgpu::TextureCopyView {
-                texture: &self.wgpu,
-                mip_level: 0,
-                array_layer: 0,
-                origin: wgpu::Origin3d {
-                    x: src.x1,
-                    y: src.y1,
-                    z: 0.0,
-                },
-            },
-            wgpu::TextureCopyView {
-                texture: &self.wgpu,
-                mip_level: 0,
-                array_layer: 0,
-                origin: wgpu::Origin3d {
-                    x: dst.x1,
-                    y: dst.y1,
-                    z: 0.0,
-                },
-            },
-            wgpu::Extent3d {
-                width: src.width() as u32,
-                height: src.height() as u32,
-                depth: 1,
-            },
-        );
-    }
+/// Builds a 'BindingGroup' by binding resources to the names declared
+/// in the pipeline's shaders, rather than by index. Bindings can also be
+/// given by index, for modules stripped of their debug names.
+///
+/// Created with [`Pipeline::binding_group`]. Errors are reported by
+/// [`BindingGroupBuilder::build`].
+///
+/// ```ignore
+/// let binding = pipeline
+///     .binding_group(2)
+///     .texture("tex", &texture)
+///     .sampler(1, &sampler)
+///     .build(&renderer.device)?;
+/// ```
+pub struct BindingGroupBuilder<'a> {
+    pipeline: &'a Pipeline,
+    layout: &'a BindingGroupLayout,
+    binds: Vec<Option<&'a dyn Bind>>,
+    /// The first error, reported when the group is built.
+    error: Option<BindingError>,
+}
 
-    fn copy(
-        texture: &wgpu::Texture,
-        w: u32,
-        h: u32,
-        extent: wgpu::Extent3d,
-        buffer: &wgpu::Buffer,
-        encoder: &mut wgpu::CommandEncoder,
-    ) {
-        encoder.copy_buffer_to_texture(
-            wgpu::BufferCopyView {
-                buffer,
-                offset: 0,
-                row_pitch: 4 * w,
-                image_height: h,
-            },
-            wgpu::TextureCopyView {
-                texture,
-                mip_level: 0,
-                array_layer: 0,
-                origin: wgpu::Origin3d {
-                    x: 0.0,
-                    y: 0.0,
-                    z: 0.0,
-                },
-            },
-            extent,
-        );
+impl<'a> BindingGroupBuilder<'a> {
+    /// Bind a texture, texture array or cube texture to the given shader
+    /// variable.
+    pub fn texture<'n>(self, key: impl Into<BindingKey<'n>>, texture: &'a Texture) -> Self {
+        let ty = if texture.is_array() {
+            BindingType::SampledTextureArray
+        } else if texture.is_cube() {
+            BindingType::SampledCubeTexture
+        } else {
+            BindingType::SampledTexture
+        };
+        self.bind(key.into(), ty, texture)
     }
-}
 
-impl Bind for Texture {
-    fn binding(&self, index: u32) -> wgpu::Binding {
-        wgpu::Binding {
-            binding: index as u32,
-            resource: wgpu::BindingResource::TextureView(&self.view),
-        }
+    /// Bind a sampler to the given shader variable.
+    pub fn sampler<'n>(self, key: impl Into<BindingKey<'n>>, sampler: &'a Sampler) -> Self {
+        self.bind(key.into(), BindingType::Sampler, sampler)
     }
-}
 
-impl Canvas for Texture {
-    fn fill(&self, buf: &[u8], device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
-        Texture::fill(&self, buf, device, encoder);
+    /// Bind an array of uniform records to the given shader block.
+    pub fn uniform_array<'n>(
+        self,
+        key: impl Into<BindingKey<'n>>,
+        array: &'a UniformArray,
+    ) -> Self {
+        self.bind(key.into(), BindingType::UniformBuffer, array)
     }
 
-    fn clear(&self, color: Rgba, device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
-        Texture::clear(&self, color, device, encoder);
+    /// Bind a uniform buffer to the given shader block.
+    pub fn uniform_buffer<'n>(
+        self,
+        key: impl Into<BindingKey<'n>>,
+        buf: &'a UniformBuffer,
+    ) -> Self {
+        let key = key.into();
+        let ty = match self.index(key).map(|i| self.layout.types[i]) {
+            Ok(BindingType::UniformBufferDynamic) => BindingType::UniformBufferDynamic,
+            _ => BindingType::UniformBuffer,
+        };
+        self.bind(key, ty, buf)
     }
 
-    fn transfer(
-        &self,
-        buf: &[u8],
-        w: u32,
-        h: u32,
-        tw: u32,
-        th: u32,
-        device: &mut Device,
-        encoder: &mut wgpu::CommandEncoder,
-    ) {
-        Texture::transfer(&self, buf, w, h, tw, th, device, encoder);
+    /// Create the binding group. Fails with the first binding which
+    /// couldn't be resolved, or if any of the slots in the group were left
+    /// unbound.
+    pub fn build(self, device: &Device) -> Result<BindingGroup, BindingError> {
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        let mut binds: SmallVec<[&dyn Bind; 4]> = SmallVec::new();
+
+        for (i, b) in self.binds.iter().enumerate() {
+            match b {
+                Some(b) => binds.push(*b),
+                None => {
+                    return Err(BindingError::Unbound {
+                        set: self.layout.set_index,
+                        binding: i as u32,
+                    })
+                }
+            }
+        }
+        Ok(device.create_binding_group(self.layout, binds.as_slice()))
     }
 
-    fn blit(&self, src: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder) {
-        Texture::blit(&self, src, dst, encoder);
+    fn bind(mut self, key: BindingKey<'_>, ty: BindingType, resource: &'a dyn Bind) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        let result = self.index(key).and_then(|index| {
+            let expected = self.layout.types[index];
+
+            if expected == ty {
+                Ok(index)
+            } else {
+                Err(BindingError::Type {
+                    set: self.layout.set_index,
+                    binding: index as u32,
+                    expected,
+                    found: ty,
+                })
+            }
+        });
+
+        match result {
+            Ok(index) => self.binds[index] = Some(resource),
+            Err(err) => self.error = Some(err),
+        }
+        self
+    }
+
+    fn index(&self, key: BindingKey<'_>) -> Result<usize, BindingError> {
+        let set = self.layout.set_index;
+        let binding = match key {
+            BindingKey::Index(i) => i,
+            BindingKey::Name(name) => self
+                .pipeline
+                .bindings
+                .iter()
+                .find(|b| b.set == set && b.name.as_deref() == Some(name))
+                .map(|b| b.binding)
+                .ok_or_else(|| BindingError::Name {
+                    set,
+                    name: name.to_owned(),
+                })?,
+        };
+
+        if (binding as usize) < self.binds.len() {
+            Ok(binding as usize)
+        } else {
+            Err(BindingError::Range {
+                set,
+                binding,
+                size: self.binds.len(),
+            })
+        }
     }
 }
 
-impl From<Framebuffer> for Texture {
-    fn from(fb: Framebuffer) -> Self {
-        fb.texture
+/// A trait representing a resource that can be bound.
+pub trait Bind {
+    fn binding(&self, index: u32) -> wgpu::Binding;
+
+    /// The id of the texture view bound, if any. Used to record which
+    /// textures a pass reads in the [`FrameGraph`].
+    fn view_id(&self) -> Option<usize> {
+        None
     }
 }
 
-pub struct Sampler {
-    wgpu: wgpu::Sampler,
+///////////////////////////////////////////////////////////////////////////////
+/// Uniforms
+///////////////////////////////////////////////////////////////////////////////
+
+/// A uniform buffer that can be bound in a 'BindingGroup'. Cloning it is
+/// cheap, and yields a handle to the same buffer.
+#[derive(Clone)]
+pub struct UniformBuffer {
+    wgpu: Arc<wgpu::Buffer>,
+    size: usize,
+    count: usize,
 }
 
-impl Bind for Sampler {
+impl Bind for UniformBuffer {
     fn binding(&self, index: u32) -> wgpu::Binding {
         wgpu::Binding {
             binding: index as u32,
-            resource: wgpu::BindingResource::Sampler(&self.wgpu),
+            resource: wgpu::BindingResource::Buffer {
+                buffer: &self.wgpu,
+                range: 0..(self.size as wgpu::BufferAddress),
+            },
         }
     }
 }
 
-#[derive(Debug)]
-pub enum Filter {
-    Nearest,
-    Linear,
+/// A uniform buffer holding an array of per-object records, which is bound
+/// in its entirety. Shaders select a record with `gl_InstanceIndex`, see
+/// [`Pass::draw_buffer_instance`]. This is a portable alternative to dynamic
+/// offsets for batched draws.
+///
+/// Since records are laid out with `std140` rules, the record size must be
+/// a multiple of 16 bytes.
+pub struct UniformArray {
+    buf: UniformBuffer,
 }
 
-impl Filter {
-    fn to_wgpu(&self) -> wgpu::FilterMode {
-        match self {
-            Filter::Nearest => wgpu::FilterMode::Nearest,
-            Filter::Linear => wgpu::FilterMode::Linear,
-        }
+impl UniformArray {
+    /// The number of records in the array.
+    pub fn len(&self) -> usize {
+        self.buf.count
     }
-}
-
-///////////////////////////////////////////////////////////////////////////////
-/// Vertex/Index Buffers
-///////////////////////////////////////////////////////////////////////////////
 
-pub struct VertexBuffer {
-    pub size: u32,
-    wgpu: wgpu::Buffer,
+    pub fn is_empty(&self) -> bool {
+        self.buf.count == 0
+    }
 }
 
-impl Draw for VertexBuffer {
-    fn draw(&self, binding: &BindingGroup, pass: &mut Pass) {
-        // TODO: If we attempt to draw more vertices than exist in the buffer, because
-        // 'size' was guessed wrong, we get a wgpu error. We should somehow try to
-        // get the pipeline layout to know here if the buffer we're trying to draw
-        // is the right size. Another option is to create buffers from the pipeline,
-        // so that we can check at creation time whether the data passed in matches
-        // the format.
-        pass.set_binding(binding, &[]);
-        pass.draw_buffer(&self);
+impl Bind for UniformArray {
+    fn binding(&self, index: u32) -> wgpu::Binding {
+        wgpu::Binding {
+            binding: index,
+            resource: wgpu::BindingResource::Buffer {
+                buffer: &self.buf.wgpu,
+                range: 0..((self.buf.size * self.buf.count) as wgpu::BufferAddress),
+            },
+        }
     }
 }
 
-pub struct IndexBuffer {
-    wgpu: wgpu::Buffer,
+/// The alignment of dynamic uniform buffer offsets.
+const DYNAMIC_OFFSET_ALIGNMENT: u64 = 256;
+
+/// The largest uniform buffer range which can be bound on all backends.
+const MAX_UNIFORM_BUFFER_RANGE: usize = 64 * 1024;
+
+/// Required alignment of the rows of buffers copied to and from textures.
+const ROW_PITCH_ALIGNMENT: u32 = 256;
+
+/// The row pitch of a buffer holding rows of `row` bytes, copied to or from
+/// a texture.
+fn row_pitch(row: u32) -> u32 {
+    row.div_ceil(ROW_PITCH_ALIGNMENT) * ROW_PITCH_ALIGNMENT
 }
 
-#[derive(Clone, Copy)]
-pub enum VertexFormat {
-    Float,
-    Float2,
-    Float3,
-    Float4,
-    UByte4,
+/// A record in a [`PushBuffer`], padded to the dynamic offset alignment.
+#[repr(C, align(256))]
+#[derive(Copy, Clone)]
+struct Aligned<T: Copy>(T);
+
+/// Per-draw uniform values, eg. transforms or tints, collected while
+/// batching and uploaded once per frame. Each draw binds the same buffer
+/// at a different dynamic offset.
+///
+/// The buffer is bound to a set with a single
+/// [`BindingType::UniformBufferDynamic`] binding.
+///
+/// ```ignore
+/// let mut push: PushBuffer<Matrix4<f32>> = renderer.push_buffer(&pipeline.layout.sets[1], 64);
+///
+/// for sprite in sprites.iter() {
+///     push.push(sprite.transform);
+/// }
+/// push.upload(&mut renderer, &mut frame);
+///
+/// let mut pass = frame.pass(PassOp::Clear(Rgba::TRANSPARENT), &target);
+/// pass.set_pipeline(&pipeline);
+///
+/// for (sprite, (binding, offset)) in sprites.iter().zip(push.draws()) {
+///     pass.set_binding(binding, &[offset]);
+///     pass.draw(&sprite.buffer, &sprite.binding);
+/// }
+/// ```
+pub struct PushBuffer<T: Copy> {
+    records: Vec<Aligned<T>>,
+    buf: UniformBuffer,
+    binding: BindingGroup,
 }
 
-impl VertexFormat {
-    // TODO: Use `const fn`
-    fn bytesize(self) -> usize {
-        match self {
-            VertexFormat::Float => 4,
-            VertexFormat::Float2 => 8,
-            VertexFormat::Float3 => 12,
-            VertexFormat::Float4 => 16,
-            VertexFormat::UByte4 => 4,
-        }
+impl<T> PushBuffer<T>
+where
+    T: 'static + Copy,
+{
+    /// Add a record, returning its index.
+    ///
+    /// Panics if the buffer is full.
+    pub fn push(&mut self, record: T) -> usize {
+        assert!(
+            self.records.len() < self.capacity(),
+            "fatal: push buffer capacity of {} exceeded",
+            self.capacity()
+        );
+        self.records.push(Aligned(record));
+        self.records.len() - 1
     }
-    // TODO: Use `const fn`
-    fn to_wgpu(self) -> wgpu::VertexFormat {
-        match self {
-            VertexFormat::Float => wgpu::VertexFormat::Float,
-            VertexFormat::Float2 => wgpu::VertexFormat::Float2,
-            VertexFormat::Float3 => wgpu::VertexFormat::Float3,
-            VertexFormat::Float4 => wgpu::VertexFormat::Float4,
-            VertexFormat::UByte4 => wgpu::VertexFormat::Uchar4Norm,
+
+    /// Upload the records to the GPU. This should be done once per frame,
+    /// after all records were pushed.
+    pub fn upload(&self, renderer: &mut Renderer, f: &mut Frame) {
+        if !self.records.is_empty() {
+            renderer.update_uniform_buffer(&self.buf, self.records.as_slice(), f);
         }
     }
-}
 
-/// Describes a 'VertexBuffer' layout.
-#[derive(Default)]
-pub struct VertexLayout {
-    wgpu_attrs: Vec<wgpu::VertexAttributeDescriptor>,
-    size: usize,
-}
+    /// The binding and dynamic offset of the record at `index`.
+    pub fn get(&self, index: usize) -> (&BindingGroup, u64) {
+        assert!(
+            index < self.records.len(),
+            "fatal: push buffer index {} out of bounds",
+            index
+        );
+        (&self.binding, index as u64 * DYNAMIC_OFFSET_ALIGNMENT)
+    }
 
-impl VertexLayout {
-    pub fn from(formats: &[VertexFormat]) -> Self {
-        let mut vl = Self::default();
-        for vf in formats {
-            vl.wgpu_attrs.push(wgpu::VertexAttributeDescriptor {
-                shader_location: vl.wgpu_attrs.len() as u32,
-                offset: vl.size as wgpu::BufferAddress,
-                format: vf.to_wgpu(),
-            });
-            vl.size += vf.bytesize();
-        }
-        vl
+    /// The binding and dynamic offset of every record, in the order they
+    /// were pushed.
+    pub fn draws(&self) -> impl Iterator<Item = (&BindingGroup, u64)> + '_ {
+        (0..self.records.len()).map(move |i| self.get(i))
     }
 
-    fn to_wgpu(&self) -> wgpu::VertexBufferDescriptor {
-        wgpu::VertexBufferDescriptor {
-            stride: self.size as wgpu::BufferAddress,
-            step_mode: wgpu::InputStepMode::Vertex,
-            attributes: self.wgpu_attrs.as_slice(),
-        }
+    /// Remove all records, eg. at the start of a frame.
+    pub fn clear(&mut self) {
+        self.records.clear();
     }
-}
-
-///////////////////////////////////////////////////////////////////////////////
-/// Pipeline Bindings
-///////////////////////////////////////////////////////////////////////////////
 
-/// A binding type.
-pub enum BindingType {
-    UniformBuffer,
-    UniformBufferDynamic,
-    Sampler,
-    SampledTexture,
-}
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
 
-impl BindingType {
-    fn to_wgpu(&self) -> wgpu::BindingType {
-        match self {
-            BindingType::UniformBuffer => wgpu::BindingType::UniformBuffer { dynamic: false },
-            BindingType::UniformBufferDynamic => wgpu::BindingType::UniformBuffer { dynamic: true },
-            BindingType::SampledTexture => wgpu::BindingType::SampledTexture {
-                multisampled: false,
-                dimension: wgpu::TextureViewDimension::D2,
-            },
-            BindingType::Sampler => wgpu::BindingType::Sampler,
-        }
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
     }
-}
 
-pub struct Binding {
-    pub binding: BindingType,
-    pub stage: ShaderStage,
+    /// The maximum number of records.
+    pub fn capacity(&self) -> usize {
+        self.buf.count
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Pipeline
+/// Framebuffer
 ///////////////////////////////////////////////////////////////////////////////
 
-pub struct Pipeline {
-    wgpu: wgpu::RenderPipeline,
-
-    pub layout: PipelineLayout,
-    pub vertex_layout: VertexLayout,
+#[allow(dead_code)]
+#[derive(Clone)]
+pub struct Framebuffer {
+    pub texture: Texture,
 }
 
-impl<'a> AbstractPipeline<'a> for Pipeline {
-    type PrepareContext = ();
-    type Uniforms = ();
+impl Framebuffer {
+    pub fn size(&self) -> usize {
+        (self.texture.w * self.texture.h) as usize
+    }
 
-    fn description() -> PipelineDescription<'a> {
-        PipelineDescription {
-            vertex_layout: &[],
-            pipeline_layout: &[],
-            vertex_shader: &[],
-            fragment_shader: &[],
-        }
+    pub fn width(&self) -> u32 {
+        self.texture.w
     }
 
-    fn setup(pipeline: Self, _dev: &Device, _w: u32, _h: u32) -> Self {
-        pipeline
+    pub fn height(&self) -> u32 {
+        self.texture.h
     }
+}
 
-    fn apply(&self, pass: &mut Pass) {
-        pass.wgpu.set_pipeline(&self.wgpu);
+impl Bind for Framebuffer {
+    fn binding(&self, index: u32) -> wgpu::Binding {
+        wgpu::Binding {
+            binding: index as u32,
+            resource: wgpu::BindingResource::TextureView(&self.texture.view),
+        }
     }
 
-    fn width(&self) -> u32 {
-        unimplemented!()
+    fn view_id(&self) -> Option<usize> {
+        Some(self.texture.id)
     }
+}
 
-    fn height(&self) -> u32 {
-        unimplemented!()
+impl Canvas for Framebuffer {
+    fn clear(&self, color: Rgba, device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
+        Texture::clear_color(&self.texture, color, device, encoder);
     }
 
-    fn resize(&mut self, _w: u32, _h: u32) {}
+    fn fill(&self, buf: &[u8], device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
+        Texture::fill(&self.texture, buf, device, encoder);
+    }
 
-    fn prepare(&'a self, _unused: ()) -> Option<(&'a UniformBuffer, Vec<()>)> {
-        None
+    fn transfer(
+        &self,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        tw: u32,
+        th: u32,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Texture::transfer_region(
+            &self.texture,
+            0,
+            buf,
+            Size2D::new(w, h),
+            Rect::origin(tw, th),
+            device,
+            encoder,
+        );
     }
-}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
-pub struct Blending {
-    src_factor: BlendFactor,
-    dst_factor: BlendFactor,
-    operation: BlendOp,
-}
+    fn transfer_at(
+        &self,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        dst: Rect<u32>,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Texture::transfer_region(
+            &self.texture,
+            0,
+            buf,
+            Size2D::new(w, h),
+            dst,
+            device,
+            encoder,
+        );
+    }
 
-impl Blending {
-    pub fn new(src_factor: BlendFactor, dst_factor: BlendFactor, operation: BlendOp) -> Self {
-        Blending {
-            src_factor,
-            dst_factor,
-            operation,
-        }
+    fn blit(&self, from: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder) {
+        Texture::blit(&self.texture, from, dst, encoder);
     }
+}
 
-    pub fn constant() -> Self {
-        Blending {
-            src_factor: BlendFactor::One,
-            dst_factor: BlendFactor::Zero,
-            operation: BlendOp::Add,
-        }
+impl TextureView for Framebuffer {
+    fn texture_view(&self) -> &wgpu::TextureView {
+        &self.texture.view
     }
 
-    fn to_wgpu(&self) -> (wgpu::BlendFactor, wgpu::BlendFactor, wgpu::BlendOperation) {
-        (
-            self.src_factor.to_wgpu(),
-            self.dst_factor.to_wgpu(),
-            self.operation.to_wgpu(),
-        )
+    fn id(&self) -> usize {
+        self.texture.id
     }
-}
 
-impl Default for Blending {
-    fn default() -> Self {
-        Blending {
-            src_factor: BlendFactor::SrcAlpha,
-            dst_factor: BlendFactor::OneMinusSrcAlpha,
-            operation: BlendOp::Add,
-        }
+    fn format(&self) -> Option<TextureFormat> {
+        Some(self.texture.format)
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum BlendFactor {
-    One,
-    Zero,
-    SrcAlpha,
-    OneMinusSrcAlpha,
+///////////////////////////////////////////////////////////////////////////////
+/// Depth
+///////////////////////////////////////////////////////////////////////////////
+
+/// A depth and stencil buffer, for passes started with
+/// [`Frame::pass_with_depth`]. Cloning it is cheap, and yields a handle to
+/// the same buffer.
+#[allow(dead_code)]
+#[derive(Clone)]
+pub struct DepthBuffer {
+    wgpu: Arc<wgpu::Texture>,
+    view: Arc<wgpu::TextureView>,
+
+    pub w: u32,
+    pub h: u32,
 }
 
-impl BlendFactor {
-    fn to_wgpu(&self) -> wgpu::BlendFactor {
-        match self {
-            BlendFactor::SrcAlpha => wgpu::BlendFactor::SrcAlpha,
-            BlendFactor::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
-            BlendFactor::One => wgpu::BlendFactor::One,
-            BlendFactor::Zero => wgpu::BlendFactor::Zero,
-        }
-    }
+impl DepthBuffer {
+    /// The texel format of depth buffers.
+    const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Depth24PlusStencil8;
 }
 
+/// A comparison between a fragment's depth and the depth buffer's.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum BlendOp {
-    Add,
+pub enum DepthCompare {
+    Never,
+    Less,
+    Equal,
+    LessEqual,
+    Greater,
+    NotEqual,
+    GreaterEqual,
+    Always,
 }
 
-impl BlendOp {
-    fn to_wgpu(&self) -> wgpu::BlendOperation {
+impl DepthCompare {
+    fn to_wgpu(self) -> wgpu::CompareFunction {
         match self {
-            BlendOp::Add => wgpu::BlendOperation::Add,
+            Self::Never => wgpu::CompareFunction::Never,
+            Self::Less => wgpu::CompareFunction::Less,
+            Self::Equal => wgpu::CompareFunction::Equal,
+            Self::LessEqual => wgpu::CompareFunction::LessEqual,
+            Self::Greater => wgpu::CompareFunction::Greater,
+            Self::NotEqual => wgpu::CompareFunction::NotEqual,
+            Self::GreaterEqual => wgpu::CompareFunction::GreaterEqual,
+            Self::Always => wgpu::CompareFunction::Always,
         }
     }
 }
 
-pub struct Set<'a>(pub &'a [Binding]);
-
-pub struct PipelineLayout {
-    pub sets: Vec<BindingGroupLayout>,
+/// The depth testing of a pipeline, eg. for layered 2.5D scenes. Pipelines
+/// with a depth state can only be used in passes with a depth buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DepthState {
+    /// How fragment depths are compared to the depth buffer. Fragments
+    /// failing the comparison are discarded.
+    pub compare: DepthCompare,
+    /// Whether the depths of fragments passing the comparison are written
+    /// to the depth buffer. Translucent layers usually don't write depth.
+    pub write: bool,
 }
 
-pub trait AbstractPipeline<'a> {
-    type PrepareContext;
-    type Uniforms: Copy + 'static;
-
-    fn description() -> PipelineDescription<'a>;
-    fn setup(pip: Pipeline, dev: &Device, w: u32, h: u32) -> Self;
-    fn apply(&self, pass: &mut Pass);
-    fn resize(&mut self, w: u32, h: u32);
-    fn width(&self) -> u32;
-    fn height(&self) -> u32;
-    fn prepare(
-        &'a self,
-        t: Self::PrepareContext,
-    ) -> Option<(&'a UniformBuffer, Vec<Self::Uniforms>)>;
+impl Default for DepthState {
+    /// Fragments closer than, or as close as the depth buffer pass, and
+    /// write their depth.
+    fn default() -> Self {
+        Self {
+            compare: DepthCompare::LessEqual,
+            write: true,
+        }
+    }
 }
 
-pub struct PipelineDescription<'a> {
-    pub vertex_layout: &'a [VertexFormat],
-    pub pipeline_layout: &'a [Set<'a>],
-    pub vertex_shader: &'static [u8],
-    pub fragment_shader: &'static [u8],
+impl DepthState {
+    fn to_wgpu(self) -> wgpu::DepthStencilStateDescriptor {
+        wgpu::DepthStencilStateDescriptor {
+            format: DepthBuffer::FORMAT,
+            depth_write_enabled: self.write,
+            depth_compare: self.compare.to_wgpu(),
+            stencil_front: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_back: wgpu::StencilStateFaceDescriptor::IGNORE,
+            stencil_read_mask: 0,
+            stencil_write_mask: 0,
+        }
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Frame
+/// Texturing
 ///////////////////////////////////////////////////////////////////////////////
 
-pub struct Frame {
-    encoder: wgpu::CommandEncoder,
+/// Texel format of a texture, render target or swap chain.
+///
+/// Block-compressed formats, eg. BC1, BC3 or BC7, aren't supported: the
+/// texture formats of wgpu 0.3 have no compressed variants, nor a way to
+/// enable them on devices which support them. Compressed images have to be
+/// decoded before they are uploaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextureFormat {
+    /// 8-bit single channel, eg. for masks or glyph coverage.
+    R8Unorm,
+    /// 8-bit two channel, eg. for normal or flow maps.
+    Rg8Unorm,
+    /// 8-bit RGBA, with a linear encoding.
+    Rgba8Unorm,
+    /// 8-bit RGBA, with an sRGB encoding.
+    Rgba8UnormSrgb,
+    /// 8-bit BGRA, with a linear encoding. This is the default format for
+    /// swap chains and framebuffers.
+    #[default]
+    Bgra8Unorm,
+    /// 8-bit BGRA, with an sRGB encoding. Shader outputs are encoded when
+    /// written and decoded when sampled, so blending happens in linear space.
+    Bgra8UnormSrgb,
+    /// 10-bit RGB with a 2-bit alpha channel.
+    Rgb10a2Unorm,
+    /// 16-bit floating point RGBA, with [`Rgba16F`] texels. Values are not
+    /// clamped to `[0, 1]`, eg. for HDR scenes which are tonemapped when
+    /// presented.
+    Rgba16Float,
 }
 
-impl Frame {
-    pub fn new(encoder: wgpu::CommandEncoder) -> Self {
-        Self { encoder }
+impl TextureFormat {
+    /// Whether the format can represent values outside of `[0, 1]`.
+    pub fn is_float(self) -> bool {
+        self == Self::Rgba16Float
     }
 
-    pub fn pass<T: TextureView>(&mut self, op: PassOp, view: &T) -> Pass {
-        Pass::begin(&mut self.encoder, &view.texture_view(), op)
+    /// Whether the format is sRGB-encoded.
+    pub fn is_srgb(self) -> bool {
+        self == Self::Rgba8UnormSrgb || self == Self::Bgra8UnormSrgb
     }
 
-    pub fn copy(&mut self, src: &UniformBuffer, dst: &UniformBuffer) {
-        self.encoder.copy_buffer_to_buffer(
-            &src.wgpu,
-            0,
-            &dst.wgpu,
-            0,
-            (src.size * src.count) as wgpu::BufferAddress,
-        );
+    /// The sRGB-encoded counterpart of the format. Formats without one are
+    /// returned as-is.
+    ///
+    /// ```
+    /// use rgx::core::TextureFormat;
+    ///
+    /// assert_eq!(TextureFormat::Bgra8Unorm.srgb(), TextureFormat::Bgra8UnormSrgb);
+    /// assert_eq!(TextureFormat::Rgba16Float.srgb(), TextureFormat::Rgba16Float);
+    /// ```
+    pub fn srgb(self) -> Self {
+        match self {
+            Self::Rgba8Unorm => Self::Rgba8UnormSrgb,
+            Self::Bgra8Unorm => Self::Bgra8UnormSrgb,
+            other => other,
+        }
+    }
+
+    /// The size of a texel of this format, in bytes.
+    ///
+    /// ```
+    /// use rgx::core::TextureFormat;
+    ///
+    /// assert_eq!(TextureFormat::R8Unorm.texel_size(), 1);
+    /// assert_eq!(TextureFormat::Bgra8Unorm.texel_size(), 4);
+    /// assert_eq!(TextureFormat::Rgba16Float.texel_size(), 8);
+    /// ```
+    pub fn texel_size(self) -> u32 {
+        match self {
+            Self::R8Unorm => 1,
+            Self::Rg8Unorm => 2,
+            Self::Rgba16Float => 8,
+            _ => 4,
+        }
+    }
+
+    pub fn to_wgpu(self) -> wgpu::TextureFormat {
+        match self {
+            Self::R8Unorm => wgpu::TextureFormat::R8Unorm,
+            Self::Rg8Unorm => wgpu::TextureFormat::Rg8Unorm,
+            Self::Rgba8Unorm => wgpu::TextureFormat::Rgba8Unorm,
+            Self::Rgba8UnormSrgb => wgpu::TextureFormat::Rgba8UnormSrgb,
+            Self::Bgra8Unorm => wgpu::TextureFormat::Bgra8Unorm,
+            Self::Bgra8UnormSrgb => wgpu::TextureFormat::Bgra8UnormSrgb,
+            Self::Rgb10a2Unorm => wgpu::TextureFormat::Rgb10a2Unorm,
+            Self::Rgba16Float => wgpu::TextureFormat::Rgba16Float,
+        }
     }
 }
 
-///////////////////////////////////////////////////////////////////////////////
-/// Pass
-///////////////////////////////////////////////////////////////////////////////
+/// A texture and its view. Cloning it is cheap, and yields a handle to the
+/// same texture.
+#[allow(dead_code)]
+#[derive(Clone)]
+pub struct Texture {
+    wgpu: Arc<wgpu::Texture>,
+    view: Arc<wgpu::TextureView>,
+    id: usize,
+    extent: wgpu::Extent3d,
+    format: TextureFormat,
+    levels: u32,
+    layers: u32,
+    dimension: wgpu::TextureViewDimension,
 
-pub struct Pass<'a> {
-    wgpu: wgpu::RenderPass<'a>,
+    pub w: u32,
+    pub h: u32,
 }
 
-impl<'a> Pass<'a> {
-    pub fn begin(
-        encoder: &'a mut wgpu::CommandEncoder,
-        view: &wgpu::TextureView,
-        op: PassOp,
+impl Texture {
+    pub fn rect(&self) -> Rect<f32> {
+        Rect {
+            x1: 0.0,
+            y1: 0.0,
+            x2: self.w as f32,
+            y2: self.h as f32,
+        }
+    }
+
+    /// The number of mip levels of the texture, including the base level.
+    pub fn mip_levels(&self) -> u32 {
+        self.levels
+    }
+
+    /// The number of layers of the texture. Textures which aren't arrays
+    /// or cube textures have a single layer.
+    pub fn layers(&self) -> u32 {
+        self.layers
+    }
+
+    /// The texel format of the texture.
+    pub fn format(&self) -> TextureFormat {
+        self.format
+    }
+
+    /// Whether the texture is a texture array, created with
+    /// [`Renderer::texture_array`]. Texture arrays are bound to
+    /// [`BindingType::SampledTextureArray`] bindings.
+    pub fn is_array(&self) -> bool {
+        self.dimension == wgpu::TextureViewDimension::D2Array
+    }
+
+    /// Whether the texture is a cube texture, created with
+    /// [`Renderer::cube_texture`]. Cube textures are bound to
+    /// [`BindingType::SampledCubeTexture`] bindings.
+    pub fn is_cube(&self) -> bool {
+        self.dimension == wgpu::TextureViewDimension::Cube
+    }
+
+    /// The number of mip levels needed to downscale a texture of the given
+    /// size to a single texel.
+    ///
+    /// ```
+    /// use rgx::core::Texture;
+    ///
+    /// assert_eq!(Texture::mip_level_count(1, 1), 1);
+    /// assert_eq!(Texture::mip_level_count(256, 64), 9);
+    /// assert_eq!(Texture::mip_level_count(100, 30), 7);
+    /// ```
+    pub fn mip_level_count(w: u32, h: u32) -> u32 {
+        32 - w.max(h).max(1).leading_zeros()
+    }
+
+    /// A view of a single mip level, which can be sampled from or rendered
+    /// to, eg. to generate the level from the one above it.
+    pub fn mip_view(&self, level: u32) -> MipView {
+        assert!(
+            level < self.levels,
+            "fatal: mip level {} is out of range for a texture with {} levels",
+            level,
+            self.levels
+        );
+        let view = self.wgpu.create_view(&wgpu::TextureViewDescriptor {
+            format: self.format.to_wgpu(),
+            dimension: wgpu::TextureViewDimension::D2,
+            aspect: wgpu::TextureAspect::All,
+            base_mip_level: level,
+            level_count: 1,
+            base_array_layer: 0,
+            array_layer_count: 1,
+        });
+
+        MipView {
+            view,
+            id: next_resource_id(),
+            format: self.format,
+            level,
+            w: (self.w >> level).max(1),
+            h: (self.h >> level).max(1),
+        }
+    }
+
+    /// Size of a texel in bytes.
+    fn texel_size(&self) -> u32 {
+        self.format.texel_size()
+    }
+
+    /// Clear the texture with texels of its format.
+    fn clear_color(
+        texture: &Self,
+        color: Rgba,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        match texture.format {
+            TextureFormat::Rgba16Float => {
+                Self::clear(texture, Rgba16F::from(color), device, encoder)
+            }
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+                Self::clear(texture, Rgba8::from(color), device, encoder)
+            }
+            TextureFormat::R8Unorm => Self::clear(texture, [Rgba8::from(color).r], device, encoder),
+            TextureFormat::Rg8Unorm => {
+                let c = Rgba8::from(color);
+                Self::clear(texture, [c.r, c.g], device, encoder)
+            }
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+                Self::clear(texture, Bgra8::from(Rgba8::from(color)), device, encoder)
+            }
+            TextureFormat::Rgb10a2Unorm => {
+                // Red is in the low bits, and alpha in the two high bits.
+                let unorm = |c: f32, max: f32| (c.clamp(0., 1.) * max).round() as u32;
+                let texel = unorm(color.r, 1023.)
+                    | (unorm(color.g, 1023.) << 10)
+                    | (unorm(color.b, 1023.) << 20)
+                    | (unorm(color.a, 3.) << 30);
+
+                Self::clear(texture, texel.to_le_bytes(), device, encoder)
+            }
+        }
+    }
+
+    fn clear<T>(
+        texture: &Texture,
+        color: T,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) where
+        T: Clone,
+    {
+        let mut texels: Vec<T> = Vec::with_capacity(texture.w as usize * texture.h as usize);
+        texels.resize(texture.w as usize * texture.h as usize, color);
+
+        let (head, body, tail) = unsafe { texels.align_to::<u8>() };
+        assert!(head.is_empty());
+        assert!(tail.is_empty());
+
+        Self::fill(texture, body, device, encoder);
+    }
+
+    fn fill(
+        texture: &Texture,
+        texels: &[u8],
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Self::fill_layer(texture, 0, texels, device, encoder);
+    }
+
+    fn fill_layer(
+        texture: &Texture,
+        layer: u32,
+        texels: &[u8],
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        texture.assert_layer(layer);
+        assert_eq!(
+            texels.len() as u32,
+            texture.w * texture.h * texture.texel_size(),
+            "fatal: incorrect length for texel buffer"
+        );
+
+        Self::copy(
+            texture,
+            layer,
+            wgpu::Origin3d {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+            },
+            texels,
+            texture.w,
+            texture.h,
+            texture.extent,
+            device,
+            encoder,
+        );
+    }
+
+    /// Transfer `size` texels to the given region of a texture layer. The
+    /// region's size is the transfer size, and its origin the offset of the
+    /// copy.
+    fn transfer_region(
+        texture: &Self,
+        layer: u32,
+        texels: &[u8],
+        size: Size2D<u32>,
+        region: Rect<u32>,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let Size2D { width, height } = size;
+
+        texture.assert_layer(layer);
+        assert_eq!(
+            (texels.len() as u32 / texture.texel_size()),
+            width * height,
+            "fatal: incorrect length for texel buffer"
+        );
+        assert!(
+            region.x1 <= region.x2 && region.y1 <= region.y2,
+            "fatal: transfer region must be normalized"
+        );
+        assert!(
+            region.x2 <= texture.w && region.y2 <= texture.h,
+            "fatal: transfer region must be within the texture"
+        );
+        assert!(
+            region.x2 - region.x1 <= width && region.y2 - region.y1 <= height,
+            "fatal: transfer region must not be larger than the texels"
+        );
+
+        let extent = wgpu::Extent3d {
+            width: region.x2 - region.x1,
+            height: region.y2 - region.y1,
+            depth: 1,
+        };
+        let origin = wgpu::Origin3d {
+            x: region.x1 as f32,
+            y: region.y1 as f32,
+            z: 0.0,
+        };
+        Self::copy(
+            texture, layer, origin, texels, width, height, extent, device, encoder,
+        );
+    }
+
+    fn blit(&self, src: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder) {
+        assert_eq!(
+            src.width(),
+            dst.width(),
+            "source and destination rectangles must be of the same size"
+        );
+        assert_eq!(
+            src.height(),
+            dst.height(),
+            "source and destination rectangles must be of the same size"
+        );
+
+        encoder.copy_texture_to_texture(
+            wgpu::TextureCopyView {
+                texture: &self.wgpu,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d {
+                    x: src.x1,
+                    y: src.y1,
+                    z: 0.0,
+                },
+            },
+            wgpu::TextureCopyView {
+                texture: &self.wgpu,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d {
+                    x: dst.x1,
+                    y: dst.y1,
+                    z: 0.0,
+                },
+            },
+            wgpu::Extent3d {
+                width: src.width() as u32,
+                height: src.height() as u32,
+                depth: 1,
+            },
+        );
+    }
+
+    fn assert_layer(&self, layer: u32) {
+        assert!(
+            layer < self.layers(),
+            "fatal: layer {} is out of range for a texture with {} layers",
+            layer,
+            self.layers()
+        );
+    }
+
+    /// Copy `extent` of `w` by `h` texels to the texture. Rows are padded to
+    /// the row pitch alignment in the staging buffer.
+    #[allow(clippy::too_many_arguments)]
+    fn copy(
+        texture: &Self,
+        layer: u32,
+        origin: wgpu::Origin3d,
+        texels: &[u8],
+        w: u32,
+        h: u32,
+        extent: wgpu::Extent3d,
+        device: &Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        let row = (texture.texel_size() * w) as usize;
+        let pitch = row_pitch(row as u32);
+        let gpu = device.gpu.device();
+        let mapped =
+            gpu.create_buffer_mapped(pitch as usize * h as usize, wgpu::BufferUsage::COPY_SRC);
+
+        for (dst, src) in mapped
+            .data
+            .chunks_exact_mut(pitch as usize)
+            .zip(texels.chunks_exact(row))
+        {
+            dst[..row].copy_from_slice(src);
+        }
+        let buffer = mapped.finish();
+
+        encoder.copy_buffer_to_texture(
+            wgpu::BufferCopyView {
+                buffer: &buffer,
+                offset: 0,
+                row_pitch: pitch,
+                image_height: h,
+            },
+            wgpu::TextureCopyView {
+                texture: &texture.wgpu,
+                mip_level: 0,
+                array_layer: layer,
+                origin,
+            },
+            extent,
+        );
+    }
+}
+
+impl Bind for Texture {
+    fn binding(&self, index: u32) -> wgpu::Binding {
+        wgpu::Binding {
+            binding: index as u32,
+            resource: wgpu::BindingResource::TextureView(&self.view),
+        }
+    }
+
+    fn view_id(&self) -> Option<usize> {
+        Some(self.id)
+    }
+}
+
+impl Canvas for Texture {
+    fn fill(&self, buf: &[u8], device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
+        Texture::fill(&self, buf, device, encoder);
+    }
+
+    fn clear(&self, color: Rgba, device: &mut Device, encoder: &mut wgpu::CommandEncoder) {
+        Self::clear_color(self, color, device, encoder);
+    }
+
+    fn transfer(
+        &self,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        tw: u32,
+        th: u32,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Self::transfer_region(
+            self,
+            0,
+            buf,
+            Size2D::new(w, h),
+            Rect::origin(tw, th),
+            device,
+            encoder,
+        );
+    }
+
+    fn blit(&self, src: Rect<f32>, dst: Rect<f32>, encoder: &mut wgpu::CommandEncoder) {
+        Texture::blit(&self, src, dst, encoder);
+    }
+
+    fn fill_layer(
+        &self,
+        layer: u32,
+        buf: &[u8],
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Self::fill_layer(self, layer, buf, device, encoder);
+    }
+
+    fn transfer_layer(
+        &self,
+        layer: u32,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        tw: u32,
+        th: u32,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Self::transfer_region(
+            self,
+            layer,
+            buf,
+            Size2D::new(w, h),
+            Rect::origin(tw, th),
+            device,
+            encoder,
+        );
+    }
+
+    fn transfer_at(
+        &self,
+        buf: &[u8],
+        w: u32,
+        h: u32,
+        dst: Rect<u32>,
+        device: &mut Device,
+        encoder: &mut wgpu::CommandEncoder,
+    ) {
+        Self::transfer_region(self, 0, buf, Size2D::new(w, h), dst, device, encoder);
+    }
+}
+
+impl From<Framebuffer> for Texture {
+    fn from(fb: Framebuffer) -> Self {
+        fb.texture
+    }
+}
+
+/// A face of a cube texture. Faces are the layers of the texture, in the
+/// order of the variants.
+///
+/// ```
+/// use rgx::core::CubeFace;
+///
+/// assert_eq!(CubeFace::PositiveX.layer(), 0);
+/// assert_eq!(CubeFace::NegativeZ.layer(), CubeFace::COUNT - 1);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// The number of faces of a cube.
+    pub const COUNT: u32 = 6;
+
+    /// All faces, in layer order.
+    pub const ALL: [Self; 6] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveY,
+        Self::NegativeY,
+        Self::PositiveZ,
+        Self::NegativeZ,
+    ];
+
+    /// The texture layer holding the face.
+    pub fn layer(self) -> u32 {
+        self as u32
+    }
+}
+
+/// A view of a single mip level of a texture.
+pub struct MipView {
+    view: wgpu::TextureView,
+    id: usize,
+    format: TextureFormat,
+
+    pub level: u32,
+    pub w: u32,
+    pub h: u32,
+}
+
+impl Bind for MipView {
+    fn binding(&self, index: u32) -> wgpu::Binding {
+        wgpu::Binding {
+            binding: index as u32,
+            resource: wgpu::BindingResource::TextureView(&self.view),
+        }
+    }
+
+    fn view_id(&self) -> Option<usize> {
+        Some(self.id)
+    }
+}
+
+impl TextureView for MipView {
+    fn texture_view(&self) -> &wgpu::TextureView {
+        &self.view
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn format(&self) -> Option<TextureFormat> {
+        Some(self.format)
+    }
+}
+
+pub struct Sampler {
+    wgpu: wgpu::Sampler,
+}
+
+impl Bind for Sampler {
+    fn binding(&self, index: u32) -> wgpu::Binding {
+        wgpu::Binding {
+            binding: index as u32,
+            resource: wgpu::BindingResource::Sampler(&self.wgpu),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+impl Filter {
+    fn to_wgpu(self) -> wgpu::FilterMode {
+        match self {
+            Filter::Nearest => wgpu::FilterMode::Nearest,
+            Filter::Linear => wgpu::FilterMode::Linear,
+        }
+    }
+}
+
+/// How texture coordinates outside of `[0, 1]` are sampled.
+///
+/// Clamping to a border color isn't supported by the backend. For sprites,
+/// a transparent border can be had with [`AddressMode::ClampToEdge`] and a
+/// texel of transparent padding, eg. as added by
+/// [`kit::atlas::AtlasBuilder`](crate::kit::atlas::AtlasBuilder).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum AddressMode {
+    /// Coordinates are clamped to the edge texels, eg. to avoid bleeding
+    /// between tiles at the edges of a texture.
+    ClampToEdge,
+    /// The texture is tiled.
+    #[default]
+    Repeat,
+    /// The texture is tiled, and mirrored every other repetition.
+    MirrorRepeat,
+}
+
+impl AddressMode {
+    fn to_wgpu(self) -> wgpu::AddressMode {
+        match self {
+            Self::ClampToEdge => wgpu::AddressMode::ClampToEdge,
+            Self::Repeat => wgpu::AddressMode::Repeat,
+            Self::MirrorRepeat => wgpu::AddressMode::MirrorRepeat,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Vertex/Index Buffers
+///////////////////////////////////////////////////////////////////////////////
+
+/// A vertex buffer. Cloning it is cheap, and yields a handle to the same
+/// buffer.
+#[derive(Clone)]
+pub struct VertexBuffer {
+    pub size: u32,
+    bytesize: u64,
+    wgpu: Arc<wgpu::Buffer>,
+}
+
+impl Draw for VertexBuffer {
+    fn draw(&self, binding: &BindingGroup, pass: &mut Pass) {
+        // In debug builds, the pass checks that the buffer matches the vertex
+        // stride of the current pipeline before drawing.
+        pass.set_binding(binding, &[]);
+        pass.draw_buffer(&self);
+    }
+}
+
+pub struct IndexBuffer {
+    wgpu: wgpu::Buffer,
+}
+
+/// A range of vertices, and optionally of indices, in a [`MeshArena`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mesh {
+    /// The range of the mesh's vertices in the arena's vertex buffer.
+    pub vertices: Range<u32>,
+    /// The range of the mesh's indices in the arena's index buffer, for
+    /// indexed meshes. Indices are relative to the mesh's first vertex.
+    pub indices: Option<Range<u32>>,
+}
+
+/// Packs many small meshes, eg. one per glyph or shape, into a single
+/// vertex buffer and a single index buffer, instead of creating buffers
+/// for each mesh.
+///
+/// Meshes are added on the CPU, and the buffers are created on
+/// [`MeshArena::upload`], if meshes were added since the last upload.
+/// Draw meshes with [`Pass::draw_mesh`], or with
+/// [`Pass::draw_buffer_range`] for non-indexed meshes.
+///
+/// ```
+/// use rgx::core::MeshArena;
+///
+/// let mut arena: MeshArena<[f32; 2]> = MeshArena::new();
+///
+/// let a = arena.add(&[[0., 0.], [1., 0.], [1., 1.]]);
+/// let b = arena.add_indexed(&[[0., 0.], [1., 0.], [1., 1.], [0., 1.]], &[0, 1, 2, 0, 2, 3]);
+///
+/// assert_eq!(a.vertices, 0..3);
+/// assert_eq!(a.indices, None);
+/// assert_eq!(b.vertices, 3..7);
+/// assert_eq!(b.indices, Some(0..6));
+/// assert_eq!(arena.len(), 2);
+/// ```
+pub struct MeshArena<T: Copy> {
+    vertices: Vec<T>,
+    indices: Vec<u16>,
+    meshes: usize,
+    buffers: Option<(VertexBuffer, Option<IndexBuffer>)>,
+}
+
+impl<T> MeshArena<T>
+where
+    T: 'static + Copy,
+{
+    pub fn new() -> Self {
+        Self {
+            vertices: Vec::new(),
+            indices: Vec::new(),
+            meshes: 0,
+            buffers: None,
+        }
+    }
+
+    /// Add a non-indexed mesh, returning its ranges in the arena.
+    pub fn add(&mut self, vertices: &[T]) -> Mesh {
+        let start = self.vertices.len() as u32;
+
+        self.vertices.extend_from_slice(vertices);
+        self.meshes += 1;
+        self.buffers = None;
+
+        Mesh {
+            vertices: start..self.vertices.len() as u32,
+            indices: None,
+        }
+    }
+
+    /// Add an indexed mesh, returning its ranges in the arena. Indices
+    /// refer to the given vertices, starting from zero.
+    pub fn add_indexed(&mut self, vertices: &[T], indices: &[u16]) -> Mesh {
+        assert!(
+            indices.iter().all(|i| (*i as usize) < vertices.len()),
+            "fatal: mesh index out of bounds for {} vertices",
+            vertices.len()
+        );
+        let start = self.indices.len() as u32;
+        let mut mesh = self.add(vertices);
+
+        self.indices.extend_from_slice(indices);
+        mesh.indices = Some(start..self.indices.len() as u32);
+        mesh
+    }
+
+    /// Create the arena's buffers, if meshes were added since the last
+    /// upload.
+    pub fn upload(&mut self, renderer: &Renderer) {
+        if self.buffers.is_some() || self.vertices.is_empty() {
+            return;
+        }
+        let indices = if self.indices.is_empty() {
+            None
+        } else {
+            Some(renderer.device.create_index(self.indices.as_slice()))
+        };
+        self.buffers = Some((renderer.vertex_buffer(self.vertices.as_slice()), indices));
+    }
+
+    /// The vertex buffer holding the vertices of all meshes.
+    ///
+    /// Panics if the arena wasn't uploaded since meshes were last added.
+    pub fn vertex_buffer(&self) -> &VertexBuffer {
+        &self.buffers().0
+    }
+
+    /// The index buffer holding the indices of all indexed meshes, if any.
+    ///
+    /// Panics if the arena wasn't uploaded since meshes were last added.
+    pub fn index_buffer(&self) -> Option<&IndexBuffer> {
+        self.buffers().1.as_ref()
+    }
+
+    /// Remove all meshes. Previously returned meshes are invalidated.
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.meshes = 0;
+        self.buffers = None;
+    }
+
+    /// The number of meshes in the arena.
+    pub fn len(&self) -> usize {
+        self.meshes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.meshes == 0
+    }
+
+    fn buffers(&self) -> &(VertexBuffer, Option<IndexBuffer>) {
+        self.buffers
+            .as_ref()
+            .expect("fatal: mesh arena must be uploaded before drawing")
+    }
+}
+
+impl<T> Default for MeshArena<T>
+where
+    T: 'static + Copy,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexFormat {
+    Float,
+    Float2,
+    Float3,
+    Float4,
+    /// Four bytes, normalized to `[0, 1]` floats, eg. a color.
+    UByte4,
+    /// An unsigned integer, eg. flags.
+    Uint,
+    /// A signed integer.
+    Int,
+}
+
+impl VertexFormat {
+    // TODO: Use `const fn`
+    fn bytesize(self) -> usize {
+        match self {
+            Self::Float => 4,
+            Self::Float2 => 8,
+            Self::Float3 => 12,
+            Self::Float4 => 16,
+            Self::UByte4 => 4,
+            Self::Uint => 4,
+            Self::Int => 4,
+        }
+    }
+    // TODO: Use `const fn`
+    fn to_wgpu(self) -> wgpu::VertexFormat {
+        match self {
+            Self::Float => wgpu::VertexFormat::Float,
+            Self::Float2 => wgpu::VertexFormat::Float2,
+            Self::Float3 => wgpu::VertexFormat::Float3,
+            Self::Float4 => wgpu::VertexFormat::Float4,
+            Self::UByte4 => wgpu::VertexFormat::Uchar4Norm,
+            Self::Uint => wgpu::VertexFormat::Uint,
+            Self::Int => wgpu::VertexFormat::Int,
+        }
+    }
+}
+
+/// Describes a 'VertexBuffer' layout.
+#[derive(Default)]
+pub struct VertexLayout {
+    wgpu_attrs: Vec<wgpu::VertexAttributeDescriptor>,
+    size: usize,
+    instanced: bool,
+}
+
+impl VertexLayout {
+    /// Describe a buffer of per-instance records, with fields of mixed
+    /// formats read at explicit shader locations. Fields are laid out in
+    /// order, without padding, as in a `#[repr(C)]` struct of 4-byte
+    /// aligned fields.
+    ///
+    /// The locations must not overlap those of the pipeline's vertex
+    /// layout, which are assigned sequentially from zero, unless the vertex
+    /// layout was described with [`VertexLayout::with_locations`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::{VertexFormat, VertexLayout};
+    ///
+    /// let layout = VertexLayout::instance(&[
+    ///     (4, VertexFormat::Float2), // Position
+    ///     (5, VertexFormat::UByte4), // Color
+    ///     (7, VertexFormat::Uint),   // Flags
+    /// ]);
+    /// assert_eq!(layout.size(), 16);
+    /// ```
+    pub fn instance(fields: &[(u32, VertexFormat)]) -> Self {
+        Self {
+            instanced: true,
+            ..Self::with_locations(fields)
+        }
+    }
+
+    /// Describe a vertex buffer whose attributes are read at explicit shader
+    /// locations, eg. for shaders whose locations don't start at zero, or
+    /// have gaps. Attributes are laid out in order, without padding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::{VertexFormat, VertexLayout};
+    ///
+    /// let layout = VertexLayout::with_locations(&[
+    ///     (0, VertexFormat::Float2), // Position
+    ///     (2, VertexFormat::Float2), // UV
+    /// ]);
+    /// assert_eq!(layout.size(), 16);
+    /// ```
+    pub fn with_locations(fields: &[(u32, VertexFormat)]) -> Self {
+        let mut vl = Self::default();
+        for (location, vf) in fields {
+            assert!(
+                vl.wgpu_attrs.iter().all(|a| a.shader_location != *location),
+                "fatal: shader location {} is used more than once",
+                location
+            );
+            vl.wgpu_attrs.push(wgpu::VertexAttributeDescriptor {
+                shader_location: *location,
+                offset: vl.size as wgpu::BufferAddress,
+                format: vf.to_wgpu(),
+            });
+            vl.size += vf.bytesize();
+        }
+        vl
+    }
+
+    /// The size of one vertex, or instance record, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn from(formats: &[VertexFormat]) -> Self {
+        let mut vl = Self::default();
+        for vf in formats {
+            vl.wgpu_attrs.push(wgpu::VertexAttributeDescriptor {
+                shader_location: vl.wgpu_attrs.len() as u32,
+                offset: vl.size as wgpu::BufferAddress,
+                format: vf.to_wgpu(),
+            });
+            vl.size += vf.bytesize();
+        }
+        vl
+    }
+
+    fn to_wgpu(&self) -> wgpu::VertexBufferDescriptor {
+        wgpu::VertexBufferDescriptor {
+            stride: self.size as wgpu::BufferAddress,
+            step_mode: if self.instanced {
+                wgpu::InputStepMode::Instance
+            } else {
+                wgpu::InputStepMode::Vertex
+            },
+            attributes: self.wgpu_attrs.as_slice(),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Pipeline Bindings
+///////////////////////////////////////////////////////////////////////////////
+
+/// A binding type.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BindingType {
+    UniformBuffer,
+    UniformBufferDynamic,
+    Sampler,
+    SampledTexture,
+    /// A texture array, eg. of sprite sheets, sampled in shaders as a
+    /// `texture2DArray`.
+    SampledTextureArray,
+    /// A cube texture, eg. an environment map, sampled in shaders as a
+    /// `textureCube`.
+    SampledCubeTexture,
+}
+
+impl BindingType {
+    fn to_wgpu(self) -> wgpu::BindingType {
+        match self {
+            BindingType::UniformBuffer => wgpu::BindingType::UniformBuffer { dynamic: false },
+            BindingType::UniformBufferDynamic => wgpu::BindingType::UniformBuffer { dynamic: true },
+            BindingType::SampledTexture => wgpu::BindingType::SampledTexture {
+                multisampled: false,
+                dimension: wgpu::TextureViewDimension::D2,
+            },
+            BindingType::SampledTextureArray => wgpu::BindingType::SampledTexture {
+                multisampled: false,
+                dimension: wgpu::TextureViewDimension::D2Array,
+            },
+            BindingType::SampledCubeTexture => wgpu::BindingType::SampledTexture {
+                multisampled: false,
+                dimension: wgpu::TextureViewDimension::Cube,
+            },
+            BindingType::Sampler => wgpu::BindingType::Sampler,
+        }
+    }
+}
+
+pub struct Binding {
+    pub binding: BindingType,
+    pub stage: ShaderStage,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Pipeline
+///////////////////////////////////////////////////////////////////////////////
+
+pub struct Pipeline {
+    wgpu: wgpu::RenderPipeline,
+
+    pub layout: PipelineLayout,
+    pub vertex_layout: VertexLayout,
+    /// The layout of per-instance records, for pipelines created with
+    /// [`Renderer::instanced_pipeline_from_sources`].
+    pub instance_layout: Option<VertexLayout>,
+    pub bindings: Vec<ShaderBinding>,
+
+    format: wgpu::TextureFormat,
+    sample_count: u32,
+    depth: Option<DepthState>,
+}
+
+impl Pipeline {
+    /// Start building a binding group for the given set, binding resources
+    /// by the names declared in the pipeline's shaders.
+    pub fn binding_group(&self, set: usize) -> BindingGroupBuilder<'_> {
+        let layout = &self.layout.sets[set];
+
+        BindingGroupBuilder {
+            pipeline: self,
+            layout,
+            binds: vec![None; layout.size],
+            error: None,
+        }
+    }
+}
+
+impl<'a> AbstractPipeline<'a> for Pipeline {
+    type PrepareContext = ();
+    type Uniforms = ();
+
+    fn description() -> PipelineDescription<'a> {
+        PipelineDescription {
+            vertex_layout: &[],
+            pipeline_layout: &[],
+            vertex_shader: &[],
+            fragment_shader: &[],
+        }
+    }
+
+    fn setup(pipeline: Self, _dev: &Device, _w: u32, _h: u32) -> Self {
+        pipeline
+    }
+
+    fn apply(&self, pass: &mut Pass) {
+        pass.apply_pipeline(self);
+    }
+
+    fn width(&self) -> u32 {
+        unimplemented!()
+    }
+
+    fn height(&self) -> u32 {
+        unimplemented!()
+    }
+
+    fn resize(&mut self, _w: u32, _h: u32) {}
+
+    fn prepare(&'a self, _unused: ()) -> Option<(&'a UniformBuffer, Vec<()>)> {
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Blending {
+    src_factor: BlendFactor,
+    dst_factor: BlendFactor,
+    operation: BlendOp,
+    /// Blending of the alpha channel, if different from the color channels.
+    alpha: Option<(BlendFactor, BlendFactor, BlendOp)>,
+}
+
+impl Blending {
+    pub fn new(src_factor: BlendFactor, dst_factor: BlendFactor, operation: BlendOp) -> Self {
+        Blending {
+            src_factor,
+            dst_factor,
+            operation,
+            alpha: None,
+        }
+    }
+
+    /// Set the blending of the color channels, and of the alpha channel,
+    /// unless set separately with [`Blending::with_alpha`].
+    pub fn with_color(self, src_factor: BlendFactor, dst_factor: BlendFactor, op: BlendOp) -> Self {
+        Self {
+            src_factor,
+            dst_factor,
+            operation: op,
+            ..self
+        }
+    }
+
+    /// Blend the alpha channel separately from the color channels.
+    pub fn with_alpha(self, src_factor: BlendFactor, dst_factor: BlendFactor, op: BlendOp) -> Self {
+        Self {
+            alpha: Some((src_factor, dst_factor, op)),
+            ..self
+        }
+    }
+
+    /// Blending of sources with premultiplied alpha.
+    pub fn premultiplied() -> Self {
+        Self::new(
+            BlendFactor::One,
+            BlendFactor::OneMinusSrcAlpha,
+            BlendOp::Add,
+        )
+    }
+
+    /// Blending of sources with straight alpha into a target with
+    /// premultiplied alpha, eg. the swap chain of a transparent window.
+    /// Unlike the default blending, the target's alpha accumulates coverage,
+    /// so that the platform compositor can blend the window correctly.
+    ///
+    /// The swap chain alpha mode is chosen by the platform: the window must
+    /// be created as transparent, and the target cleared with a
+    /// premultiplied color, eg. [`Rgba::TRANSPARENT`].
+    pub fn translucent() -> Self {
+        Self::default().with_alpha(
+            BlendFactor::One,
+            BlendFactor::OneMinusSrcAlpha,
+            BlendOp::Add,
+        )
+    }
+
+    /// Additive blending, eg. for accumulating overdraw into a heat map.
+    pub fn additive() -> Self {
+        Self::new(BlendFactor::One, BlendFactor::One, BlendOp::Add)
+    }
+
+    /// Multiply the target by the source, darkening it. Sources should have
+    /// premultiplied alpha, or be opaque.
+    pub fn multiply() -> Self {
+        Self::new(
+            BlendFactor::DstColor,
+            BlendFactor::OneMinusSrcAlpha,
+            BlendOp::Add,
+        )
+        .with_alpha(
+            BlendFactor::One,
+            BlendFactor::OneMinusSrcAlpha,
+            BlendOp::Add,
+        )
+    }
+
+    /// Multiply the inverse of the target by the inverse of the source,
+    /// lightening it. Sources should have premultiplied alpha, or be opaque.
+    pub fn screen() -> Self {
+        Self::new(
+            BlendFactor::One,
+            BlendFactor::OneMinusSrcColor,
+            BlendOp::Add,
+        )
+        .with_alpha(
+            BlendFactor::One,
+            BlendFactor::OneMinusSrcAlpha,
+            BlendOp::Add,
+        )
+    }
+
+    /// Replace the target with the source, including its alpha.
+    pub fn replace() -> Self {
+        Self::constant()
+    }
+
+    pub fn constant() -> Self {
+        Blending {
+            src_factor: BlendFactor::One,
+            dst_factor: BlendFactor::Zero,
+            operation: BlendOp::Add,
+            alpha: None,
+        }
+    }
+
+    /// Return the color and alpha blend descriptors.
+    fn to_wgpu(&self) -> (wgpu::BlendDescriptor, wgpu::BlendDescriptor) {
+        let color = wgpu::BlendDescriptor {
+            src_factor: self.src_factor.to_wgpu(),
+            dst_factor: self.dst_factor.to_wgpu(),
+            operation: self.operation.to_wgpu(),
+        };
+        let alpha = match self.alpha {
+            Some((src, dst, op)) => wgpu::BlendDescriptor {
+                src_factor: src.to_wgpu(),
+                dst_factor: dst.to_wgpu(),
+                operation: op.to_wgpu(),
+            },
+            None => color.clone(),
+        };
+        (color, alpha)
+    }
+}
+
+impl Default for Blending {
+    fn default() -> Self {
+        Blending {
+            src_factor: BlendFactor::SrcAlpha,
+            dst_factor: BlendFactor::OneMinusSrcAlpha,
+            operation: BlendOp::Add,
+            alpha: None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlendFactor {
+    One,
+    Zero,
+    SrcColor,
+    OneMinusSrcColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstColor,
+    OneMinusDstColor,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+
+impl BlendFactor {
+    fn to_wgpu(&self) -> wgpu::BlendFactor {
+        match self {
+            Self::SrcColor => wgpu::BlendFactor::SrcColor,
+            Self::OneMinusSrcColor => wgpu::BlendFactor::OneMinusSrcColor,
+            Self::SrcAlpha => wgpu::BlendFactor::SrcAlpha,
+            Self::OneMinusSrcAlpha => wgpu::BlendFactor::OneMinusSrcAlpha,
+            Self::DstColor => wgpu::BlendFactor::DstColor,
+            Self::OneMinusDstColor => wgpu::BlendFactor::OneMinusDstColor,
+            Self::DstAlpha => wgpu::BlendFactor::DstAlpha,
+            Self::OneMinusDstAlpha => wgpu::BlendFactor::OneMinusDstAlpha,
+            Self::One => wgpu::BlendFactor::One,
+            Self::Zero => wgpu::BlendFactor::Zero,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BlendOp {
+    Add,
+    Subtract,
+    ReverseSubtract,
+    Min,
+    Max,
+}
+
+impl BlendOp {
+    fn to_wgpu(&self) -> wgpu::BlendOperation {
+        match self {
+            Self::Add => wgpu::BlendOperation::Add,
+            Self::Subtract => wgpu::BlendOperation::Subtract,
+            Self::ReverseSubtract => wgpu::BlendOperation::ReverseSubtract,
+            Self::Min => wgpu::BlendOperation::Min,
+            Self::Max => wgpu::BlendOperation::Max,
+        }
+    }
+}
+
+pub struct Set<'a>(pub &'a [Binding]);
+
+pub struct PipelineLayout {
+    pub sets: Vec<BindingGroupLayout>,
+}
+
+pub trait AbstractPipeline<'a> {
+    type PrepareContext;
+    type Uniforms: Copy + 'static;
+
+    fn description() -> PipelineDescription<'a>;
+    fn setup(pip: Pipeline, dev: &Device, w: u32, h: u32) -> Self;
+    fn apply(&self, pass: &mut Pass);
+    fn resize(&mut self, w: u32, h: u32);
+    fn width(&self) -> u32;
+    fn height(&self) -> u32;
+    /// Return the uniform buffer of the pipeline along with the records to
+    /// upload to it, if any.
+    fn prepare(
+        &'a self,
+        t: Self::PrepareContext,
+    ) -> Option<(&'a UniformBuffer, Vec<Self::Uniforms>)>;
+
+    /// Like [`AbstractPipeline::prepare`], without allocating for a single
+    /// record. This is what the renderer calls on every frame, so pipelines
+    /// updated on every frame should override it. By default, it calls
+    /// `prepare`.
+    fn uniforms(
+        &'a self,
+        t: Self::PrepareContext,
+    ) -> Option<(&'a UniformBuffer, SmallVec<[Self::Uniforms; 1]>)> {
+        self.prepare(t)
+            .map(|(buf, unifs)| (buf, SmallVec::from_vec(unifs)))
+    }
+
+    /// Set the ratio of physical to logical pixels. Pipelines with a
+    /// projection use it to map logical coordinates to the target.
+    fn set_scale_factor(&mut self, _factor: f64) {}
+}
+
+pub struct PipelineDescription<'a> {
+    pub vertex_layout: &'a [VertexFormat],
+    pub pipeline_layout: &'a [Set<'a>],
+    pub vertex_shader: &'static [u8],
+    pub fragment_shader: &'static [u8],
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Frame
+///////////////////////////////////////////////////////////////////////////////
+
+pub struct Frame {
+    encoder: wgpu::CommandEncoder,
+    draw_budget: Option<u32>,
+    trace: Option<Trace>,
+    graph: FrameGraph,
+    arena: FrameArena,
+    started: Instant,
+    /// Cleared read lists of earlier frames, reused by the passes of this one.
+    reads: Vec<Vec<usize>>,
+}
+
+impl Frame {
+    pub fn new(encoder: wgpu::CommandEncoder) -> Self {
+        Self::with_arena(encoder, FrameArena::new())
+    }
+
+    fn with_arena(encoder: wgpu::CommandEncoder, arena: FrameArena) -> Self {
+        Self {
+            encoder,
+            draw_budget: None,
+            trace: None,
+            graph: FrameGraph::default(),
+            arena,
+            started: Instant::now(),
+            reads: Vec::new(),
+        }
+    }
+
+    /// Return a handle to the frame's arena, which keeps resources created
+    /// while recording the frame alive until it is submitted.
+    ///
+    /// Since the handle doesn't borrow the frame, resources kept in it can be
+    /// drawn in passes of the frame:
+    ///
+    /// ```ignore
+    /// let arena = frame.arena();
+    /// let mut pass = frame.pass(PassOp::Load(), &out);
+    ///
+    /// let buffer = arena.keep(batch.finish(&renderer));
+    /// pass.draw(buffer, &binding);
+    /// ```
+    pub fn arena(&self) -> FrameArena {
+        self.arena.clone()
+    }
+
+    pub fn pass<T: TextureView + ?Sized>(&mut self, op: PassOp, view: &T) -> Pass {
+        self.begin_pass(op, view, None)
+    }
+
+    /// Start a pass with a depth buffer, for pipelines created with a
+    /// [`DepthState`]. The depth buffer is cleared to `1.0` and the stencil
+    /// to `0` unless `op` loads them, or clears them to other values with
+    /// [`PassOp::ClearDepthStencil`].
+    pub fn pass_with_depth<T: TextureView + ?Sized>(
+        &mut self,
+        op: PassOp,
+        view: &T,
+        depth: &DepthBuffer,
+    ) -> Pass<'_> {
+        self.begin_pass(op, view, Some(depth))
+    }
+
+    fn begin_pass<T: TextureView + ?Sized>(
+        &mut self,
+        op: PassOp,
+        view: &T,
+        depth: Option<&DepthBuffer>,
+    ) -> Pass<'_> {
+        let mut pass = Pass::begin_with_depth(&mut self.encoder, view, depth, op);
+        pass.draw_budget = self.draw_budget;
+
+        self.graph.passes.push(PassNode {
+            op,
+            target: view.id(),
+            format: view.format(),
+            reads: self.reads.pop().unwrap_or_default(),
+            stats: PassStats::default(),
+        });
+        pass.node = self.graph.passes.last_mut();
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.events.push(TraceEvent::Pass(op));
+            pass.trace = Some(trace);
+        }
+        pass
+    }
+
+    /// Set the maximum number of draw calls allowed in each pass of this frame.
+    /// In debug builds, exceeding the budget panics.
+    pub fn set_draw_budget(&mut self, draws: Option<u32>) {
+        self.draw_budget = draws;
+    }
+
+    pub fn copy(&mut self, src: &UniformBuffer, dst: &UniformBuffer) {
+        self.encoder.copy_buffer_to_buffer(
+            &src.wgpu,
+            0,
+            &dst.wgpu,
+            0,
+            (src.size * src.count) as wgpu::BufferAddress,
+        );
+    }
+
+    /// Copy the whole of `src` into `dst`. Both textures must have the same
+    /// size and format.
+    pub fn copy_texture(&mut self, src: &Texture, dst: &Texture) {
+        assert_eq!(
+            (src.w, src.h, src.format),
+            (dst.w, dst.h, dst.format),
+            "fatal: source and destination textures must be of the same size and format"
+        );
+        let origin = wgpu::Origin3d {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+        };
+
+        self.encoder.copy_texture_to_texture(
+            wgpu::TextureCopyView {
+                texture: &src.wgpu,
+                mip_level: 0,
+                array_layer: 0,
+                origin,
+            },
+            wgpu::TextureCopyView {
+                texture: &dst.wgpu,
+                mip_level: 0,
+                array_layer: 0,
+                origin,
+            },
+            src.extent,
+        );
+    }
+}
+
+/// Append-only storage for per-frame resources, such as vertex buffers and
+/// binding groups. Handles are cheap to clone, and resources are dropped
+/// when the last handle is, ie. after the frame is submitted.
+///
+/// ```
+/// use rgx::core::FrameArena;
+///
+/// let arena = FrameArena::new();
+/// let a = arena.keep(vec![1, 2, 3]);
+/// let b = arena.keep(String::from("rgx"));
+///
+/// assert_eq!((a.len(), b.as_str()), (3, "rgx"));
+/// assert_eq!(arena.len(), 2);
+/// ```
+#[derive(Clone, Default)]
+pub struct FrameArena {
+    resources: Rc<RefCell<Vec<Box<dyn Any>>>>,
+}
+
+impl FrameArena {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep a resource alive for as long as the arena, and return a reference
+    /// to it.
+    pub fn keep<T: 'static>(&self, resource: T) -> &T {
+        let boxed = Box::new(resource);
+        let ptr: *const T = &*boxed;
+
+        self.resources.borrow_mut().push(boxed);
+
+        // Safety: resources are boxed, so they don't move when the storage
+        // grows, and they are never removed while a handle to the arena is
+        // borrowed.
+        unsafe { &*ptr }
+    }
+
+    pub fn len(&self) -> usize {
+        self.resources.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.resources.borrow().is_empty()
+    }
+
+    /// Drop the resources kept in the arena, keeping its storage for the
+    /// next frame, unless a handle to it is still held.
+    fn reset(self) -> Option<Self> {
+        if Rc::strong_count(&self.resources) > 1 {
+            return None;
+        }
+        self.resources.borrow_mut().clear();
+
+        Some(self)
+    }
+}
+
+/// Holds dropped resources, such as textures and buffers, until the GPU
+/// frames which may reference them have completed. Resources are retired
+/// with the index of the next submission, and dropped once that submission
+/// has completed.
+///
+/// Each [`Renderer`] has a queue, see [`Renderer::retire`]. The renderer
+/// signals every submission as [`Renderer::submit_with_signal`] does, and
+/// collects the queue as submissions complete.
+///
+/// ```
+/// use rgx::core::DeletionQueue;
+/// use std::rc::Rc;
+///
+/// let texture = Rc::new(());
+/// let mut queue = DeletionQueue::new();
+///
+/// queue.retire(2, texture.clone());
+/// assert_eq!(queue.collect(1), 0);
+/// assert_eq!(Rc::strong_count(&texture), 2);
+///
+/// assert_eq!(queue.collect(2), 1);
+/// assert_eq!(Rc::strong_count(&texture), 1);
+/// assert!(queue.is_empty());
+/// ```
+#[derive(Default)]
+pub struct DeletionQueue {
+    retired: std::collections::VecDeque<(u64, Box<dyn Any>)>,
+}
+
+impl DeletionQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep a resource alive until the submission with the given index has
+    /// completed.
+    pub fn retire<T: 'static>(&mut self, submission: u64, resource: T) {
+        // Submission indices only grow, so the queue stays sorted unless
+        // resources are retired with older indices.
+        let at = self
+            .retired
+            .iter()
+            .rposition(|(s, _)| *s <= submission)
+            .map_or(0, |i| i + 1);
+
+        self.retired.insert(at, (submission, Box::new(resource)));
+    }
+
+    /// Drop the resources of completed submissions, given the index of the
+    /// last submission completed. Returns the number of resources dropped.
+    pub fn collect(&mut self, completed: u64) -> usize {
+        let mut dropped = 0;
+
+        while let Some((s, _)) = self.retired.front() {
+            if *s > completed {
+                break;
+            }
+            self.retired.pop_front();
+            dropped += 1;
+        }
+        dropped
+    }
+
+    /// The number of resources waiting to be dropped.
+    pub fn len(&self) -> usize {
+        self.retired.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.retired.is_empty()
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// FrameBuilder
+///////////////////////////////////////////////////////////////////////////////
+
+/// A deferred upload of a range of the builder's upload data, encoded when
+/// the frame is submitted.
+type Upload<'a> = (&'a UniformBuffer, Range<usize>);
+
+/// A deferred pass command, encoded when the frame is submitted.
+type Command<'a> = Box<dyn Fn(&mut Pass) + 'a>;
+
+/// A frame declared as data: uploads, then passes and their draws, which are
+/// only encoded on [`FrameBuilder::submit`].
+///
+/// Since nothing is encoded until then, resources only have to outlive the
+/// builder, and can be created in any order while the frame is declared.
+///
+/// ```ignore
+/// let mut f = FrameBuilder::new();
+///
+/// f.update(&pipeline, transform);
+/// f.pass(&texture)
+///     .clear(Rgba::TRANSPARENT)
+///     .pipeline(&pipeline)
+///     .draw(&buffer, &binding);
+/// f.submit(&mut renderer);
+/// ```
+#[derive(Default)]
+pub struct FrameBuilder<'a> {
+    uploads: Vec<Upload<'a>>,
+    /// The records of all uploads, as bytes.
+    data: Vec<u8>,
+    ops: Vec<Op<'a>>,
+    passes: Vec<PassBuilder<'a>>,
+}
+
+impl<'a> FrameBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a texture operation, encoded before any pass.
+    pub fn op(&mut self, op: Op<'a>) -> &mut Self {
+        self.ops.push(op);
+        self
+    }
+
+    /// Update the uniforms of a pipeline, before any pass.
+    pub fn update<T>(&mut self, pipeline: &'a T, ctx: T::PrepareContext) -> &mut Self
+    where
+        T: AbstractPipeline<'a>,
+    {
+        if let Some((buf, unifs)) = pipeline.uniforms(ctx) {
+            self.push(buf, unifs.as_slice());
+        }
+        self
+    }
+
+    /// Update a uniform buffer, before any pass.
+    pub fn upload<T>(&mut self, buf: &'a UniformBuffer, data: Vec<T>) -> &mut Self
+    where
+        T: 'static + Copy,
+    {
+        self.push(buf, data.as_slice());
+        self
+    }
+
+    fn push<T: 'static + Copy>(&mut self, buf: &'a UniformBuffer, records: &[T]) {
+        let start = self.data.len();
+
+        // Safety: records are plain data, which is uploaded byte for byte,
+        // like wgpu does when filling a mapped buffer.
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                records.as_ptr() as *const u8,
+                std::mem::size_of_val(records),
+            )
+        };
+        self.data.extend_from_slice(bytes);
+        self.uploads.push((buf, start..self.data.len()));
+    }
+
+    /// Declare a pass rendering to `target`. The pass loads the existing
+    /// contents of the target, unless [`PassBuilder::clear`] is called.
+    pub fn pass<T: TextureView>(&mut self, target: &'a T) -> &mut PassBuilder<'a> {
+        self.passes.push(PassBuilder {
+            target,
+            op: PassOp::Load(),
+            commands: Vec::new(),
+        });
+        self.passes.last_mut().unwrap()
+    }
+
+    /// Encode the frame and submit it.
+    pub fn submit(self, renderer: &mut Renderer) {
+        let mut frame = renderer.frame();
+
+        for op in self.ops.iter() {
+            if let Some(trace) = frame.trace.as_mut() {
+                trace.events.push(TraceEvent::from(op));
+            }
+            op.encode(&mut renderer.device, &mut frame.encoder);
+        }
+        for (buf, range) in self.uploads {
+            renderer
+                .device
+                .update_uniform_buffer(&self.data[range], buf, &mut frame.encoder);
+        }
+        for p in self.passes.iter() {
+            let mut pass = frame.pass(p.op, p.target);
+
+            for cmd in p.commands.iter() {
+                cmd(&mut pass);
+            }
+        }
+        renderer.submit(frame);
+    }
+}
+
+/// A pass declared with [`FrameBuilder::pass`].
+pub struct PassBuilder<'a> {
+    target: &'a dyn TextureView,
+    op: PassOp,
+    commands: Vec<Command<'a>>,
+}
+
+impl<'a> PassBuilder<'a> {
+    /// Clear the target to `color` at the start of the pass.
+    pub fn clear(&mut self, color: Rgba) -> &mut Self {
+        self.op = PassOp::Clear(color);
+        self
+    }
+
+    /// Initialize the target with the given operation.
+    pub fn op(&mut self, op: PassOp) -> &mut Self {
+        self.op = op;
+        self
+    }
+
+    pub fn pipeline<T>(&mut self, pipeline: &'a T) -> &mut Self
+    where
+        T: AbstractPipeline<'a>,
+    {
+        self.commands
+            .push(Box::new(move |pass| pipeline.apply(pass)));
+        self
+    }
+
+    pub fn binding(&mut self, group: &'a BindingGroup) -> &mut Self {
+        self.commands
+            .push(Box::new(move |pass| pass.set_binding(group, &[])));
+        self
+    }
+
+    pub fn draw<T: Draw>(&mut self, drawable: &'a T, binding: &'a BindingGroup) -> &mut Self {
+        self.commands
+            .push(Box::new(move |pass| pass.draw(drawable, binding)));
+        self
+    }
+
+    pub fn draw_buffer(&mut self, buf: &'a VertexBuffer) -> &mut Self {
+        self.commands
+            .push(Box::new(move |pass| pass.draw_buffer(buf)));
+        self
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Pass
+///////////////////////////////////////////////////////////////////////////////
+
+pub struct Pass<'a> {
+    wgpu: wgpu::RenderPass<'a>,
+
+    format: Option<TextureFormat>,
+    sample_count: u32,
+    depth: bool,
+    stride: Option<u64>,
+    instance_stride: Option<u64>,
+    stats: PassStats,
+    draw_budget: Option<u32>,
+    trace: Option<&'a mut Trace>,
+    node: Option<&'a mut PassNode>,
+}
+
+/// Draw statistics of a single pass, useful for finding batching problems.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PassStats {
+    /// Number of draw calls issued.
+    pub draws: u32,
+    /// Number of vertices (or indices) drawn, over all instances.
+    pub vertices: u64,
+    /// Number of binding groups set, eg. texture switches between sprite
+    /// batches.
+    pub bindings: u32,
+}
+
+impl<'a> Pass<'a> {
+    /// Begin a pass on a raw view. Since the format of the view isn't known,
+    /// pipelines aren't validated against it; see [`Pass::begin_view`].
+    pub fn begin(
+        encoder: &'a mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        op: PassOp,
+    ) -> Self {
+        Self::begin_attachment(encoder, view, None, 1, None, op)
+    }
+
+    /// Begin a pass on a [`TextureView`], validating pipelines against its
+    /// format and sample count in debug builds.
+    pub fn begin_view<T: TextureView + ?Sized>(
+        encoder: &'a mut wgpu::CommandEncoder,
+        view: &T,
+        op: PassOp,
+    ) -> Self {
+        Self::begin_with_depth(encoder, view, None, op)
+    }
+
+    pub fn begin_with_depth<T: TextureView + ?Sized>(
+        encoder: &'a mut wgpu::CommandEncoder,
+        view: &T,
+        depth: Option<&DepthBuffer>,
+        op: PassOp,
+    ) -> Self {
+        Self::begin_attachment(
+            encoder,
+            view.texture_view(),
+            view.format(),
+            view.sample_count(),
+            depth,
+            op,
+        )
+    }
+
+    fn begin_attachment(
+        encoder: &'a mut wgpu::CommandEncoder,
+        view: &wgpu::TextureView,
+        format: Option<TextureFormat>,
+        sample_count: u32,
+        depth: Option<&DepthBuffer>,
+        op: PassOp,
     ) -> Self {
+        // Depth and stencil are cleared along with the color attachment,
+        // unless `ClearDepthStencil` leaves them out.
+        let load_op = |cleared: bool| match op {
+            PassOp::Load() => wgpu::LoadOp::Load,
+            PassOp::ClearDepthStencil(_, _, _) if !cleared => wgpu::LoadOp::Load,
+            _ => wgpu::LoadOp::Clear,
+        };
         let pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[wgpu::RenderPassColorAttachmentDescriptor {
-                attachment: &view,
+                attachment: view,
                 load_op: op.to_wgpu(),
                 store_op: wgpu::StoreOp::Store,
-                clear_color: match op {
-                    PassOp::Clear(color) => color.to_wgpu(),
-                    PassOp::Load() => Rgba::TRANSPARENT.to_wgpu(),
+                clear_color: op.clear_color().to_wgpu(),
+                resolve_target: None,
+            }],
+            depth_stencil_attachment: depth.map(|d| {
+                wgpu::RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: &*d.view,
+                    depth_load_op: load_op(op.depth().is_some()),
+                    depth_store_op: wgpu::StoreOp::Store,
+                    clear_depth: op.depth().unwrap_or(1.0),
+                    stencil_load_op: load_op(op.stencil().is_some()),
+                    stencil_store_op: wgpu::StoreOp::Store,
+                    clear_stencil: op.stencil().unwrap_or(0),
+                }
+            }),
+        });
+        Pass {
+            wgpu: pass,
+            format,
+            sample_count,
+            depth: depth.is_some(),
+            stride: None,
+            instance_stride: None,
+            stats: PassStats::default(),
+            draw_budget: None,
+            trace: None,
+            node: None,
+        }
+    }
+    pub fn set_pipeline<T>(&mut self, pipeline: &T)
+    where
+        T: AbstractPipeline<'a>,
+    {
+        pipeline.apply(self);
+    }
+    pub fn set_binding(&mut self, group: &BindingGroup, offsets: &[u64]) {
+        self.trace(|| TraceEvent::Binding(group.set_index, offsets.to_vec()));
+        self.stats.bindings += 1;
+
+        if let Some(node) = self.node.as_mut() {
+            for v in group.views.iter() {
+                if !node.reads.contains(v) {
+                    node.reads.push(*v);
+                }
+            }
+            node.stats = self.stats;
+        }
+        self.wgpu
+            .set_bind_group(group.set_index, &group.wgpu, offsets);
+    }
+    pub fn set_index_buffer(&mut self, index_buf: &IndexBuffer) {
+        self.wgpu.set_index_buffer(&index_buf.wgpu, 0)
+    }
+    pub fn set_vertex_buffer(&mut self, vertex_buf: &VertexBuffer) {
+        self.wgpu.set_vertex_buffers(0, &[(&vertex_buf.wgpu, 0)])
+    }
+    pub fn draw<T: Draw>(&mut self, drawable: &T, binding: &BindingGroup) {
+        drawable.draw(binding, self);
+    }
+    pub fn draw_buffer(&mut self, buf: &VertexBuffer) {
+        self.draw_buffer_range(buf, 0..buf.size);
+    }
+    pub fn draw_buffer_range(&mut self, buf: &VertexBuffer, range: Range<u32>) {
+        if cfg!(debug_assertions) {
+            self.validate_buffer(buf, &range);
+        }
+        self.set_vertex_buffer(buf);
+        self.record(range.end - range.start, 1);
+        self.trace(|| TraceEvent::Draw(range.clone(), 0..1));
+        self.wgpu.draw(range, 0..1);
+    }
+    /// Draw the buffer as a single instance with the given index, which is
+    /// available in the vertex shader as `gl_InstanceIndex`. This can be used
+    /// to select a per-draw record from a [`UniformArray`].
+    pub fn draw_buffer_instance(&mut self, buf: &VertexBuffer, instance: u32) {
+        let (range, instances) = (0..buf.size, instance..instance + 1);
+
+        if cfg!(debug_assertions) {
+            self.validate_buffer(buf, &range);
+        }
+        self.set_vertex_buffer(buf);
+        self.record(buf.size, 1);
+        self.trace(|| TraceEvent::Draw(range.clone(), instances.clone()));
+        self.wgpu.draw(range, instances);
+    }
+    /// Draw the buffer once for every record of `instances`, with a pipeline
+    /// created with [`Renderer::instanced_pipeline_from_sources`].
+    pub fn draw_instanced(&mut self, buf: &VertexBuffer, instances: &VertexBuffer) {
+        let range = 0..buf.size;
+
+        if cfg!(debug_assertions) {
+            self.validate_buffer(buf, &range);
+
+            let stride = self
+                .instance_stride
+                .expect("fatal: the pipeline has no instance layout");
+            assert_eq!(
+                instances.bytesize % stride,
+                0,
+                "fatal: instance buffer size ({} bytes) is not a multiple of the pipeline instance stride ({})",
+                instances.bytesize,
+                stride
+            );
+        }
+        self.wgpu
+            .set_vertex_buffers(0, &[(&buf.wgpu, 0), (&instances.wgpu, 0)]);
+        self.record(buf.size, instances.size);
+        self.trace(|| TraceEvent::Draw(range.clone(), 0..instances.size));
+        self.wgpu.draw(range, 0..instances.size);
+    }
+    /// Draw a mesh of the given arena, which must have been uploaded.
+    pub fn draw_mesh<T>(&mut self, arena: &MeshArena<T>, mesh: &Mesh)
+    where
+        T: 'static + Copy,
+    {
+        let buf = arena.vertex_buffer();
+
+        match (mesh.indices.clone(), arena.index_buffer()) {
+            (Some(indices), Some(index_buf)) => {
+                if cfg!(debug_assertions) {
+                    self.validate_buffer(buf, &mesh.vertices);
+                }
+                self.set_vertex_buffer(buf);
+                self.set_index_buffer(index_buf);
+                self.record(indices.end - indices.start, 1);
+                self.trace(|| TraceEvent::DrawIndexed(indices.clone(), 0..1));
+                self.wgpu
+                    .draw_indexed(indices, mesh.vertices.start as i32, 0..1);
+            }
+            (Some(_), None) => panic!("fatal: indexed mesh drawn from an arena without indices"),
+            (None, _) => self.draw_buffer_range(buf, mesh.vertices.clone()),
+        }
+    }
+    pub fn draw_indexed(&mut self, indices: Range<u32>, instances: Range<u32>) {
+        self.record(indices.end - indices.start, instances.end - instances.start);
+        self.trace(|| TraceEvent::DrawIndexed(indices.clone(), instances.clone()));
+        self.wgpu.draw_indexed(indices, 0, instances)
+    }
+
+    /// The draw statistics of this pass so far.
+    pub fn stats(&self) -> PassStats {
+        self.stats
+    }
+
+    fn record(&mut self, vertices: u32, instances: u32) {
+        self.stats.draws += 1;
+        self.stats.vertices += u64::from(vertices) * u64::from(instances);
+
+        if let Some(node) = self.node.as_mut() {
+            node.stats = self.stats;
+        }
+
+        if cfg!(debug_assertions) {
+            if let Some(budget) = self.draw_budget {
+                assert!(
+                    self.stats.draws <= budget,
+                    "draw budget of {} draw calls per pass exceeded",
+                    budget
+                );
+            }
+        }
+    }
+
+    fn apply_pipeline(&mut self, pipeline: &Pipeline) {
+        if cfg!(debug_assertions) {
+            if let Some(format) = self.format {
+                assert_eq!(
+                    pipeline.format,
+                    format.to_wgpu(),
+                    "pipeline target format doesn't match the pass attachment"
+                );
+            }
+            assert_eq!(
+                pipeline.sample_count, self.sample_count,
+                "pipeline sample count doesn't match the pass attachment"
+            );
+            assert_eq!(
+                pipeline.depth.is_some(),
+                self.depth,
+                "pipeline depth state doesn't match the pass depth attachment"
+            );
+        }
+        self.stride = Some(pipeline.vertex_layout.size as u64);
+        self.instance_stride = pipeline.instance_layout.as_ref().map(|l| l.size as u64);
+        self.trace(|| TraceEvent::Pipeline(pipeline.vertex_layout.size as u64));
+        self.wgpu.set_pipeline(&pipeline.wgpu);
+    }
+
+    fn trace<F: FnOnce() -> TraceEvent>(&mut self, event: F) {
+        if let Some(trace) = self.trace.as_mut() {
+            trace.events.push(event());
+        }
+    }
+
+    fn validate_buffer(&self, buf: &VertexBuffer, range: &Range<u32>) {
+        if let Some(stride) = self.stride.filter(|s| *s > 0) {
+            assert_eq!(
+                buf.bytesize % stride,
+                0,
+                "vertex buffer size ({} bytes) is not a multiple of the pipeline vertex stride ({})",
+                buf.bytesize,
+                stride
+            );
+            assert!(
+                range.end as u64 * stride <= buf.bytesize,
+                "attempt to draw {} vertices from a buffer of {}",
+                range.end,
+                buf.bytesize / stride
+            );
+        }
+    }
+}
+
+/// Specifies how the attachments of a pass are initialized.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PassOp {
+    /// Clear the color attachment.
+    Clear(Rgba),
+    /// Clear the color attachment, and optionally the depth and stencil
+    /// attachments. A `None` value loads the existing contents instead.
+    ClearDepthStencil(Rgba, Option<f32>, Option<u32>),
+    /// Load the existing contents of the attachments.
+    Load(),
+    /// Don't care about the existing contents, because the pass will
+    /// overwrite every texel. Since wgpu has no "undefined" load
+    /// operation, this is encoded as a transparent clear, which is
+    /// free on tile-based GPUs, unlike a load.
+    DontCare(),
+}
+
+impl PassOp {
+    /// The depth clear value, if the depth attachment is to be cleared.
+    pub fn depth(&self) -> Option<f32> {
+        match self {
+            Self::ClearDepthStencil(_, depth, _) => *depth,
+            _ => None,
+        }
+    }
+
+    /// The stencil clear value, if the stencil attachment is to be cleared.
+    pub fn stencil(&self) -> Option<u32> {
+        match self {
+            Self::ClearDepthStencil(_, _, stencil) => *stencil,
+            _ => None,
+        }
+    }
+
+    fn clear_color(&self) -> Rgba {
+        match self {
+            Self::Clear(color) | Self::ClearDepthStencil(color, _, _) => *color,
+            Self::Load() | Self::DontCare() => Rgba::TRANSPARENT,
+        }
+    }
+
+    fn to_wgpu(self) -> wgpu::LoadOp {
+        match self {
+            Self::Clear(_) | Self::ClearDepthStencil(_, _, _) | Self::DontCare() => {
+                wgpu::LoadOp::Clear
+            }
+            Self::Load() => wgpu::LoadOp::Load,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// SwapChain & TextureView
+///////////////////////////////////////////////////////////////////////////////
+
+pub trait TextureView {
+    fn texture_view(&self) -> &wgpu::TextureView;
+
+    /// Identifies the view in a [`FrameGraph`]. Views created by the renderer
+    /// are numbered when they are created. By default, the address of the
+    /// view is used, which is only unique while the view is alive.
+    fn id(&self) -> usize {
+        self.texture_view() as *const wgpu::TextureView as usize
+    }
+
+    /// The texel format of the view, when used as a render target, if known.
+    /// Pipelines are only validated against the formats of views which
+    /// report one.
+    fn format(&self) -> Option<TextureFormat> {
+        None
+    }
+
+    /// The number of samples per texel, when used as a render target.
+    fn sample_count(&self) -> u32 {
+        1
+    }
+}
+
+pub struct SwapChainTexture<'a>(wgpu::SwapChainOutput<'a>, TextureFormat, usize);
+
+impl TextureView for SwapChainTexture<'_> {
+    fn texture_view(&self) -> &wgpu::TextureView {
+        &self.0.view
+    }
+
+    fn id(&self) -> usize {
+        self.2
+    }
+
+    fn format(&self) -> Option<TextureFormat> {
+        Some(self.1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PresentMode {
+    Vsync,
+    NoVsync,
+}
+
+impl PresentMode {
+    fn to_wgpu(&self) -> wgpu::PresentMode {
+        match self {
+            PresentMode::Vsync => wgpu::PresentMode::Vsync,
+            PresentMode::NoVsync => wgpu::PresentMode::NoVsync,
+        }
+    }
+}
+
+impl Default for PresentMode {
+    fn default() -> Self {
+        PresentMode::Vsync
+    }
+}
+
+/// A handle to a swap chain.
+///
+/// A `SwapChain` represents the image or series of images that will be presented to a [`Renderer`].
+/// A `SwapChain` may be created with [`Renderer::swap_chain`], or with
+/// [`Renderer::swap_chain_with_format`] for 10-bit and half-float displays.
+pub struct SwapChain {
+    pub width: u32,
+    pub height: u32,
+    pub format: TextureFormat,
+
+    wgpu: wgpu::SwapChain,
+}
+
+impl SwapChain {
+    /// Convenience method to retrieve `(width, height)`
+    #[inline]
+    pub fn size(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    /// Returns the next texture to be presented by the swapchain for drawing.
+    ///
+    /// When the [`SwapChainTexture`] returned by this method is dropped, the
+    /// swapchain will present the texture to the associated [`Renderer`].
+    pub fn next(&mut self) -> SwapChainTexture {
+        SwapChainTexture(
+            self.wgpu.get_next_texture(),
+            self.format,
+            next_resource_id(),
+        )
+    }
+
+    fn descriptor(
+        width: u32,
+        height: u32,
+        mode: PresentMode,
+        format: TextureFormat,
+    ) -> wgpu::SwapChainDescriptor {
+        wgpu::SwapChainDescriptor {
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+            format: format.to_wgpu(),
+            present_mode: mode.to_wgpu(),
+            width,
+            height,
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+/// Renderer
+///////////////////////////////////////////////////////////////////////////////
+
+pub struct Renderer {
+    pub device: Device,
+
+    draw_budget: Option<u32>,
+    scale_factor: f64,
+    srgb: bool,
+    trace: Option<Trace>,
+    report: FrameReport,
+    scratch: Scratch,
+    uploaded: u64,
+    submissions: u64,
+    completed: u64,
+    deletions: DeletionQueue,
+    signals: Vec<Signal>,
+    requests: mpsc::Receiver<Request>,
+    handle: DeviceHandle,
+}
+
+impl Renderer {
+    pub fn new(window: RawWindowHandle) -> Self {
+        Self::with_device(Device::new(window))
+    }
+
+    /// Create a renderer presenting to the given window, on a GPU shared
+    /// with other renderers. Resources created by any of them can be used
+    /// by all of them.
+    pub fn with_gpu(gpu: &Gpu, window: RawWindowHandle) -> Self {
+        Self::with_device(Device::with_gpu(gpu, window))
+    }
+
+    /// Create a renderer without a window, on a GPU shared with other
+    /// renderers, eg. for rendering to framebuffers in headless tests.
+    /// Offscreen renderers can't create swap chains.
+    pub fn offscreen(gpu: &Gpu) -> Self {
+        Self::with_device(Device::offscreen(gpu))
+    }
+
+    /// The GPU the renderer runs on, which can be shared with other renderers.
+    pub fn gpu(&self) -> &Gpu {
+        self.device.gpu()
+    }
+
+    fn with_device(device: Device) -> Self {
+        let (sender, requests) = mpsc::channel();
+
+        Self {
+            device,
+            draw_budget: None,
+            scale_factor: 1.0,
+            srgb: false,
+            trace: None,
+            report: FrameReport::default(),
+            scratch: Scratch::default(),
+            uploaded: 0,
+            submissions: 0,
+            completed: 0,
+            deletions: DeletionQueue::new(),
+            signals: Vec::new(),
+            requests,
+            handle: DeviceHandle { sender },
+        }
+    }
+
+    /// Drop a resource, eg. a texture or buffer, once the GPU is done with
+    /// the frames which may use it, including the frame being recorded.
+    /// Resources can otherwise be dropped while still in use by the GPU.
+    pub fn retire<T: 'static>(&mut self, resource: T) {
+        self.deletions.retire(self.submissions + 1, resource);
+    }
+
+    /// The number of retired resources which weren't dropped yet.
+    pub fn retired(&self) -> usize {
+        self.deletions.len()
+    }
+
+    /// Return a handle for creating resources from other threads. Requests
+    /// are fulfilled when the next frame is created with [`Renderer::frame`].
+    pub fn device_handle(&self) -> DeviceHandle {
+        self.handle.clone()
+    }
+
+    /// Return the graph of passes and render targets of the last submitted frame.
+    /// See [`FrameGraph::to_dot`] and [`FrameGraph::to_json`] for export formats.
+    pub fn dump_frame_graph(&self) -> &FrameGraph {
+        &self.report.graph
+    }
+
+    /// Return the timings, upload statistics and passes of the last
+    /// submitted frame.
+    pub fn frame_report(&self) -> &FrameReport {
+        &self.report
+    }
+
+    /// Render the [`FrameReport`] of the last submitted frame as JSON, eg. to
+    /// track rendering performance over time in a headless CI job.
+    pub fn export_frame_report(&self) -> String {
+        self.report.to_json()
+    }
+
+    /// Start capturing every operation, pass, pipeline switch and draw into a [`Trace`].
+    pub fn start_capture(&mut self) {
+        self.trace = Some(Trace::default());
+    }
+
+    /// Stop capturing, and return the trace captured since [`Renderer::start_capture`].
+    pub fn stop_capture(&mut self) -> Option<Trace> {
+        self.trace.take()
+    }
+
+    /// Set the maximum number of draw calls allowed per pass. In debug
+    /// builds, passes exceeding the budget will panic.
+    pub fn set_draw_budget(&mut self, draws: Option<u32>) {
+        self.draw_budget = draws;
+    }
+
+    /// Set the ratio of physical to logical pixels, eg. the window's
+    /// hi-DPI factor. Pipelines created from then on are sized in physical
+    /// pixels, but project logical coordinates. Existing pipelines can be
+    /// updated with [`AbstractPipeline::set_scale_factor`].
+    pub fn set_scale_factor(&mut self, factor: f64) {
+        assert!(factor > 0., "fatal: scale factor must be positive");
+
+        self.scale_factor = factor;
+    }
+
+    pub fn scale_factor(&self) -> f64 {
+        self.scale_factor
+    }
+
+    /// Create swap chains, framebuffers and pipelines with sRGB formats by
+    /// default, so that blending and filtering happen in linear space. Colors
+    /// passed to shaders should then be converted with [`Rgba::to_linear`].
+    ///
+    /// Textures created with [`Renderer::texture`] are then sRGB too, so that
+    /// their texels are decoded when sampled. Textures holding data rather
+    /// than colors should be created with [`Renderer::texture_with_format`].
+    pub fn set_srgb(&mut self, srgb: bool) {
+        self.srgb = srgb;
+    }
+
+    /// The format of targets created without an explicit format.
+    pub fn format(&self) -> TextureFormat {
+        if self.srgb {
+            TextureFormat::default().srgb()
+        } else {
+            TextureFormat::default()
+        }
+    }
+
+    /// The format of textures created without an explicit format.
+    pub fn texture_format(&self) -> TextureFormat {
+        if self.srgb {
+            TextureFormat::Rgba8Unorm.srgb()
+        } else {
+            TextureFormat::Rgba8Unorm
+        }
+    }
+
+    pub fn swap_chain(&self, w: u32, h: u32, mode: PresentMode) -> SwapChain {
+        self.swap_chain_with_format(w, h, mode, self.format())
+    }
+
+    /// Create a swap chain presenting in the given format, eg. `Rgb10a2Unorm`
+    /// or `Rgba16Float` on HDR displays. The format must be supported by the
+    /// surface; scenes rendered in another format can be converted with
+    /// [`kit::present::Presenter`](crate::kit::present::Presenter).
+    pub fn swap_chain_with_format(
+        &self,
+        w: u32,
+        h: u32,
+        mode: PresentMode,
+        format: TextureFormat,
+    ) -> SwapChain {
+        SwapChain {
+            wgpu: self.device.create_swap_chain(w, h, mode, format),
+            width: w,
+            height: h,
+            format,
+        }
+    }
+
+    /// Create a texture in the [`Renderer::texture_format`].
+    pub fn texture(&self, w: u32, h: u32) -> Texture {
+        self.texture_with_format(w, h, self.texture_format())
+    }
+
+    /// Create a texture with the given texel format, eg. a single channel
+    /// format for masks. Fills and transfers must use texels of that format.
+    pub fn texture_with_format(&self, w: u32, h: u32, format: TextureFormat) -> Texture {
+        self.device.create_texture_with_format(w, h, format)
+    }
+
+    /// Create a texture with a full chain of mip levels, for sprites drawn
+    /// downscaled with linear filtering. Sample it with a
+    /// [`Renderer::mipmap_sampler`] to blend between levels.
+    pub fn texture_with_mipmaps(&self, w: u32, h: u32) -> Texture {
+        self.device.create_texture_with_mipmaps(w, h)
+    }
+
+    /// Create a texture array with `layers` layers of the same size, eg. one
+    /// per sprite sheet, so that a single binding covers all of them.
+    /// Layers are uploaded with [`Op::FillLayer`] and [`Op::TransferLayer`].
+    pub fn texture_array(&self, w: u32, h: u32, layers: u32) -> Texture {
+        self.device.create_texture_array(w, h, layers)
+    }
+
+    /// Create a cube texture with square faces of the given size, eg. for
+    /// environment maps in custom pipelines. Each face is a layer, uploaded
+    /// with [`Op::FillLayer`] and the index of a [`CubeFace`].
+    pub fn cube_texture(&self, size: u32) -> Texture {
+        self.device.create_cube_texture(size)
+    }
+
+    /// Decode a PNG or JPEG image file, and upload it to a new texture.
+    #[cfg(feature = "image")]
+    pub fn texture_from_image<P: AsRef<std::path::Path>>(
+        &mut self,
+        path: P,
+    ) -> image::ImageResult<Texture> {
+        Ok(self.texture_from_rgba(image::open(path)?.to_rgba()))
+    }
+
+    /// Decode a PNG or JPEG image from memory, eg. from `include_bytes!`,
+    /// and upload it to a new texture.
+    #[cfg(feature = "image")]
+    pub fn texture_from_bytes(&mut self, bytes: &[u8]) -> image::ImageResult<Texture> {
+        Ok(self.texture_from_rgba(image::load_from_memory(bytes)?.to_rgba()))
+    }
+
+    #[cfg(feature = "image")]
+    fn texture_from_rgba(&mut self, img: image::RgbaImage) -> Texture {
+        let (w, h) = img.dimensions();
+        let texture = self.texture(w, h);
+
+        self.prepare(&[Op::Fill(&texture, &img)]);
+        texture
+    }
+
+    pub fn framebuffer(&self, w: u32, h: u32) -> Framebuffer {
+        self.device
+            .create_framebuffer_with_format(w, h, self.format())
+    }
+
+    pub fn framebuffer_with_format(&self, w: u32, h: u32, format: TextureFormat) -> Framebuffer {
+        self.device.create_framebuffer_with_format(w, h, format)
+    }
+
+    /// Create a depth buffer, for passes started with [`Frame::pass_with_depth`].
+    /// It should be the same size as the pass targets.
+    pub fn depth_buffer(&self, w: u32, h: u32) -> DepthBuffer {
+        self.device.create_depth_buffer(w, h)
+    }
+
+    pub fn vertex_buffer<T>(&self, verts: &[T]) -> VertexBuffer
+    where
+        T: 'static + Copy,
+    {
+        self.device.create_buffer(verts)
+    }
+
+    pub fn uniform_buffer<T>(&self, buf: &[T]) -> UniformBuffer
+    where
+        T: 'static + Copy,
+    {
+        self.device.create_uniform_buffer(buf)
+    }
+
+    pub fn uniform_array<T>(&self, records: &[T]) -> UniformArray
+    where
+        T: 'static + Copy,
+    {
+        self.device.create_uniform_array(records)
+    }
+
+    /// Create a push buffer holding up to `capacity` records, bound with the
+    /// given layout.
+    pub fn push_buffer<T>(&self, layout: &BindingGroupLayout, capacity: usize) -> PushBuffer<T>
+    where
+        T: 'static + Copy,
+    {
+        self.device.create_push_buffer(layout, capacity)
+    }
+
+    pub fn binding_group(&self, layout: &BindingGroupLayout, binds: &[&dyn Bind]) -> BindingGroup {
+        self.device.create_binding_group(layout, binds)
+    }
+
+    pub fn sampler(&self, min_filter: Filter, mag_filter: Filter) -> Sampler {
+        self.device.create_sampler(min_filter, mag_filter)
+    }
+
+    /// Create a sampler with the given address mode, eg. to clamp or mirror
+    /// coordinates outside of the texture. [`Renderer::sampler`] repeats
+    /// textures.
+    pub fn sampler_with_address_mode(
+        &self,
+        min_filter: Filter,
+        mag_filter: Filter,
+        mode: AddressMode,
+    ) -> Sampler {
+        self.device
+            .create_sampler_with_address_mode(min_filter, mag_filter, mode)
+    }
+
+    /// Create a sampler which filters between mip levels with `mipmap_filter`,
+    /// eg. `Filter::Linear` for trilinear filtering of textures created with
+    /// [`Renderer::texture_with_mipmaps`]. Other samplers use the nearest
+    /// mip level.
+    pub fn mipmap_sampler(
+        &self,
+        min_filter: Filter,
+        mag_filter: Filter,
+        mipmap_filter: Filter,
+    ) -> Sampler {
+        self.device.create_sampler_with_mipmap_filter(
+            min_filter,
+            mag_filter,
+            mipmap_filter,
+            AddressMode::Repeat,
+        )
+    }
+
+    pub fn pipeline<T>(&self, w: u32, h: u32, blending: Blending) -> T
+    where
+        T: AbstractPipeline<'static>,
+    {
+        self.pipeline_with_format(w, h, blending, self.format())
+    }
+
+    /// Create a pipeline which tests and writes fragment depths according to
+    /// `depth`. It can only be used in passes started with
+    /// [`Frame::pass_with_depth`].
+    pub fn pipeline_with_depth<T>(&self, w: u32, h: u32, blending: Blending, depth: DepthState) -> T
+    where
+        T: AbstractPipeline<'static>,
+    {
+        let desc = T::description();
+        let pip_layout = self.device.create_pipeline_layout(desc.pipeline_layout);
+        let vs =
+            self.device
+                .create_shader("vertex shader", desc.vertex_shader, ShaderStage::Vertex);
+        let fs = self.device.create_shader(
+            "fragment shader",
+            desc.fragment_shader,
+            ShaderStage::Fragment,
+        );
+        let pipeline = self.device.create_pipeline(
+            pip_layout,
+            VertexLayout::from(desc.vertex_layout),
+            None,
+            blending,
+            self.format(),
+            Some(depth),
+            &vs,
+            &fs,
+        );
+
+        let mut pipeline = T::setup(pipeline, &self.device, w, h);
+        pipeline.set_scale_factor(self.scale_factor);
+        pipeline
+    }
+
+    /// Create a pipeline rendering to targets of the given format.
+    pub fn pipeline_with_format<T>(
+        &self,
+        w: u32,
+        h: u32,
+        blending: Blending,
+        format: TextureFormat,
+    ) -> T
+    where
+        T: AbstractPipeline<'static>,
+    {
+        let desc = T::description();
+        self.pipeline_with_shaders(
+            w,
+            h,
+            blending,
+            format,
+            desc.vertex_shader,
+            desc.fragment_shader,
+        )
+    }
+
+    /// Create a pipeline with its description's layout, but with the given
+    /// SPIR-V shaders, eg. to hot-reload the shaders of a kit pipeline.
+    pub fn pipeline_with_shaders<T>(
+        &self,
+        w: u32,
+        h: u32,
+        blending: Blending,
+        format: TextureFormat,
+        vs: &[u8],
+        fs: &[u8],
+    ) -> T
+    where
+        T: AbstractPipeline<'static>,
+    {
+        let desc = T::description();
+        let pipeline = self.pipeline_from_sources(
+            vs.to_vec(),
+            fs.to_vec(),
+            desc.vertex_layout,
+            desc.pipeline_layout,
+            blending,
+            format,
+        );
+
+        let mut pipeline = T::setup(pipeline, &self.device, w, h);
+        pipeline.set_scale_factor(self.scale_factor);
+        pipeline
+    }
+
+    /// Create a pipeline from SPIR-V shaders loaded at runtime, eg. from disk,
+    /// rather than included in the binary.
+    pub fn pipeline_from_sources(
+        &self,
+        vs: Vec<u8>,
+        fs: Vec<u8>,
+        vertex_layout: &[VertexFormat],
+        pipeline_layout: &[Set],
+        blending: Blending,
+        format: TextureFormat,
+    ) -> Pipeline {
+        self.pipeline_with_vertex_layout(
+            vs,
+            fs,
+            VertexLayout::from(vertex_layout),
+            pipeline_layout,
+            blending,
+            format,
+        )
+    }
+
+    /// Create a pipeline from SPIR-V shaders with the given vertex layout,
+    /// eg. one described with [`VertexLayout::with_locations`].
+    pub fn pipeline_with_vertex_layout(
+        &self,
+        vs: Vec<u8>,
+        fs: Vec<u8>,
+        vertex_layout: VertexLayout,
+        pipeline_layout: &[Set],
+        blending: Blending,
+        format: TextureFormat,
+    ) -> Pipeline {
+        let pip_layout = self.device.create_pipeline_layout(pipeline_layout);
+        let vs = self
+            .device
+            .create_shader("vertex shader", &vs, ShaderStage::Vertex);
+        let fs = self
+            .device
+            .create_shader("fragment shader", &fs, ShaderStage::Fragment);
+
+        self.device.create_pipeline(
+            pip_layout,
+            vertex_layout,
+            None,
+            blending,
+            format,
+            None,
+            &vs,
+            &fs,
+        )
+    }
+
+    /// Create a pipeline from SPIR-V shaders, which reads per-instance
+    /// records from a second vertex buffer, eg. the position, color and
+    /// flags of each sprite. Draw with [`Pass::draw_instanced`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn instanced_pipeline_from_sources(
+        &self,
+        vs: Vec<u8>,
+        fs: Vec<u8>,
+        vertex_layout: &[VertexFormat],
+        instance_layout: VertexLayout,
+        pipeline_layout: &[Set],
+        blending: Blending,
+        format: TextureFormat,
+    ) -> Pipeline {
+        let pip_layout = self.device.create_pipeline_layout(pipeline_layout);
+        let vertex_layout = VertexLayout::from(vertex_layout);
+        let vs = self
+            .device
+            .create_shader("vertex shader", &vs, ShaderStage::Vertex);
+        let fs = self
+            .device
+            .create_shader("fragment shader", &fs, ShaderStage::Fragment);
+
+        self.device.create_pipeline(
+            pip_layout,
+            vertex_layout,
+            Some(instance_layout),
+            blending,
+            format,
+            None,
+            &vs,
+            &fs,
+        )
+    }
+
+    pub fn read<F>(&mut self, fb: &Framebuffer, f: F)
+    where
+        F: 'static + FnOnce(&[u8]),
+    {
+        self.read_texture(&fb.texture, f);
+    }
+
+    /// Read back the texels of a texture, eg. to save a packed atlas or to
+    /// inspect generated content. Only the base level of the first layer is
+    /// read. Like [`Renderer::read`], `f` is called with the texels once the
+    /// device is polled after the copy completes.
+    pub fn read_texture<F>(&mut self, texture: &Texture, f: F)
+    where
+        F: 'static + FnOnce(&[u8]),
+    {
+        let mut encoder = self.device.create_command_encoder();
+
+        let row = (texture.texel_size() * texture.w) as usize;
+        let pitch = row_pitch(row as u32) as usize;
+        let bytesize = pitch * texture.h as usize;
+        let dst = self
+            .device
+            .gpu
+            .device()
+            .create_buffer(&wgpu::BufferDescriptor {
+                size: bytesize as u64,
+                usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
+            });
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TextureCopyView {
+                texture: &texture.wgpu,
+                mip_level: 0,
+                array_layer: 0,
+                origin: wgpu::Origin3d {
+                    x: 0.0,
+                    y: 0.0,
+                    z: 0.0,
                 },
-                resolve_target: None,
-            }],
-            depth_stencil_attachment: None,
-        });
-        Pass { wgpu: pass }
+            },
+            wgpu::BufferCopyView {
+                buffer: &dst,
+                offset: 0,
+                row_pitch: pitch as u32,
+                image_height: texture.h,
+            },
+            texture.extent,
+        );
+        self.submit_encoder(encoder);
+
+        let texels = row * texture.h as usize;
+        let mut buffer: Vec<u8> = Vec::with_capacity(texels);
+
+        dst.map_read_async(
+            0,
+            bytesize as u64,
+            move |result: wgpu::BufferMapAsyncResult<&[u8]>| match result {
+                Ok(ref mapping) => {
+                    // Strip the padding at the end of each row.
+                    for r in mapping.data.chunks_exact(pitch) {
+                        buffer.extend_from_slice(&r[..row]);
+                    }
+                    if buffer.len() == texels {
+                        f(unsafe { std::mem::transmute(buffer.as_slice()) });
+                    }
+                }
+                Err(ref err) => panic!("{:?}", err),
+            },
+        );
     }
-    pub fn set_pipeline<T>(&mut self, pipeline: &T)
+
+    // MUTABLE API ////////////////////////////////////////////////////////////
+
+    pub fn update_pipeline<'a, T>(&mut self, pip: &'a T, p: T::PrepareContext, f: &mut Frame)
     where
         T: AbstractPipeline<'a>,
     {
-        pipeline.apply(self);
+        if let Some((buf, unifs)) = pip.uniforms(p) {
+            self.uploaded += std::mem::size_of_val(unifs.as_slice()) as u64;
+            self.device
+                .update_uniform_buffer::<T::Uniforms>(unifs.as_slice(), buf, &mut f.encoder);
+        }
     }
-    pub fn set_binding(&mut self, group: &BindingGroup, offsets: &[u64]) {
-        self.wgpu
-            .set_bind_group(group.set_index, &group.wgpu, offsets);
+
+    pub fn update_uniform_buffer<T>(&mut self, buf: &UniformBuffer, data: &[T], f: &mut Frame)
+    where
+        T: 'static + Copy,
+    {
+        self.uploaded += std::mem::size_of_val(data) as u64;
+        self.device.update_uniform_buffer(data, buf, &mut f.encoder);
     }
-    pub fn set_index_buffer(&mut self, index_buf: &IndexBuffer) {
-        self.wgpu.set_index_buffer(&index_buf.wgpu, 0)
+
+    pub fn update_uniform_array<T>(&mut self, array: &UniformArray, records: &[T], f: &mut Frame)
+    where
+        T: 'static + Copy,
+    {
+        assert!(
+            records.len() <= array.len(),
+            "fatal: too many records for uniform array of length {}",
+            array.len()
+        );
+        let size = std::mem::size_of_val(records);
+        assert!(
+            size <= array.buf.size * array.buf.count && size <= MAX_UNIFORM_BUFFER_RANGE,
+            "fatal: {} bytes of records don't fit in uniform array of {} bytes",
+            size,
+            (array.buf.size * array.buf.count).min(MAX_UNIFORM_BUFFER_RANGE)
+        );
+        self.uploaded += size as u64;
+        self.device
+            .update_uniform_buffer(records, &array.buf, &mut f.encoder);
     }
-    pub fn set_vertex_buffer(&mut self, vertex_buf: &VertexBuffer) {
-        self.wgpu.set_vertex_buffers(0, &[(&vertex_buf.wgpu, 0)])
+
+    pub fn frame(&mut self) -> Frame {
+        let encoder = self.device.create_command_encoder();
+        let arena = self.scratch.arena.take().unwrap_or_default();
+        let mut frame = Frame::with_arena(encoder, arena);
+        frame.set_draw_budget(self.draw_budget);
+        frame.graph.passes = std::mem::take(&mut self.scratch.passes);
+        frame.reads = std::mem::take(&mut self.scratch.reads);
+
+        if self.trace.is_some() {
+            frame.trace = Some(Trace::default());
+        }
+
+        let requests: Vec<Request> = self.requests.try_iter().collect();
+        for request in requests {
+            request(self, &mut frame);
+        }
+        frame
     }
-    pub fn draw<T: Draw>(&mut self, drawable: &T, binding: &BindingGroup) {
-        drawable.draw(binding, self);
+
+    pub fn submit(&mut self, frame: Frame) {
+        if let (Some(trace), Some(captured)) = (self.trace.as_mut(), frame.trace) {
+            trace.events.extend(captured.events);
+            trace.events.push(TraceEvent::Submit);
+        }
+        let record = frame.started.elapsed();
+        let started = Instant::now();
+
+        self.submit_encoder(frame.encoder);
+
+        let report = FrameReport {
+            frame: self.report.frame + 1,
+            record,
+            submit: started.elapsed(),
+            uploaded: std::mem::take(&mut self.uploaded),
+            graph: frame.graph,
+        };
+        let last = std::mem::replace(&mut self.report, report);
+
+        self.scratch.reclaim(last.graph, frame.reads);
+        self.scratch.arena = frame.arena.reset();
     }
-    pub fn draw_buffer(&mut self, buf: &VertexBuffer) {
-        self.set_vertex_buffer(buf);
-        self.wgpu.draw(0..buf.size, 0..1);
+
+    /// Submit a frame, and return a handle which is signaled when the GPU has
+    /// completed it. Completion is detected by [`Renderer::poll`].
+    ///
+    /// ```ignore
+    /// let handle = renderer.submit_with_signal(frame);
+    ///
+    /// renderer.on_complete(handle, move || println!("frame completed"));
+    /// renderer.poll();
+    /// ```
+    pub fn submit_with_signal(&mut self, frame: Frame) -> SubmissionHandle {
+        self.submit(frame);
+        SubmissionHandle(self.submissions)
     }
-    pub fn draw_buffer_range(&mut self, buf: &VertexBuffer, range: Range<u32>) {
-        self.set_vertex_buffer(buf);
-        self.wgpu.draw(range, 0..1);
+
+    /// Check for completed submissions without blocking, and run the
+    /// callbacks registered for them with [`Renderer::on_complete`], in
+    /// submission order.
+    pub fn poll(&mut self) {
+        let done = self.signaled();
+
+        for signal in self.signals.drain(..done) {
+            for callback in signal.callbacks {
+                callback();
+            }
+        }
+    }
+
+    /// Run `callback` from [`Renderer::poll`] once the submission has
+    /// completed, or right away if it already has.
+    pub fn on_complete<F: FnOnce() + 'static>(&mut self, handle: SubmissionHandle, callback: F) {
+        match self.signals.iter_mut().find(|s| s.handle == handle) {
+            Some(signal) => signal.callbacks.push(Box::new(callback)),
+            None => callback(),
+        }
+    }
+
+    /// Whether the submission has completed, as of the last call to
+    /// [`Renderer::poll`] or the last submission.
+    pub fn is_complete(&self, handle: SubmissionHandle) -> bool {
+        self.signals.iter().all(|s| s.handle != handle)
+    }
+
+    pub fn prepare(&mut self, commands: &[Op]) {
+        let mut encoder = self.device.create_command_encoder();
+        for c in commands.iter() {
+            if let Some(trace) = self.trace.as_mut() {
+                trace.events.push(TraceEvent::from(c));
+            }
+            c.encode(&mut self.device, &mut encoder);
+            self.uploaded += c.bytes() as u64;
+        }
+        if let Some(trace) = self.trace.as_mut() {
+            trace.events.push(TraceEvent::Submit);
+        }
+        self.submit_encoder(encoder);
+    }
+
+    /// Submit commands, signaling their completion. Every submission is
+    /// signaled, so that retired resources are dropped as soon as the GPU
+    /// is done with them.
+    fn submit_encoder(&mut self, mut encoder: wgpu::CommandEncoder) {
+        // The GPU signals completion by making a buffer written by the
+        // submission's last command available for mapping.
+        let dst = {
+            let gpu = self.device.gpu.device();
+            let src = gpu
+                .create_buffer_mapped(1, wgpu::BufferUsage::COPY_SRC)
+                .fill_from_slice(&[0u32]);
+            let dst = gpu.create_buffer(&wgpu::BufferDescriptor {
+                size: 4,
+                usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
+            });
+            encoder.copy_buffer_to_buffer(&src, 0, &dst, 0, 4);
+            dst
+        };
+
+        self.device.submit(&[encoder.finish()]);
+        self.submissions += 1;
+
+        let done = Rc::new(Cell::new(false));
+        let signal = done.clone();
+        dst.map_read_async(0, 4, move |_: wgpu::BufferMapAsyncResult<&[u8]>| {
+            signal.set(true);
+        });
+        self.signals.push(Signal {
+            handle: SubmissionHandle(self.submissions),
+            done,
+            callbacks: Vec::new(),
+            _buffer: dst,
+        });
+
+        // Completed submissions without callbacks can be forgotten right
+        // away: the others are kept until the next call to `poll`.
+        let done = self.signaled();
+        let mut i = 0;
+        self.signals.retain(|s| {
+            i += 1;
+            i > done || !s.callbacks.is_empty()
+        });
     }
-    pub fn draw_indexed(&mut self, indices: Range<u32>, instances: Range<u32>) {
-        self.wgpu.draw_indexed(indices, 0, instances)
+
+    /// Check for completed submissions without blocking, and drop the
+    /// resources retired by them. Returns the number of pending signals
+    /// which are done.
+    fn signaled(&mut self) -> usize {
+        self.device.gpu.device().poll(false);
+
+        // Submissions complete in order, so only a prefix of the signals
+        // can be done.
+        let done = self
+            .signals
+            .iter()
+            .rposition(|s| s.done.get())
+            .map_or(0, |i| i + 1);
+
+        if let Some(signal) = self.signals[..done].last() {
+            self.completed = self.completed.max(signal.handle.index());
+        }
+        self.deletions.collect(self.completed);
+
+        done
     }
 }
 
-pub enum PassOp {
-    Clear(Rgba),
-    Load(),
+pub enum Op<'a> {
+    Clear(&'a dyn Canvas, Rgba),
+    Fill(&'a dyn Canvas, &'a [u8]),
+    Transfer(&'a dyn Canvas, &'a [u8], u32, u32, u32, u32),
+    Blit(&'a dyn Canvas, Rect<f32>, Rect<f32>),
+    /// Fill the given layer of a texture array.
+    FillLayer(&'a dyn Canvas, u32, &'a [u8]),
+    /// Transfer texels to the given layer of a texture array.
+    TransferLayer(&'a dyn Canvas, u32, &'a [u8], u32, u32, u32, u32),
+    /// Transfer texels of the given size to a region of the canvas, eg. to
+    /// add an image to a partially populated atlas.
+    TransferAt(&'a dyn Canvas, &'a [u8], u32, u32, Rect<u32>),
 }
 
-impl PassOp {
-    fn to_wgpu(&self) -> wgpu::LoadOp {
-        match self {
-            PassOp::Clear(_) => wgpu::LoadOp::Clear,
-            PassOp::Load() => wgpu::LoadOp::Load,
+impl<'a> Op<'a> {
+    /// The number of bytes uploaded by the operation.
+    fn bytes(&self) -> usize {
+        match *self {
+            Op::Fill(_, buf)
+            | Op::Transfer(_, buf, _, _, _, _)
+            | Op::FillLayer(_, _, buf)
+            | Op::TransferLayer(_, _, buf, _, _, _, _)
+            | Op::TransferAt(_, buf, _, _, _) => buf.len(),
+            Op::Clear(_, _) | Op::Blit(_, _, _) => 0,
+        }
+    }
+
+    fn encode(&self, dev: &mut Device, encoder: &mut wgpu::CommandEncoder) {
+        match *self {
+            Op::Clear(f, color) => {
+                f.clear(color, dev, encoder);
+            }
+            Op::Fill(f, buf) => {
+                f.fill(buf, dev, encoder);
+            }
+            Op::Transfer(f, buf, w, h, tw, th) => {
+                f.transfer(buf, w, h, tw, th, dev, encoder);
+            }
+            Op::Blit(f, src, dst) => {
+                f.blit(src, dst, encoder);
+            }
+            Op::FillLayer(f, layer, buf) => {
+                f.fill_layer(layer, buf, dev, encoder);
+            }
+            Op::TransferLayer(f, layer, buf, w, h, tw, th) => {
+                f.transfer_layer(layer, buf, w, h, tw, th, dev, encoder);
+            }
+            Op::TransferAt(f, buf, w, h, dst) => {
+                f.transfer_at(buf, w, h, dst, dev, encoder);
+            }
         }
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// SwapChain & TextureView
+/// DeviceHandle
 ///////////////////////////////////////////////////////////////////////////////
 
-pub trait TextureView {
-    fn texture_view(&self) -> &wgpu::TextureView;
-}
+/// A queued resource creation request.
+type Request = Box<dyn FnOnce(&mut Renderer, &mut Frame) + Send>;
 
-pub struct SwapChainTexture<'a>(wgpu::SwapChainOutput<'a>);
+/// Identifies a frame submitted with [`Renderer::submit_with_signal`].
+/// Handles of later submissions compare greater.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SubmissionHandle(u64);
 
-impl TextureView for SwapChainTexture<'_> {
-    fn texture_view(&self) -> &wgpu::TextureView {
-        &self.0.view
+impl SubmissionHandle {
+    /// The index of the submission, starting at `1` for the first frame.
+    pub fn index(self) -> u64 {
+        self.0
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum PresentMode {
-    Vsync,
-    NoVsync,
+/// A pending [`SubmissionHandle`], and the callbacks to run on completion.
+struct Signal {
+    handle: SubmissionHandle,
+    done: Rc<Cell<bool>>,
+    callbacks: Vec<Box<dyn FnOnce()>>,
+    /// The buffer mapped on completion, which must be kept alive until then.
+    _buffer: wgpu::Buffer,
 }
 
-impl PresentMode {
-    fn to_wgpu(&self) -> wgpu::PresentMode {
-        match self {
-            PresentMode::Vsync => wgpu::PresentMode::Vsync,
-            PresentMode::NoVsync => wgpu::PresentMode::NoVsync,
-        }
-    }
+/// A handle for creating resources from worker threads, eg. while loading
+/// assets, obtained with [`Renderer::device_handle`].
+///
+/// Requests are queued, and fulfilled on the render thread when the next
+/// frame is created. Texture uploads are encoded in that frame.
+#[derive(Clone)]
+pub struct DeviceHandle {
+    sender: mpsc::Sender<Request>,
 }
 
-impl Default for PresentMode {
-    fn default() -> Self {
-        PresentMode::Vsync
+impl DeviceHandle {
+    /// Queue a request to run with the renderer on the render thread.
+    pub fn create<T, F>(&self, f: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Renderer) -> T + Send + 'static,
+    {
+        self.request(move |r, _| f(r))
     }
-}
 
-/// A handle to a swap chain.
-///
-/// A `SwapChain` represents the image or series of images that will be presented to a [`Renderer`].
-/// A `SwapChain` may be created with [`Renderer::swap_chain`].
-pub struct SwapChain {
-    pub width: u32,
-    pub height: u32,
+    /// Create a texture filled with the given texels.
+    pub fn texture(&self, w: u32, h: u32, texels: Vec<u8>) -> Pending<Texture> {
+        self.request(move |r, frame| {
+            let texture = r.texture(w, h);
+            Texture::fill(&texture, &texels, &mut r.device, &mut frame.encoder);
+            texture
+        })
+    }
 
-    wgpu: wgpu::SwapChain,
-}
+    pub fn vertex_buffer<T>(&self, vertices: Vec<T>) -> Pending<VertexBuffer>
+    where
+        T: Copy + Send + 'static,
+    {
+        self.request(move |r, _| r.vertex_buffer(vertices.as_slice()))
+    }
 
-impl SwapChain {
-    /// Convenience method to retrieve `(width, height)`
-    #[inline]
-    pub fn size(&self) -> (u32, u32) {
-        (self.width, self.height)
+    pub fn uniform_buffer<T>(&self, data: Vec<T>) -> Pending<UniformBuffer>
+    where
+        T: Copy + Send + 'static,
+    {
+        self.request(move |r, _| r.uniform_buffer(data.as_slice()))
     }
 
-    /// Returns the next texture to be presented by the swapchain for drawing.
-    ///
-    /// When the [`SwapChainTexture`] returned by this method is dropped, the
-    /// swapchain will present the texture to the associated [`Renderer`].
-    pub fn next(&mut self) -> SwapChainTexture {
-        SwapChainTexture(self.wgpu.get_next_texture())
+    pub fn pipeline<T>(&self, w: u32, h: u32, blending: Blending) -> Pending<T>
+    where
+        T: AbstractPipeline<'static> + Send + 'static,
+    {
+        self.request(move |r, _| r.pipeline(w, h, blending))
     }
 
-    fn descriptor(width: u32, height: u32, mode: PresentMode) -> wgpu::SwapChainDescriptor {
-        wgpu::SwapChainDescriptor {
-            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
-            format: wgpu::TextureFormat::Bgra8Unorm,
-            present_mode: mode.to_wgpu(),
-            width,
-            height,
-        }
+    fn request<T, F>(&self, f: F) -> Pending<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Renderer, &mut Frame) -> T + Send + 'static,
+    {
+        let (reply, receiver) = mpsc::channel();
+
+        self.sender
+            .send(Box::new(move |r, frame| {
+                // The requester may have given up on the resource.
+                reply.send(f(r, frame)).ok();
+            }))
+            .expect("fatal: renderer was dropped");
+
+        Pending { receiver }
+    }
+}
+
+/// A resource requested with a [`DeviceHandle`].
+pub struct Pending<T> {
+    receiver: mpsc::Receiver<T>,
+}
+
+impl<T> Pending<T> {
+    /// Return the resource, if it was created.
+    pub fn try_get(&self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Block until the resource is created. This will never return if called
+    /// from the render thread.
+    pub fn wait(self) -> T {
+        self.receiver
+            .recv()
+            .expect("fatal: renderer was dropped before the resource was created")
     }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
-/// Renderer
+/// Capture
 ///////////////////////////////////////////////////////////////////////////////
 
-pub struct Renderer {
-    pub device: Device,
+/// A captured command, as recorded in a [`Trace`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceEvent {
+    /// A canvas was cleared with the given color.
+    Clear(Rgba),
+    /// A canvas was filled with the given texels.
+    Fill(Vec<u8>),
+    /// Texels of the given size were transferred to a canvas, with the given transfer size.
+    Transfer(Vec<u8>, u32, u32, u32, u32),
+    /// A canvas region was blitted to another region.
+    Blit(Rect<f32>, Rect<f32>),
+    /// A layer of a texture array was filled with the given texels.
+    FillLayer(u32, Vec<u8>),
+    /// Texels were transferred to a layer of a texture array, as with `Transfer`.
+    TransferLayer(u32, Vec<u8>, u32, u32, u32, u32),
+    /// Texels of the given size were transferred to a canvas region.
+    TransferAt(Vec<u8>, u32, u32, Rect<u32>),
+    /// A pass was started.
+    Pass(PassOp),
+    /// A pipeline with the given vertex stride was set.
+    Pipeline(u64),
+    /// A binding group was set at the given index, with the given dynamic offsets.
+    Binding(u32, Vec<u64>),
+    /// Vertices and instances were drawn.
+    Draw(Range<u32>, Range<u32>),
+    /// Indices and instances were drawn.
+    DrawIndexed(Range<u32>, Range<u32>),
+    /// The recorded commands were submitted to the GPU.
+    Submit,
 }
 
-impl Renderer {
-    pub fn new(window: RawWindowHandle) -> Self {
-        Self {
-            device: Device::new(window),
+impl<'a> From<&Op<'a>> for TraceEvent {
+    fn from(op: &Op<'a>) -> Self {
+        match *op {
+            Op::Clear(_, color) => Self::Clear(color),
+            Op::Fill(_, buf) => Self::Fill(buf.to_vec()),
+            Op::Transfer(_, buf, w, h, tw, th) => Self::Transfer(buf.to_vec(), w, h, tw, th),
+            Op::Blit(_, src, dst) => Self::Blit(src, dst),
+            Op::FillLayer(_, layer, buf) => Self::FillLayer(layer, buf.to_vec()),
+            Op::TransferLayer(_, layer, buf, w, h, tw, th) => {
+                Self::TransferLayer(layer, buf.to_vec(), w, h, tw, th)
+            }
+            Op::TransferAt(_, buf, w, h, dst) => Self::TransferAt(buf.to_vec(), w, h, dst),
         }
     }
+}
 
-    pub fn swap_chain(&self, w: u32, h: u32, mode: PresentMode) -> SwapChain {
-        SwapChain {
-            wgpu: self.device.create_swap_chain(w, h, mode),
-            width: w,
-            height: h,
+/// A serializable trace of rendering commands, captured with
+/// [`Renderer::start_capture`].
+///
+/// Traces are serialized to a line-based text format, one command per line,
+/// which makes them suitable for attaching to bug reports and for comparing
+/// in regression tests.
+///
+/// # Examples
+///
+/// ```
+/// use rgx::core::{PassOp, Rect, Rgba, Trace, TraceEvent};
+///
+/// let trace = Trace {
+///     events: vec![
+///         TraceEvent::Transfer(vec![0xff, 0, 0x7f, 0xff], 1, 1, 1, 1),
+///         TraceEvent::FillLayer(3, vec![0, 0, 0, 0xff]),
+///         TraceEvent::TransferAt(vec![0, 0xff, 0, 0xff], 1, 1, Rect::new(8, 16, 9, 17)),
+///         TraceEvent::Submit,
+///         TraceEvent::Pass(PassOp::Clear(Rgba::BLACK)),
+///         TraceEvent::Pipeline(24),
+///         TraceEvent::Binding(2, vec![]),
+///         TraceEvent::Draw(0..6, 0..1),
+///         TraceEvent::Submit,
+///     ],
+/// };
+/// let parsed: Trace = trace.to_string().parse().unwrap();
+///
+/// assert_eq!(parsed, trace);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trace {
+    pub events: Vec<TraceEvent>,
+}
+
+impl Trace {
+    /// Replay the trace on `target`, eg. a framebuffer of a renderer created
+    /// with [`Renderer::offscreen`], to reproduce a captured frame headlessly.
+    ///
+    /// Operations and passes are re-encoded against the target, and submitted
+    /// wherever the trace was. Pipeline switches, bindings and draws are
+    /// skipped, since traces don't record the resources they used.
+    ///
+    /// ```no_run
+    /// use rgx::core::{Gpu, Renderer, Rgba, Trace, TraceEvent};
+    ///
+    /// let gpu = Gpu::new();
+    /// let mut renderer = Renderer::offscreen(&gpu);
+    /// let target = renderer.framebuffer(1, 1);
+    ///
+    /// let trace: Trace = "fill ff00ffff\nsubmit".parse().unwrap();
+    /// trace.replay(&mut renderer, &target);
+    ///
+    /// renderer.read(&target, |texels| {
+    ///     assert_eq!(texels, &[0xff, 0, 0xff, 0xff]);
+    /// });
+    /// renderer.poll();
+    /// ```
+    pub fn replay(&self, renderer: &mut Renderer, target: &Framebuffer) {
+        let mut frame = renderer.frame();
+
+        for event in self.events.iter() {
+            let op = match *event {
+                TraceEvent::Clear(color) => Op::Clear(target, color),
+                TraceEvent::Fill(ref buf) => Op::Fill(target, buf),
+                TraceEvent::Transfer(ref buf, w, h, tw, th) => {
+                    Op::Transfer(target, buf, w, h, tw, th)
+                }
+                TraceEvent::Blit(src, dst) => Op::Blit(target, src, dst),
+                TraceEvent::FillLayer(layer, ref buf) => Op::FillLayer(target, layer, buf),
+                TraceEvent::TransferLayer(layer, ref buf, w, h, tw, th) => {
+                    Op::TransferLayer(target, layer, buf, w, h, tw, th)
+                }
+                TraceEvent::TransferAt(ref buf, w, h, dst) => {
+                    Op::TransferAt(target, buf, w, h, dst)
+                }
+                TraceEvent::Pass(op) => {
+                    frame.pass(op, target);
+                    continue;
+                }
+                TraceEvent::Submit => {
+                    let next = renderer.frame();
+                    renderer.submit(std::mem::replace(&mut frame, next));
+                    continue;
+                }
+                TraceEvent::Pipeline(_)
+                | TraceEvent::Binding(_, _)
+                | TraceEvent::Draw(_, _)
+                | TraceEvent::DrawIndexed(_, _) => continue,
+            };
+            renderer.uploaded += op.bytes() as u64;
+            op.encode(&mut renderer.device, &mut frame.encoder);
         }
+        renderer.submit(frame);
     }
+}
 
-    pub fn texture(&self, w: u32, h: u32) -> Texture {
-        self.device.create_texture(w, h)
-    }
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn hex(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+            write!(f, " ")?;
+            for b in bytes {
+                write!(f, "{:02x}", b)?;
+            }
+            Ok(())
+        }
+        fn rgba(f: &mut fmt::Formatter<'_>, c: Rgba) -> fmt::Result {
+            write!(f, " {} {} {} {}", c.r, c.g, c.b, c.a)
+        }
+        fn rect<T: fmt::Display>(f: &mut fmt::Formatter<'_>, r: Rect<T>) -> fmt::Result {
+            write!(f, " {} {} {} {}", r.x1, r.y1, r.x2, r.y2)
+        }
+        fn opt<T: fmt::Display>(f: &mut fmt::Formatter<'_>, o: Option<T>) -> fmt::Result {
+            match o {
+                Some(v) => write!(f, " {}", v),
+                None => write!(f, " -"),
+            }
+        }
 
-    pub fn framebuffer(&self, w: u32, h: u32) -> Framebuffer {
-        self.device.create_framebuffer(w, h)
+        for e in self.events.iter() {
+            match e {
+                TraceEvent::Clear(c) => {
+                    write!(f, "clear")?;
+                    rgba(f, *c)?;
+                }
+                TraceEvent::Fill(buf) => {
+                    write!(f, "fill")?;
+                    hex(f, buf)?;
+                }
+                TraceEvent::Transfer(buf, w, h, tw, th) => {
+                    write!(f, "transfer {} {} {} {}", w, h, tw, th)?;
+                    hex(f, buf)?;
+                }
+                TraceEvent::Blit(src, dst) => {
+                    write!(f, "blit")?;
+                    rect(f, *src)?;
+                    rect(f, *dst)?;
+                }
+                TraceEvent::FillLayer(layer, buf) => {
+                    write!(f, "fill-layer {}", layer)?;
+                    hex(f, buf)?;
+                }
+                TraceEvent::TransferLayer(layer, buf, w, h, tw, th) => {
+                    write!(f, "transfer-layer {} {} {} {} {}", layer, w, h, tw, th)?;
+                    hex(f, buf)?;
+                }
+                TraceEvent::TransferAt(buf, w, h, dst) => {
+                    write!(f, "transfer-at {} {}", w, h)?;
+                    rect(f, *dst)?;
+                    hex(f, buf)?;
+                }
+                TraceEvent::Pass(op) => {
+                    write!(f, "pass")?;
+                    match op {
+                        PassOp::Clear(c) => {
+                            write!(f, " clear")?;
+                            rgba(f, *c)?;
+                        }
+                        PassOp::ClearDepthStencil(c, depth, stencil) => {
+                            write!(f, " clear-depth-stencil")?;
+                            rgba(f, *c)?;
+                            opt(f, *depth)?;
+                            opt(f, *stencil)?;
+                        }
+                        PassOp::Load() => write!(f, " load")?,
+                        PassOp::DontCare() => write!(f, " dont-care")?,
+                    }
+                }
+                TraceEvent::Pipeline(stride) => write!(f, "pipeline {}", stride)?,
+                TraceEvent::Binding(index, offsets) => {
+                    write!(f, "binding {}", index)?;
+                    for o in offsets {
+                        write!(f, " {}", o)?;
+                    }
+                }
+                TraceEvent::Draw(v, i) => {
+                    write!(f, "draw {} {} {} {}", v.start, v.end, i.start, i.end)?
+                }
+                TraceEvent::DrawIndexed(v, i) => write!(
+                    f,
+                    "draw-indexed {} {} {} {}",
+                    v.start, v.end, i.start, i.end
+                )?,
+                TraceEvent::Submit => write!(f, "submit")?,
+            }
+            writeln!(f)?;
+        }
+        Ok(())
     }
+}
 
-    pub fn vertex_buffer<T>(&self, verts: &[T]) -> VertexBuffer
-    where
-        T: 'static + Copy,
-    {
-        self.device.create_buffer(verts)
-    }
+/// An error returned when parsing a [`Trace`] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTraceError {
+    /// The line at which the error occured, starting at `1`.
+    pub line: usize,
+}
 
-    pub fn uniform_buffer<T>(&self, buf: &[T]) -> UniformBuffer
-    where
-        T: 'static + Copy,
-    {
-        self.device.create_uniform_buffer(buf)
+impl fmt::Display for ParseTraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid trace command on line {}", self.line)
     }
+}
 
-    pub fn binding_group(&self, layout: &BindingGroupLayout, binds: &[&dyn Bind]) -> BindingGroup {
-        self.device.create_binding_group(layout, binds)
-    }
+impl std::error::Error for ParseTraceError {}
 
-    pub fn sampler(&self, min_filter: Filter, mag_filter: Filter) -> Sampler {
-        self.device.create_sampler(min_filter, mag_filter)
-    }
+impl FromStr for Trace {
+    type Err = ParseTraceError;
 
-    pub fn pipeline<T>(&self, w: u32, h: u32, blending: Blending) -> T
-    where
-        T: AbstractPipeline<'static>,
-    {
-        let desc = T::description();
-        let pip_layout = self.device.create_pipeline_layout(desc.pipeline_layout);
-        let vertex_layout = VertexLayout::from(desc.vertex_layout);
-        let vs =
-            self.device
-                .create_shader("vertex shader", desc.vertex_shader, ShaderStage::Vertex);
-        let fs = self.device.create_shader(
-            "fragment shader",
-            desc.fragment_shader,
-            ShaderStage::Fragment,
-        );
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        fn num<T: FromStr>(words: &mut std::str::SplitWhitespace) -> Option<T> {
+            words.next()?.parse().ok()
+        }
+        fn opt<T: FromStr>(words: &mut std::str::SplitWhitespace) -> Option<Option<T>> {
+            match words.next()? {
+                "-" => Some(None),
+                w => w.parse().ok().map(Some),
+            }
+        }
+        fn rgba(words: &mut std::str::SplitWhitespace) -> Option<Rgba> {
+            Some(Rgba::new(
+                num(words)?,
+                num(words)?,
+                num(words)?,
+                num(words)?,
+            ))
+        }
+        fn rect<T: FromStr>(words: &mut std::str::SplitWhitespace) -> Option<Rect<T>> {
+            Some(Rect::new(
+                num(words)?,
+                num(words)?,
+                num(words)?,
+                num(words)?,
+            ))
+        }
+        fn range(words: &mut std::str::SplitWhitespace) -> Option<Range<u32>> {
+            Some(num(words)?..num(words)?)
+        }
+        fn hex(words: &mut std::str::SplitWhitespace) -> Option<Vec<u8>> {
+            words
+                .next()
+                .unwrap_or("")
+                .as_bytes()
+                .chunks(2)
+                .map(|c| match std::str::from_utf8(c) {
+                    Ok(c) if c.len() == 2 => u8::from_str_radix(c, 16).ok(),
+                    _ => None,
+                })
+                .collect()
+        }
+        fn event(line: &str) -> Option<TraceEvent> {
+            let mut words = line.split_whitespace();
+            let w = &mut words;
+
+            let event = match w.next()? {
+                "clear" => TraceEvent::Clear(rgba(w)?),
+                "fill" => TraceEvent::Fill(hex(w)?),
+                "transfer" => {
+                    let (width, height) = (num(w)?, num(w)?);
+                    let (tw, th) = (num(w)?, num(w)?);
+                    TraceEvent::Transfer(hex(w)?, width, height, tw, th)
+                }
+                "blit" => TraceEvent::Blit(rect(w)?, rect(w)?),
+                "fill-layer" => {
+                    let layer = num(w)?;
+                    TraceEvent::FillLayer(layer, hex(w)?)
+                }
+                "transfer-layer" => {
+                    let layer = num(w)?;
+                    let (width, height) = (num(w)?, num(w)?);
+                    let (tw, th) = (num(w)?, num(w)?);
+                    TraceEvent::TransferLayer(layer, hex(w)?, width, height, tw, th)
+                }
+                "transfer-at" => {
+                    let (width, height) = (num(w)?, num(w)?);
+                    let dst = rect(w)?;
+                    TraceEvent::TransferAt(hex(w)?, width, height, dst)
+                }
+                "pass" => TraceEvent::Pass(match w.next()? {
+                    "clear" => PassOp::Clear(rgba(w)?),
+                    "clear-depth-stencil" => PassOp::ClearDepthStencil(rgba(w)?, opt(w)?, opt(w)?),
+                    "load" => PassOp::Load(),
+                    "dont-care" => PassOp::DontCare(),
+                    _ => return None,
+                }),
+                "pipeline" => TraceEvent::Pipeline(num(w)?),
+                "binding" => {
+                    let index = num(w)?;
+                    let offsets = w.map(|o| o.parse().ok()).collect::<Option<_>>()?;
+                    TraceEvent::Binding(index, offsets)
+                }
+                "draw" => TraceEvent::Draw(range(w)?, range(w)?),
+                "draw-indexed" => TraceEvent::DrawIndexed(range(w)?, range(w)?),
+                "submit" => TraceEvent::Submit,
+                _ => return None,
+            };
+
+            if w.next().is_some() {
+                return None;
+            }
+            Some(event)
+        }
 
-        T::setup(
-            self.device
-                .create_pipeline(pip_layout, vertex_layout, blending, &vs, &fs),
-            &self.device,
-            w,
-            h,
-        )
+        let mut events = Vec::new();
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            events.push(event(line).ok_or(ParseTraceError { line: i + 1 })?);
+        }
+        Ok(Self { events })
     }
+}
 
-    pub fn read<F>(&mut self, fb: &Framebuffer, f: F)
-    where
-        F: 'static + FnOnce(&[u8]),
-    {
-        let mut encoder = self.device.create_command_encoder();
+///////////////////////////////////////////////////////////////////////////////
+/// Frame Graph
+///////////////////////////////////////////////////////////////////////////////
 
-        let bytesize = 4 * fb.size();
-        let dst = self.device.device.create_buffer(&wgpu::BufferDescriptor {
-            size: bytesize as u64,
-            usage: wgpu::BufferUsage::MAP_READ | wgpu::BufferUsage::COPY_DST,
-        });
+/// Source of texture view ids. Ids are never reused, so passes recorded in
+/// different frames can be compared.
+static NEXT_RESOURCE_ID: AtomicUsize = AtomicUsize::new(1);
 
-        encoder.copy_texture_to_buffer(
-            wgpu::TextureCopyView {
-                texture: &fb.texture.wgpu,
-                mip_level: 0,
-                array_layer: 0,
-                origin: wgpu::Origin3d {
-                    x: 0.0,
-                    y: 0.0,
-                    z: 0.0,
-                },
-            },
-            wgpu::BufferCopyView {
-                buffer: &dst,
-                offset: 0,
-                // TODO: Must be a multiple of 256
-                row_pitch: 4 * fb.texture.w,
-                image_height: fb.texture.h,
-            },
-            fb.texture.extent,
-        );
-        self.device.submit(&[encoder.finish()]);
+fn next_resource_id() -> usize {
+    NEXT_RESOURCE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Quote and escape a string for use in JSON output.
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// A pass recorded in a [`FrameGraph`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PassNode {
+    /// How the pass attachment was initialized.
+    pub op: PassOp,
+    /// The render target of the pass.
+    pub target: usize,
+    /// The format of the render target, if known.
+    pub format: Option<TextureFormat>,
+    /// Textures sampled during the pass, eg. render targets of earlier passes.
+    pub reads: Vec<usize>,
+    /// Draw statistics of the pass.
+    pub stats: PassStats,
+}
 
-        let mut buffer: Vec<u8> = Vec::with_capacity(bytesize);
+/// The passes of a frame, and the resources they read and write.
+///
+/// Resources are identified by opaque ids, which are only meaningful
+/// within a single frame.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameGraph {
+    pub passes: Vec<PassNode>,
+}
 
-        dst.map_read_async(
-            0,
-            bytesize as u64,
-            move |result: wgpu::BufferMapAsyncResult<&[u8]>| match result {
-                Ok(ref mapping) => {
-                    buffer.extend_from_slice(mapping.data);
-                    if buffer.len() == bytesize {
-                        f(unsafe { std::mem::transmute(buffer.as_slice()) });
-                    }
-                }
-                Err(ref err) => panic!("{:?}", err),
-            },
-        );
-    }
+/// Allocations made while recording frames, kept by the renderer for the
+/// next frame. Once they've grown to fit the frames being recorded, starting
+/// passes and setting bindings no longer allocates.
+#[derive(Default)]
+struct Scratch {
+    passes: Vec<PassNode>,
+    reads: Vec<Vec<usize>>,
+    arena: Option<FrameArena>,
+}
 
-    // MUTABLE API ////////////////////////////////////////////////////////////
+impl Scratch {
+    /// Reclaim the allocations of a frame graph which is no longer reported,
+    /// along with the read lists its frame didn't use.
+    fn reclaim(&mut self, mut graph: FrameGraph, mut reads: Vec<Vec<usize>>) {
+        for mut pass in graph.passes.drain(..) {
+            pass.reads.clear();
+            reads.push(pass.reads);
+        }
+        self.passes = graph.passes;
+        self.reads = reads;
+    }
+}
 
-    pub fn update_pipeline<'a, T>(&mut self, pip: &'a T, p: T::PrepareContext, f: &mut Frame)
-    where
-        T: AbstractPipeline<'a>,
-    {
-        if let Some((buf, unifs)) = pip.prepare(p) {
-            self.device
-                .update_uniform_buffer::<T::Uniforms>(unifs.as_slice(), buf, &mut f.encoder);
+impl FrameGraph {
+    /// Render the graph in the DOT format, for use with Graphviz.
+    pub fn to_dot(&self) -> String {
+        let ids = self.resources();
+        let mut out = String::from("digraph frame {\n");
+
+        for (i, p) in self.passes.iter().enumerate() {
+            out.push_str(&format!(
+                "    pass{} [shape=box, label=\"pass {}\\n{}\\n{} draws, {} vertices\"];\n",
+                i,
+                i,
+                Self::op_name(&p.op),
+                p.stats.draws,
+                p.stats.vertices
+            ));
+        }
+        for (i, id) in ids.iter().enumerate() {
+            let format = self
+                .passes
+                .iter()
+                .find(|p| p.target == *id)
+                .and_then(|p| p.format)
+                .map(|f| format!("\\n{:?}", f))
+                .unwrap_or_default();
+            out.push_str(&format!(
+                "    texture{} [shape=ellipse, label=\"texture {}{}\"];\n",
+                i, i, format
+            ));
         }
+        for (i, p) in self.passes.iter().enumerate() {
+            for r in p.reads.iter() {
+                out.push_str(&format!(
+                    "    texture{} -> pass{} [label=\"sampled\"];\n",
+                    Self::index(&ids, *r),
+                    i
+                ));
+            }
+            out.push_str(&format!(
+                "    pass{} -> texture{} [label=\"attachment\"];\n",
+                i,
+                Self::index(&ids, p.target)
+            ));
+        }
+        out.push_str("}\n");
+        out
     }
 
-    pub fn frame(&mut self) -> Frame {
-        let encoder = self.device.create_command_encoder();
-        Frame::new(encoder)
+    /// Render the graph as JSON.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rgx::core::{FrameGraph, PassNode, PassOp, PassStats, Rgba, TextureFormat};
+    ///
+    /// let pass = PassNode {
+    ///     op: PassOp::Clear(Rgba::TRANSPARENT),
+    ///     target: 0xa,
+    ///     format: Some(TextureFormat::Bgra8Unorm),
+    ///     reads: vec![0xb],
+    ///     stats: PassStats { draws: 1, vertices: 6, bindings: 1 },
+    /// };
+    /// let graph = FrameGraph { passes: vec![pass] };
+    ///
+    /// assert_eq!(
+    ///     graph.to_json(),
+    ///     r#"{"textures":2,"passes":[{"op":"clear","target":1,"format":"Bgra8Unorm","reads":[0],"draws":1,"vertices":6}]}"#
+    /// );
+    /// ```
+    pub fn to_json(&self) -> String {
+        let ids = self.resources();
+        let passes: Vec<String> = self
+            .passes
+            .iter()
+            .map(|p| {
+                let reads: Vec<String> = p
+                    .reads
+                    .iter()
+                    .map(|r| Self::index(&ids, *r).to_string())
+                    .collect();
+                format!(
+                    "{{\"op\":{},\"target\":{},\"format\":{},\"reads\":[{}],\"draws\":{},\"vertices\":{}}}",
+                    json_string(Self::op_name(&p.op)),
+                    Self::index(&ids, p.target),
+                    p.format
+                        .map(|f| json_string(&format!("{:?}", f)))
+                        .unwrap_or_else(|| String::from("null")),
+                    reads.join(","),
+                    p.stats.draws,
+                    p.stats.vertices
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"textures\":{},\"passes\":[{}]}}",
+            ids.len(),
+            passes.join(",")
+        )
     }
 
-    pub fn submit(&mut self, frame: Frame) {
-        self.device.submit(&[frame.encoder.finish()]);
+    /// All resources in the graph, in order of first use.
+    fn resources(&self) -> Vec<usize> {
+        let mut ids = Vec::new();
+        for p in self.passes.iter() {
+            for id in p.reads.iter().chain(std::iter::once(&p.target)) {
+                if !ids.contains(id) {
+                    ids.push(*id);
+                }
+            }
+        }
+        ids
     }
 
-    pub fn prepare(&mut self, commands: &[Op]) {
-        let mut encoder = self.device.create_command_encoder();
-        for c in commands.iter() {
-            c.encode(&mut self.device, &mut encoder);
+    fn index(ids: &[usize], id: usize) -> usize {
+        ids.iter().position(|i| *i == id).unwrap_or_default()
+    }
+
+    fn op_name(op: &PassOp) -> &'static str {
+        match op {
+            PassOp::Clear(_) => "clear",
+            PassOp::ClearDepthStencil(_, _, _) => "clear-depth-stencil",
+            PassOp::Load() => "load",
+            PassOp::DontCare() => "dont-care",
         }
-        self.device.submit(&[encoder.finish()]);
     }
 }
 
-pub enum Op<'a> {
-    Clear(&'a dyn Canvas, Rgba),
-    Fill(&'a dyn Canvas, &'a [u8]),
-    Transfer(&'a dyn Canvas, &'a [u8], u32, u32, u32, u32),
-    Blit(&'a dyn Canvas, Rect<f32>, Rect<f32>),
+/// Performance report of a submitted frame, see
+/// [`Renderer::export_frame_report`].
+///
+/// Timings are measured on the CPU, since GPU timestamps aren't available.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use rgx::core::{
+///     FrameGraph, FrameReport, PassNode, PassOp, PassStats, Rgba, TextureFormat,
+/// };
+///
+/// let pass = PassNode {
+///     op: PassOp::Load(),
+///     target: 0xa,
+///     format: Some(TextureFormat::Bgra8Unorm),
+///     reads: vec![],
+///     stats: PassStats { draws: 2, vertices: 12, bindings: 2 },
+/// };
+/// let report = FrameReport {
+///     frame: 7,
+///     record: Duration::from_micros(250),
+///     submit: Duration::from_micros(40),
+///     uploaded: 1024,
+///     graph: FrameGraph { passes: vec![pass.clone(), pass] },
+/// };
+///
+/// assert_eq!(report.stats(), PassStats { draws: 4, vertices: 24, bindings: 4 });
+/// assert!(report.to_json().starts_with(
+///     r#"{"frame":7,"timings":{"record_us":250,"submit_us":40},"memory":{"uploaded":1024},"stats":{"passes":2,"draws":4,"vertices":24,"bindings":4},"graph":{"#
+/// ));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct FrameReport {
+    /// Number of frames submitted so far, including this one.
+    pub frame: u64,
+    /// Time spent recording the frame, from [`Renderer::frame`] to
+    /// [`Renderer::submit`].
+    pub record: Duration,
+    /// Time spent submitting the frame's commands.
+    pub submit: Duration,
+    /// Bytes of texture and uniform data uploaded since the previous frame was
+    /// submitted.
+    pub uploaded: u64,
+    /// The passes of the frame.
+    pub graph: FrameGraph,
 }
 
-impl<'a> Op<'a> {
-    fn encode(&self, dev: &mut Device, encoder: &mut wgpu::CommandEncoder) {
-        match *self {
-            Op::Clear(f, color) => {
-                f.clear(color, dev, encoder);
-            }
-            Op::Fill(f, buf) => {
-                f.fill(buf, dev, encoder);
-            }
-            Op::Transfer(f, buf, w, h, tw, th) => {
-                f.transfer(buf, w, h, tw, th, dev, encoder);
-            }
-            Op::Blit(f, src, dst) => {
-                f.blit(src, dst, encoder);
-            }
-        }
+impl FrameReport {
+    /// Draw statistics summed over all passes.
+    pub fn stats(&self) -> PassStats {
+        self.graph
+            .passes
+            .iter()
+            .fold(PassStats::default(), |acc, p| PassStats {
+                draws: acc.draws + p.stats.draws,
+                vertices: acc.vertices + p.stats.vertices,
+                bindings: acc.bindings + p.stats.bindings,
+            })
+    }
+
+    /// Render the report as JSON. Timings are in microseconds.
+    pub fn to_json(&self) -> String {
+        let stats = self.stats();
+
+        format!(
+            "{{\"frame\":{},\"timings\":{{\"record_us\":{},\"submit_us\":{}}},\"memory\":{{\"uploaded\":{}}},\"stats\":{{\"passes\":{},\"draws\":{},\"vertices\":{},\"bindings\":{}}},\"graph\":{}}}",
+            self.frame,
+            self.record.as_micros(),
+            self.submit.as_micros(),
+            self.uploaded,
+            self.graph.passes.len(),
+            stats.draws,
+            stats.vertices,
+            stats.bindings,
+            self.graph.to_json()
+        )
     }
 }
 
@@ -1614,38 +6422,115 @@ impl<'a> Op<'a> {
 /// Device
 ///////////////////////////////////////////////////////////////////////////////
 
-pub struct Device {
-    device: wgpu::Device,
-    surface: wgpu::Surface,
+/// The GPU device and its queue. Cloning it is cheap, and yields a handle to
+/// the same device, so that several renderers can share it and its
+/// resources, eg. one renderer per window, plus an offscreen renderer for
+/// tests or background work.
+///
+/// ```ignore
+/// let gpu = Gpu::new();
+///
+/// let mut main = Renderer::with_gpu(&gpu, main_window.raw_window_handle());
+/// let mut tools = Renderer::with_gpu(&gpu, tools_window.raw_window_handle());
+/// let mut offscreen = Renderer::offscreen(&gpu);
+///
+/// // Textures created by one renderer can be drawn by the others.
+/// let texture = main.texture(w, h);
+/// ```
+#[derive(Clone)]
+pub struct Gpu {
+    instance: Arc<wgpu::Instance>,
+    device: Arc<Mutex<wgpu::Device>>,
 }
 
-impl Device {
-    pub fn new(window: RawWindowHandle) -> Self {
+impl Gpu {
+    pub fn new() -> Self {
         let instance = wgpu::Instance::new();
         let adapter = instance.request_adapter(&wgpu::RequestAdapterOptions {
             power_preference: wgpu::PowerPreference::LowPower,
         });
-        let surface = instance.create_surface(window);
+        let device = adapter.request_device(&wgpu::DeviceDescriptor {
+            extensions: wgpu::Extensions {
+                anisotropic_filtering: false,
+            },
+            limits: wgpu::Limits::default(),
+        });
 
         Self {
-            device: adapter.request_device(&wgpu::DeviceDescriptor {
-                extensions: wgpu::Extensions {
-                    anisotropic_filtering: false,
-                },
-                limits: wgpu::Limits::default(),
-            }),
-            surface,
+            instance: Arc::new(instance),
+            device: Arc::new(Mutex::new(device)),
         }
     }
 
-    pub fn create_command_encoder(&self) -> wgpu::CommandEncoder {
+    /// Lock the device. The lock should only be held for the duration of a
+    /// single wgpu call.
+    fn device(&self) -> MutexGuard<'_, wgpu::Device> {
         self.device
+            .lock()
+            .expect("fatal: GPU device lock was poisoned")
+    }
+}
+
+impl Default for Gpu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates resources on a [`Gpu`], and presents to a window surface, unless
+/// it is an offscreen device.
+pub struct Device {
+    gpu: Gpu,
+    surface: Option<wgpu::Surface>,
+}
+
+impl Device {
+    pub fn new(window: RawWindowHandle) -> Self {
+        Self::with_gpu(&Gpu::new(), window)
+    }
+
+    /// Create a device presenting to the given window, on a shared GPU.
+    pub fn with_gpu(gpu: &Gpu, window: RawWindowHandle) -> Self {
+        Self {
+            surface: Some(gpu.instance.create_surface(window)),
+            gpu: gpu.clone(),
+        }
+    }
+
+    /// Create a device without a surface, eg. for rendering to framebuffers
+    /// in tests or on worker threads.
+    pub fn offscreen(gpu: &Gpu) -> Self {
+        Self {
+            gpu: gpu.clone(),
+            surface: None,
+        }
+    }
+
+    /// The GPU the device creates resources on.
+    pub fn gpu(&self) -> &Gpu {
+        &self.gpu
+    }
+
+    pub fn create_command_encoder(&self) -> wgpu::CommandEncoder {
+        self.gpu
+            .device()
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 })
     }
 
-    pub fn create_swap_chain(&self, w: u32, h: u32, mode: PresentMode) -> wgpu::SwapChain {
-        let desc = SwapChain::descriptor(w, h, mode);
-        self.device.create_swap_chain(&self.surface, &desc)
+    pub fn create_swap_chain(
+        &self,
+        w: u32,
+        h: u32,
+        mode: PresentMode,
+        format: TextureFormat,
+    ) -> wgpu::SwapChain {
+        let desc = SwapChain::descriptor(w, h, mode, format);
+        let surface = self
+            .surface
+            .as_ref()
+            .expect("fatal: offscreen devices can't create swap chains");
+
+        self.gpu.device().create_swap_chain(surface, &desc)
     }
 
     pub fn create_pipeline_layout(&self, ss: &[Set]) -> PipelineLayout {
@@ -1656,59 +6541,212 @@ impl Device {
         PipelineLayout { sets }
     }
 
-    pub fn create_shader(&self, _name: &str, source: &[u8], _stage: ShaderStage) -> Shader {
+    pub fn create_shader(&self, name: &str, source: &[u8], stage: ShaderStage) -> Shader {
+        if let Err(err) = validate_spirv(source, stage) {
+            panic!("fatal: invalid {}: {}", name, err);
+        }
         let buf = std::io::Cursor::new(source);
         let spv = wgpu::read_spirv(buf).unwrap();
 
         Shader {
-            module: self.device.create_shader_module(spv.as_slice()),
+            module: self.gpu.device().create_shader_module(spv.as_slice()),
+            bindings: ShaderBinding::reflect(spv.as_slice()),
         }
     }
 
     pub fn create_encoder(&self) -> wgpu::CommandEncoder {
-        self.device
+        self.gpu
+            .device()
             .create_command_encoder(&wgpu::CommandEncoderDescriptor { todo: 0 })
     }
 
     pub fn create_texture(&self, w: u32, h: u32) -> Texture {
+        self.create_texture_with_format(w, h, TextureFormat::Rgba8Unorm)
+    }
+
+    pub fn create_texture_with_format(&self, w: u32, h: u32, format: TextureFormat) -> Texture {
         let texture_extent = wgpu::Extent3d {
             width: w,
             height: h,
             depth: 1,
         };
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+        let texture = self.gpu.device().create_texture(&wgpu::TextureDescriptor {
             size: texture_extent,
             array_layer_count: 1,
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
+            format: format.to_wgpu(),
+            usage: wgpu::TextureUsage::SAMPLED
+                | wgpu::TextureUsage::COPY_DST
+                | wgpu::TextureUsage::COPY_SRC,
+        });
+        let texture_view = texture.create_default_view();
+
+        Texture {
+            wgpu: Arc::new(texture),
+            view: Arc::new(texture_view),
+            id: next_resource_id(),
+            extent: texture_extent,
+            format,
+            levels: 1,
+            layers: 1,
+            dimension: wgpu::TextureViewDimension::D2,
+            w,
+            h,
+        }
+    }
+
+    /// Create a texture array, sampled in shaders as a `texture2DArray`.
+    pub fn create_texture_array(&self, w: u32, h: u32, layers: u32) -> Texture {
+        assert!(
+            layers > 0,
+            "fatal: texture arrays must have at least one layer"
+        );
+        self.create_layered_texture(w, h, layers, wgpu::TextureViewDimension::D2Array)
+    }
+
+    /// Create a cube texture, sampled in shaders as a `textureCube`.
+    pub fn create_cube_texture(&self, size: u32) -> Texture {
+        self.create_layered_texture(
+            size,
+            size,
+            CubeFace::COUNT,
+            wgpu::TextureViewDimension::Cube,
+        )
+    }
+
+    fn create_layered_texture(
+        &self,
+        w: u32,
+        h: u32,
+        layers: u32,
+        dimension: wgpu::TextureViewDimension,
+    ) -> Texture {
+        let texture_extent = wgpu::Extent3d {
+            width: w,
+            height: h,
+            depth: 1,
+        };
+        let texture = self.gpu.device().create_texture(&wgpu::TextureDescriptor {
+            size: texture_extent,
+            array_layer_count: layers,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsage::SAMPLED
+                | wgpu::TextureUsage::COPY_DST
+                | wgpu::TextureUsage::COPY_SRC,
+        });
+        let texture_view = texture.create_view(&wgpu::TextureViewDescriptor {
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            dimension,
+            aspect: wgpu::TextureAspect::All,
+            base_mip_level: 0,
+            level_count: 1,
+            base_array_layer: 0,
+            array_layer_count: layers,
+        });
+
+        Texture {
+            wgpu: Arc::new(texture),
+            view: Arc::new(texture_view),
+            id: next_resource_id(),
+            extent: texture_extent,
+            format: TextureFormat::Rgba8Unorm,
+            levels: 1,
+            layers,
+            dimension,
+            w,
+            h,
+        }
+    }
+
+    /// Create a texture with a full chain of mip levels. Only the base level
+    /// is filled by uploads: the others can be generated with
+    /// [`crate::kit::mipmap::MipmapGenerator`].
+    pub fn create_texture_with_mipmaps(&self, w: u32, h: u32) -> Texture {
+        let levels = Texture::mip_level_count(w, h);
+        let texture_extent = wgpu::Extent3d {
+            width: w,
+            height: h,
+            depth: 1,
+        };
+        let texture = self.gpu.device().create_texture(&wgpu::TextureDescriptor {
+            size: texture_extent,
+            array_layer_count: 1,
+            mip_level_count: levels,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Rgba8Unorm,
-            usage: wgpu::TextureUsage::SAMPLED | wgpu::TextureUsage::COPY_DST,
+            usage: wgpu::TextureUsage::SAMPLED
+                | wgpu::TextureUsage::COPY_DST
+                | wgpu::TextureUsage::COPY_SRC
+                | wgpu::TextureUsage::OUTPUT_ATTACHMENT,
         });
         let texture_view = texture.create_default_view();
 
         Texture {
-            wgpu: texture,
-            view: texture_view,
+            wgpu: Arc::new(texture),
+            view: Arc::new(texture_view),
+            id: next_resource_id(),
             extent: texture_extent,
+            format: TextureFormat::Rgba8Unorm,
+            levels,
+            layers: 1,
+            dimension: wgpu::TextureViewDimension::D2,
             w,
             h,
         }
     }
 
     pub fn create_framebuffer(&self, w: u32, h: u32) -> Framebuffer {
+        self.create_framebuffer_with_format(w, h, TextureFormat::default())
+    }
+
+    pub fn create_depth_buffer(&self, w: u32, h: u32) -> DepthBuffer {
+        let texture = self.gpu.device().create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: w,
+                height: h,
+                depth: 1,
+            },
+            array_layer_count: 1,
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: DepthBuffer::FORMAT,
+            usage: wgpu::TextureUsage::OUTPUT_ATTACHMENT,
+        });
+        let view = texture.create_default_view();
+
+        DepthBuffer {
+            wgpu: Arc::new(texture),
+            view: Arc::new(view),
+            w,
+            h,
+        }
+    }
+
+    pub fn create_framebuffer_with_format(
+        &self,
+        w: u32,
+        h: u32,
+        format: TextureFormat,
+    ) -> Framebuffer {
         let extent = wgpu::Extent3d {
             width: w,
             height: h,
             depth: 1,
         };
-        let texture = self.device.create_texture(&wgpu::TextureDescriptor {
+        let texture = self.gpu.device().create_texture(&wgpu::TextureDescriptor {
             size: extent,
             array_layer_count: 1,
             mip_level_count: 1,
             sample_count: 1,
             dimension: wgpu::TextureDimension::D2,
-            format: wgpu::TextureFormat::Bgra8Unorm,
+            format: format.to_wgpu(),
             usage: wgpu::TextureUsage::SAMPLED
                 | wgpu::TextureUsage::COPY_DST
                 | wgpu::TextureUsage::COPY_SRC
@@ -1718,9 +6756,14 @@ impl Device {
 
         Framebuffer {
             texture: Texture {
-                wgpu: texture,
-                view,
+                wgpu: Arc::new(texture),
+                view: Arc::new(view),
+                id: next_resource_id(),
                 extent,
+                format,
+                levels: 1,
+                layers: 1,
+                dimension: wgpu::TextureViewDimension::D2,
                 w,
                 h,
             },
@@ -1738,18 +6781,25 @@ impl Device {
             "layout slot count does not match bindings"
         );
 
-        let mut bindings = Vec::new();
+        let mut bindings: SmallVec<[wgpu::Binding; 4]> = SmallVec::new();
+        let mut views = SmallVec::new();
 
         for (i, b) in binds.iter().enumerate() {
+            if let Some(id) = b.view_id() {
+                views.push(id);
+            }
             bindings.push(b.binding(i as u32));
         }
 
         BindingGroup::new(
             layout.set_index,
-            self.device.create_bind_group(&wgpu::BindGroupDescriptor {
-                layout: &layout.wgpu,
-                bindings: bindings.as_slice(),
-            }),
+            self.gpu
+                .device()
+                .create_bind_group(&wgpu::BindGroupDescriptor {
+                    layout: &layout.wgpu,
+                    bindings: bindings.as_slice(),
+                }),
+            views,
         )
     }
 
@@ -1758,11 +6808,14 @@ impl Device {
         T: 'static + Copy,
     {
         VertexBuffer {
-            wgpu: self
-                .device
-                .create_buffer_mapped(vertices.len(), wgpu::BufferUsage::VERTEX)
-                .fill_from_slice(vertices),
+            wgpu: Arc::new(
+                self.gpu
+                    .device()
+                    .create_buffer_mapped(vertices.len(), wgpu::BufferUsage::VERTEX)
+                    .fill_from_slice(vertices),
+            ),
             size: vertices.len() as u32,
+            bytesize: std::mem::size_of_val(vertices) as u64,
         }
     }
 
@@ -1773,35 +6826,114 @@ impl Device {
         UniformBuffer {
             size: std::mem::size_of::<T>(),
             count: buf.len(),
-            wgpu: self
-                .device
-                .create_buffer_mapped::<T>(
-                    buf.len(),
-                    wgpu::BufferUsage::UNIFORM
-                        | wgpu::BufferUsage::COPY_DST
-                        | wgpu::BufferUsage::COPY_SRC,
-                )
-                .fill_from_slice(buf),
+            wgpu: Arc::new(
+                self.gpu
+                    .device()
+                    .create_buffer_mapped::<T>(
+                        buf.len(),
+                        wgpu::BufferUsage::UNIFORM
+                            | wgpu::BufferUsage::COPY_DST
+                            | wgpu::BufferUsage::COPY_SRC,
+                    )
+                    .fill_from_slice(buf),
+            ),
+        }
+    }
+
+    pub fn create_uniform_array<T>(&self, records: &[T]) -> UniformArray
+    where
+        T: 'static + Copy,
+    {
+        assert_eq!(
+            std::mem::size_of::<T>() % 16,
+            0,
+            "fatal: uniform array record size must be a multiple of 16 bytes"
+        );
+        assert!(
+            std::mem::size_of_val(records) <= MAX_UNIFORM_BUFFER_RANGE,
+            "fatal: uniform array must be at most {} bytes",
+            MAX_UNIFORM_BUFFER_RANGE
+        );
+        UniformArray {
+            buf: self.create_uniform_buffer(records),
+        }
+    }
+
+    pub fn create_push_buffer<T>(
+        &self,
+        layout: &BindingGroupLayout,
+        capacity: usize,
+    ) -> PushBuffer<T>
+    where
+        T: 'static + Copy,
+    {
+        assert!(
+            std::mem::size_of::<T>() as u64 <= DYNAMIC_OFFSET_ALIGNMENT,
+            "fatal: push buffer record size must be at most {} bytes",
+            DYNAMIC_OFFSET_ALIGNMENT
+        );
+        assert!(capacity > 0, "fatal: push buffer capacity must be non-zero");
+        assert_eq!(
+            layout.types.as_slice(),
+            &[BindingType::UniformBufferDynamic],
+            "fatal: push buffer layout must have a single dynamic uniform buffer binding"
+        );
+
+        let size = std::mem::size_of::<Aligned<T>>();
+        let buf = UniformBuffer {
+            size,
+            count: capacity,
+            wgpu: Arc::new(self.gpu.device().create_buffer(&wgpu::BufferDescriptor {
+                size: (size * capacity) as wgpu::BufferAddress,
+                usage: wgpu::BufferUsage::UNIFORM | wgpu::BufferUsage::COPY_DST,
+            })),
+        };
+        let binding = self.create_binding_group(layout, &[&buf]);
+
+        PushBuffer {
+            records: Vec::with_capacity(capacity),
+            buf,
+            binding,
         }
     }
 
     pub fn create_index(&self, indices: &[u16]) -> IndexBuffer {
         let index_buf = self
-            .device
+            .gpu
+            .device()
             .create_buffer_mapped(indices.len(), wgpu::BufferUsage::INDEX)
             .fill_from_slice(indices);
         IndexBuffer { wgpu: index_buf }
     }
 
     pub fn create_sampler(&self, min_filter: Filter, mag_filter: Filter) -> Sampler {
+        self.create_sampler_with_address_mode(min_filter, mag_filter, AddressMode::Repeat)
+    }
+
+    pub fn create_sampler_with_address_mode(
+        &self,
+        min_filter: Filter,
+        mag_filter: Filter,
+        mode: AddressMode,
+    ) -> Sampler {
+        self.create_sampler_with_mipmap_filter(min_filter, mag_filter, Filter::Nearest, mode)
+    }
+
+    pub fn create_sampler_with_mipmap_filter(
+        &self,
+        min_filter: Filter,
+        mag_filter: Filter,
+        mipmap_filter: Filter,
+        mode: AddressMode,
+    ) -> Sampler {
         Sampler {
-            wgpu: self.device.create_sampler(&wgpu::SamplerDescriptor {
-                address_mode_u: wgpu::AddressMode::Repeat,
-                address_mode_v: wgpu::AddressMode::Repeat,
-                address_mode_w: wgpu::AddressMode::Repeat,
+            wgpu: self.gpu.device().create_sampler(&wgpu::SamplerDescriptor {
+                address_mode_u: mode.to_wgpu(),
+                address_mode_v: mode.to_wgpu(),
+                address_mode_w: mode.to_wgpu(),
                 mag_filter: mag_filter.to_wgpu(),
                 min_filter: min_filter.to_wgpu(),
-                mipmap_filter: wgpu::FilterMode::Nearest,
+                mipmap_filter: mipmap_filter.to_wgpu(),
                 lod_min_clamp: -100.0,
                 lod_max_clamp: 100.0,
                 compare_function: wgpu::CompareFunction::Always,
@@ -1811,6 +6943,7 @@ impl Device {
 
     pub fn create_binding_group_layout(&self, index: u32, slots: &[Binding]) -> BindingGroupLayout {
         let mut bindings = Vec::new();
+        let mut types = Vec::new();
 
         for s in slots {
             bindings.push(wgpu::BindGroupLayoutBinding {
@@ -1818,13 +6951,15 @@ impl Device {
                 visibility: s.stage.to_wgpu(),
                 ty: s.binding.to_wgpu(),
             });
+            types.push(s.binding);
         }
         let layout = self
-            .device
+            .gpu
+            .device()
             .create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
                 bindings: bindings.as_slice(),
             });
-        BindingGroupLayout::new(index, layout, bindings.len())
+        BindingGroupLayout::new(index, layout, types)
     }
 
     pub fn update_uniform_buffer<T: Copy + 'static>(
@@ -1834,7 +6969,8 @@ impl Device {
         encoder: &mut wgpu::CommandEncoder,
     ) {
         let src = self
-            .device
+            .gpu
+            .device()
             .create_buffer_mapped::<T>(
                 slice.len(),
                 wgpu::BufferUsage::UNIFORM
@@ -1855,35 +6991,46 @@ impl Device {
     // MUTABLE API ////////////////////////////////////////////////////////////
 
     pub fn submit(&mut self, cmds: &[wgpu::CommandBuffer]) {
-        self.device.get_queue().submit(cmds);
+        self.gpu.device().get_queue().submit(cmds);
     }
 
     // PRIVATE API ////////////////////////////////////////////////////////////
 
+    #[allow(clippy::too_many_arguments)]
     fn create_pipeline(
         &self,
         pipeline_layout: PipelineLayout,
         vertex_layout: VertexLayout,
+        instance_layout: Option<VertexLayout>,
         blending: Blending,
+        format: TextureFormat,
+        depth: Option<DepthState>,
         vs: &Shader,
         fs: &Shader,
     ) -> Pipeline {
-        let vertex_attrs = vertex_layout.to_wgpu();
+        let mut vertex_buffers = vec![vertex_layout.to_wgpu()];
+        if let Some(layout) = instance_layout.as_ref() {
+            vertex_buffers.push(layout.to_wgpu());
+        }
 
         let mut sets = Vec::new();
         for s in pipeline_layout.sets.iter() {
             sets.push(&s.wgpu);
         }
         let layout = &self
-            .device
+            .gpu
+            .device()
             .create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
                 bind_group_layouts: sets.as_slice(),
             });
 
-        let (src_factor, dst_factor, operation) = blending.to_wgpu();
+        let (color_blend, alpha_blend) = blending.to_wgpu();
+        let format = format.to_wgpu();
+        let sample_count = 1;
 
         let wgpu = self
-            .device
+            .gpu
+            .device()
             .create_render_pipeline(&wgpu::RenderPipelineDescriptor {
                 layout,
                 vertex_stage: wgpu::ProgrammableStageDescriptor {
@@ -1903,31 +7050,34 @@ impl Device {
                 }),
                 primitive_topology: wgpu::PrimitiveTopology::TriangleList,
                 color_states: &[wgpu::ColorStateDescriptor {
-                    // TODO: Try Bgra8UnormSrgb
-                    format: wgpu::TextureFormat::Bgra8Unorm,
-                    color_blend: wgpu::BlendDescriptor {
-                        src_factor,
-                        dst_factor,
-                        operation,
-                    },
-                    alpha_blend: wgpu::BlendDescriptor {
-                        src_factor,
-                        dst_factor,
-                        operation,
-                    },
+                    format,
+                    color_blend,
+                    alpha_blend,
                     write_mask: wgpu::ColorWrite::ALL,
                 }],
-                depth_stencil_state: None,
+                depth_stencil_state: depth.map(DepthState::to_wgpu),
                 index_format: wgpu::IndexFormat::Uint16,
-                vertex_buffers: &[vertex_attrs],
-                sample_count: 1,
+                vertex_buffers: vertex_buffers.as_slice(),
+                sample_count,
                 sample_mask: !0,
                 alpha_to_coverage_enabled: false,
             });
 
+        let mut bindings = vs.bindings.clone();
+        for b in fs.bindings.iter() {
+            if !bindings.contains(b) {
+                bindings.push(b.clone());
+            }
+        }
+
         Pipeline {
             layout: pipeline_layout,
             vertex_layout,
+            instance_layout,
+            bindings,
+            format,
+            sample_count,
+            depth,
             wgpu,
         }
     }
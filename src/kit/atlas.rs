@@ -0,0 +1,572 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::core;
+use crate::core::{Op, Rect, Rgba, Rgba8};
+
+use crate::kit::sprite2d::TextureRegion;
+
+/// The placement of every image in an atlas, in texels.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Layout {
+    pub width: u32,
+    pub height: u32,
+    pub regions: HashMap<String, Rect<u32>>,
+}
+
+/// Packs loose images, eg. decoded at startup, into a single atlas
+/// texture, so that they can be drawn from one sprite batch.
+///
+/// Images are packed on shelves, from the tallest to the shortest, with
+/// `padding` transparent texels between them to avoid bleeding when
+/// sampling with linear filtering.
+///
+/// Tiles drawn edge to edge can still show seams, since texels at the edge
+/// of a region are blended with the transparent padding. Setting `extrude`
+/// surrounds each image with copies of its edge texels, and regions can
+/// also be inset by half a texel with [`TextureRegion::inset`].
+///
+/// ```
+/// use rgx::core::{Rect, Rgba8};
+/// use rgx::kit::atlas::AtlasBuilder;
+///
+/// let mut builder = AtlasBuilder::new(64);
+/// builder.add("hero", 16, 32, &[Rgba8::RED; 16 * 32]);
+/// builder.add("coin", 8, 8, &[Rgba8::WHITE; 8 * 8]);
+/// builder.add("wall", 48, 16, &[Rgba8::BLUE; 48 * 16]);
+///
+/// let layout = builder.pack();
+/// assert_eq!((layout.width, layout.height), (57, 49));
+/// assert_eq!(layout.regions["hero"], Rect::new(0, 0, 16, 32));
+/// assert_eq!(layout.regions["wall"], Rect::new(0, 33, 48, 49));
+///
+/// let texels = builder.texels(&layout);
+/// assert_eq!(texels[layout.width as usize * 33], Rgba8::BLUE);
+/// assert_eq!(texels[16], Rgba8::TRANSPARENT);
+/// ```
+///
+/// With extruded edges, tiles drawn edge to edge stay seamless:
+///
+/// ```
+/// use rgx::core::{Rect, Rgba8};
+/// use rgx::kit::atlas::AtlasBuilder;
+///
+/// let mut builder = AtlasBuilder::new(64);
+/// builder.extrude = 1;
+/// builder.add("tile", 2, 2, &[Rgba8::RED, Rgba8::BLUE, Rgba8::WHITE, Rgba8::BLACK]);
+///
+/// let layout = builder.pack();
+/// assert_eq!((layout.width, layout.height), (4, 4));
+/// assert_eq!(layout.regions["tile"], Rect::new(1, 1, 3, 3));
+///
+/// let texels = builder.texels(&layout);
+/// assert_eq!(&texels[..4], &[Rgba8::RED, Rgba8::RED, Rgba8::BLUE, Rgba8::BLUE]);
+/// assert_eq!(&texels[12..], &[Rgba8::WHITE, Rgba8::WHITE, Rgba8::BLACK, Rgba8::BLACK]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct AtlasBuilder {
+    /// Maximum width of the atlas texture.
+    pub max_width: u32,
+    /// Transparent texels between images.
+    pub padding: u32,
+    /// Texels around each image filled with copies of its edges, in
+    /// addition to the padding.
+    pub extrude: u32,
+
+    images: Vec<(String, u32, u32, Vec<Rgba8>)>,
+}
+
+impl AtlasBuilder {
+    pub fn new(max_width: u32) -> Self {
+        Self {
+            max_width,
+            padding: 1,
+            extrude: 0,
+            images: Vec::new(),
+        }
+    }
+
+    /// Add a `w` by `h` image, with rows of texels from top to bottom.
+    pub fn add(&mut self, name: &str, w: u32, h: u32, texels: &[Rgba8]) {
+        assert_eq!(
+            texels.len(),
+            (w * h) as usize,
+            "fatal: image `{}` should have {} texels",
+            name,
+            w * h
+        );
+        assert!(
+            w + 2 * self.extrude <= self.max_width,
+            "fatal: image `{}` is wider than the atlas",
+            name
+        );
+        assert!(
+            self.images.iter().all(|(n, _, _, _)| n != name),
+            "fatal: duplicate image `{}` in atlas",
+            name
+        );
+        self.images.push((name.to_owned(), w, h, texels.to_vec()));
+    }
+
+    pub fn len(&self) -> usize {
+        self.images.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.is_empty()
+    }
+
+    /// Place the images, without building the atlas.
+    pub fn pack(&self) -> Layout {
+        let mut order: Vec<usize> = (0..self.images.len()).collect();
+        order.sort_by_key(|&i| {
+            let (_, w, h, _) = &self.images[i];
+            (std::cmp::Reverse(*h), std::cmp::Reverse(*w))
+        });
+
+        let (mut x, mut y, mut shelf) = (0, 0, 0);
+        let (mut width, mut height) = (0, 0);
+        let mut regions = HashMap::with_capacity(self.images.len());
+
+        let e = self.extrude;
+
+        for i in order {
+            let (name, w, h, _) = &self.images[i];
+            let (cw, ch) = (w + 2 * e, h + 2 * e);
+
+            if x > 0 && x + cw > self.max_width {
+                x = 0;
+                y += shelf + self.padding;
+                shelf = 0;
+            }
+            regions.insert(name.clone(), Rect::new(x + e, y + e, x + e + w, y + e + h));
+
+            width = width.max(x + cw);
+            height = height.max(y + ch);
+            shelf = shelf.max(ch);
+            x += cw + self.padding;
+        }
+
+        Layout {
+            width,
+            height,
+            regions,
+        }
+    }
+
+    /// The atlas texels for the given layout, with rows from top to
+    /// bottom.
+    pub fn texels(&self, layout: &Layout) -> Vec<Rgba8> {
+        let mut texels = vec![Rgba8::TRANSPARENT; (layout.width * layout.height) as usize];
+
+        let e = self.extrude as usize;
+
+        for (name, w, h, image) in self.images.iter() {
+            if *w == 0 || *h == 0 {
+                continue;
+            }
+            let r = layout.regions[name];
+            let (w, h) = (*w as usize, *h as usize);
+
+            // Rows above and below the image repeat its first and last rows,
+            // and each row is extended with its first and last texels.
+            for row in 0..h + 2 * e {
+                let src = &image[row.saturating_sub(e).min(h - 1) * w..][..w];
+                let offset = (r.y1 as usize + row - e) * layout.width as usize + r.x1 as usize;
+                let dst = &mut texels[offset - e..offset + w + e];
+
+                dst[..e].fill(src[0]);
+                dst[e..e + w].copy_from_slice(src);
+                dst[e + w..].fill(src[w - 1]);
+            }
+        }
+        texels
+    }
+
+    /// Pack the images and upload them to a new texture.
+    pub fn build(&self, renderer: &mut core::Renderer) -> Atlas {
+        let layout = self.pack();
+        let texels = self.texels(&layout);
+        let texture = renderer.texture(layout.width.max(1), layout.height.max(1));
+
+        if !texels.is_empty() {
+            let (head, body, tail) = unsafe { texels.align_to::<u8>() };
+            assert!(head.is_empty() && tail.is_empty());
+
+            renderer.prepare(&[Op::Fill(&texture, body)]);
+        }
+
+        Atlas {
+            texture,
+            regions: layout
+                .regions
+                .into_iter()
+                .map(|(name, r)| (name, r.map(|n| n as f32)))
+                .collect(),
+        }
+    }
+}
+
+/// A texture holding packed images, built with an [`AtlasBuilder`].
+pub struct Atlas {
+    pub texture: core::Texture,
+
+    regions: HashMap<String, Rect<f32>>,
+}
+
+impl Atlas {
+    /// The region of the named image.
+    pub fn region(&self, name: &str) -> Option<TextureRegion<'_>> {
+        self.regions
+            .get(name)
+            .map(|r| TextureRegion::new(&self.texture, *r))
+    }
+
+    /// The names of the images in the atlas.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.regions.keys().map(|k| k.as_str())
+    }
+}
+
+/// Allocates rectangles in a fixed-size area, eg. the regions of a
+/// [`TextureAtlas`].
+///
+/// Fresh space is allocated with a skyline packer, which places each
+/// rectangle as low as possible. Freed rectangles are kept and reused by
+/// later allocations that fit in them, splitting off the unused space.
+/// Freed space isn't merged back into the skyline, so an area which sees
+/// a lot of churn should eventually be cleared and repacked.
+///
+/// ```
+/// use rgx::core::Rect;
+/// use rgx::kit::atlas::AtlasPacker;
+///
+/// let mut packer = AtlasPacker::new(64, 64);
+///
+/// assert_eq!(packer.allocate(32, 16), Some(Rect::new(0, 0, 32, 16)));
+/// assert_eq!(packer.allocate(32, 32), Some(Rect::new(32, 0, 64, 32)));
+/// assert_eq!(packer.allocate(32, 16), Some(Rect::new(0, 16, 32, 32)));
+/// assert_eq!(packer.allocate(65, 1), None);
+///
+/// packer.free(Rect::new(32, 0, 64, 32));
+/// assert_eq!(packer.allocate(16, 16), Some(Rect::new(32, 0, 48, 16)));
+/// assert_eq!(packer.allocate(16, 16), Some(Rect::new(48, 0, 64, 16)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AtlasPacker {
+    pub width: u32,
+    pub height: u32,
+
+    /// Top edge of the allocated space, as segments from left to right
+    /// covering the full width.
+    skyline: Vec<Segment>,
+    free: Vec<Rect<u32>>,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Segment {
+    x: u32,
+    y: u32,
+    w: u32,
+}
+
+impl AtlasPacker {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            skyline: vec![Segment {
+                x: 0,
+                y: 0,
+                w: width,
+            }],
+            free: Vec::new(),
+        }
+    }
+
+    /// Allocate a `w` by `h` rectangle, or return `None` if there is no room
+    /// left for it.
+    pub fn allocate(&mut self, w: u32, h: u32) -> Option<Rect<u32>> {
+        self.allocate_free(w, h)
+            .or_else(|| self.allocate_skyline(w, h))
+    }
+
+    /// Make a rectangle returned by [`AtlasPacker::allocate`] available
+    /// again.
+    pub fn free(&mut self, rect: Rect<u32>) {
+        if rect.x2 > rect.x1 && rect.y2 > rect.y1 {
+            self.free.push(rect);
+        }
+    }
+
+    /// Free all rectangles.
+    pub fn clear(&mut self) {
+        *self = Self::new(self.width, self.height);
+    }
+
+    /// Take back a rectangle which was just freed, when nothing was
+    /// allocated since.
+    fn unfree(&mut self, rect: Rect<u32>) {
+        if let Some(i) = self.free.iter().rposition(|r| *r == rect) {
+            self.free.swap_remove(i);
+        }
+    }
+
+    /// Allocate from the smallest freed rectangle that fits, and keep the
+    /// space left over to its right and below it.
+    fn allocate_free(&mut self, w: u32, h: u32) -> Option<Rect<u32>> {
+        let (i, r) = self
+            .free
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.x2 - r.x1 >= w && r.y2 - r.y1 >= h)
+            .min_by_key(|(_, r)| (r.x2 - r.x1) * (r.y2 - r.y1))
+            .map(|(i, r)| (i, *r))?;
+
+        self.free.swap_remove(i);
+
+        let (x, y) = (r.x1 + w, r.y1 + h);
+
+        // Split along the longer leftover side, so that the larger of the two
+        // leftover rectangles is as large as possible.
+        let (right, below) = if r.x2 - x > r.y2 - y {
+            (Rect::new(x, r.y1, r.x2, r.y2), Rect::new(r.x1, y, x, r.y2))
+        } else {
+            (Rect::new(x, r.y1, r.x2, y), Rect::new(r.x1, y, r.x2, r.y2))
+        };
+        self.free(right);
+        self.free(below);
+
+        Some(Rect::new(r.x1, r.y1, x, y))
+    }
+
+    /// Allocate on the skyline, at the position with the lowest bottom
+    /// edge, and the leftmost one among those.
+    fn allocate_skyline(&mut self, w: u32, h: u32) -> Option<Rect<u32>> {
+        let (i, y) = (0..self.skyline.len())
+            .filter_map(|i| self.fit(i, w, h).map(|y| (i, y)))
+            .min_by_key(|&(i, y)| (y + h, self.skyline[i].x))?;
+        let x = self.skyline[i].x;
+
+        self.skyline.insert(i, Segment { x, y: y + h, w });
+
+        // Shorten or remove the segments now under the new one.
+        let right = x + w;
+        while let Some(s) = self.skyline.get_mut(i + 1) {
+            if s.x >= right {
+                break;
+            }
+            let overlap = right - s.x;
+
+            if s.w > overlap {
+                s.x += overlap;
+                s.w -= overlap;
+                break;
+            }
+            self.skyline.remove(i + 1);
+        }
+
+        // Merge neighbouring segments of the same height.
+        let mut j = 0;
+        while j + 1 < self.skyline.len() {
+            if self.skyline[j].y == self.skyline[j + 1].y {
+                self.skyline[j].w += self.skyline[j + 1].w;
+                self.skyline.remove(j + 1);
+            } else {
+                j += 1;
+            }
+        }
+
+        Some(Rect::new(x, y, right, y + h))
+    }
+
+    /// The top edge of a `w` by `h` rectangle placed at the left of the
+    /// `i`th segment, if it fits.
+    fn fit(&self, i: usize, w: u32, h: u32) -> Option<u32> {
+        let x = self.skyline[i].x;
+
+        if x + w > self.width {
+            return None;
+        }
+        let mut y = 0;
+
+        for s in self.skyline[i..].iter().take_while(|s| s.x < x + w) {
+            y = y.max(s.y);
+        }
+        if y + h > self.height {
+            return None;
+        }
+        Some(y)
+    }
+}
+
+/// A fixed-size texture which images are added to and evicted from while
+/// running, eg. glyphs or sprites streamed in as they are needed. Unlike an
+/// [`Atlas`], it doesn't need all images up front, and only uploads the
+/// texels of each new image.
+///
+/// Inserted images are queued, and uploaded together by
+/// [`TextureAtlas::flush`], which should be called once per frame before
+/// drawing from the atlas.
+///
+/// ```ignore
+/// let mut atlas = TextureAtlas::new(&renderer, 1024, 1024);
+///
+/// let uv = atlas.insert(glyph, w, h, &texels).expect("atlas is full");
+/// atlas.flush(&mut renderer);
+/// ...
+/// atlas.evict(&glyph);
+/// ```
+pub struct TextureAtlas<K> {
+    pub texture: core::Texture,
+    /// Transparent texels between images. Only applies to images inserted
+    /// after it is set.
+    pub padding: u32,
+
+    packer: AtlasPacker,
+    entries: HashMap<K, (Rect<u32>, Rect<u32>)>,
+    /// Allocations to upload on the next flush, with their texels.
+    pending: Vec<(Rect<u32>, Vec<Rgba8>)>,
+    /// Whether the texture was cleared, which is done on the first flush.
+    cleared: bool,
+}
+
+impl<K: Hash + Eq> TextureAtlas<K> {
+    pub fn new(renderer: &core::Renderer, w: u32, h: u32) -> Self {
+        Self {
+            texture: renderer.texture(w, h),
+            padding: 1,
+            packer: AtlasPacker::new(w, h),
+            entries: HashMap::new(),
+            pending: Vec::new(),
+            cleared: false,
+        }
+    }
+
+    /// Add a `w` by `h` image, with rows of texels from top to bottom, and
+    /// return its region in normalized texture coordinates. An image
+    /// already added with the same key is replaced. Returns `None` if
+    /// there is no room left for the image, in which case the atlas is left
+    /// as it was.
+    ///
+    /// The image is uploaded on the next [`TextureAtlas::flush`].
+    pub fn insert(&mut self, key: K, w: u32, h: u32, texels: &[Rgba8]) -> Option<Rect<f32>> {
+        assert_eq!(
+            texels.len(),
+            (w * h) as usize,
+            "fatal: image should have {} texels",
+            w * h
+        );
+        let old = self.entries.remove(&key);
+        if let Some((alloc, _)) = old {
+            self.packer.free(alloc);
+        }
+
+        let alloc = match self.packer.allocate(w + self.padding, h + self.padding) {
+            Some(alloc) => alloc,
+            None => {
+                if let Some((alloc, rect)) = old {
+                    self.packer.unfree(alloc);
+                    self.entries.insert(key, (alloc, rect));
+                }
+                return None;
+            }
+        };
+        let rect = Rect::new(alloc.x1, alloc.y1, alloc.x1 + w, alloc.y1 + h);
+
+        // The whole allocation is uploaded, so that the padding is cleared
+        // of any image previously placed there.
+        let (aw, ah) = (
+            (alloc.x2 - alloc.x1) as usize,
+            (alloc.y2 - alloc.y1) as usize,
+        );
+        let mut padded = vec![Rgba8::TRANSPARENT; aw * ah];
+
+        if w > 0 {
+            for (dst, src) in padded
+                .chunks_exact_mut(aw)
+                .zip(texels.chunks_exact(w as usize))
+            {
+                dst[..w as usize].copy_from_slice(src);
+            }
+        }
+        if !padded.is_empty() {
+            self.pending.push((alloc, padded));
+        }
+        self.entries.insert(key, (alloc, rect));
+
+        Some(self.uv(rect))
+    }
+
+    /// Upload the images inserted since the last flush. The texture is
+    /// cleared on the first flush.
+    pub fn flush(&mut self, renderer: &mut core::Renderer) {
+        if self.cleared && self.pending.is_empty() {
+            return;
+        }
+        let mut ops = Vec::with_capacity(self.pending.len() + 1);
+
+        if !self.cleared {
+            ops.push(Op::Clear(&self.texture, Rgba::TRANSPARENT));
+        }
+        for (alloc, texels) in self.pending.iter() {
+            let (head, body, tail) = unsafe { texels.align_to::<u8>() };
+            assert!(head.is_empty() && tail.is_empty());
+
+            let (w, h) = (alloc.x2 - alloc.x1, alloc.y2 - alloc.y1);
+            ops.push(Op::TransferAt(&self.texture, body, w, h, *alloc));
+        }
+        renderer.prepare(&ops);
+
+        self.pending.clear();
+        self.cleared = true;
+    }
+
+    /// The region of an image, in texels.
+    pub fn region(&self, key: &K) -> Option<TextureRegion<'_>> {
+        self.entries
+            .get(key)
+            .map(|(_, r)| TextureRegion::new(&self.texture, r.map(|n| n as f32)))
+    }
+
+    /// The region of an image, in normalized texture coordinates.
+    pub fn get(&self, key: &K) -> Option<Rect<f32>> {
+        self.entries.get(key).map(|(_, r)| self.uv(*r))
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Remove an image, making its space available to new images. Returns
+    /// whether the image was in the atlas.
+    pub fn evict(&mut self, key: &K) -> bool {
+        match self.entries.remove(key) {
+            Some((alloc, _)) => {
+                self.packer.free(alloc);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove all images.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.packer.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn uv(&self, rect: Rect<u32>) -> Rect<f32> {
+        TextureRegion::new(&self.texture, rect.map(|n| n as f32)).uv()
+    }
+}
@@ -0,0 +1,301 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use crate::core;
+use crate::core::{Blending, Op, PassOp, PassStats, Rect, Rgba};
+use crate::kit::sprite2d::Tint;
+use crate::kit::{shape2d, sprite2d, Repeat};
+use crate::math::{Matrix4, Point2};
+
+///////////////////////////////////////////////////////////////////////////
+// Scene
+///////////////////////////////////////////////////////////////////////////
+
+/// A stress scene, recorded and submitted once per frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Scene {
+    /// Batch and draw the given number of sprites.
+    Sprites(u32),
+    /// Batch and draw the given number of shapes, alternating rectangles
+    /// and circles.
+    Shapes(u32),
+    /// Transfer the given number of tiles to a texture.
+    Uploads(u32),
+}
+
+impl fmt::Display for Scene {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sprites(n) => write!(f, "{} sprites", n),
+            Self::Shapes(n) => write!(f, "{} shapes", n),
+            Self::Uploads(n) => write!(f, "{} uploads", n),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Report
+///////////////////////////////////////////////////////////////////////////
+
+/// The measurements of a scene over several frames.
+///
+/// ```
+/// use std::time::Duration;
+/// use rgx::core::PassStats;
+/// use rgx::kit::bench::{Report, Scene};
+///
+/// let report = Report {
+///     scene: Scene::Sprites(1000),
+///     frames: 4,
+///     elapsed: Duration::from_millis(8),
+///     record: Duration::from_millis(4),
+///     submit: Duration::from_millis(2),
+///     uploaded: 256,
+///     stats: PassStats { draws: 1, vertices: 6000, bindings: 1 },
+/// };
+///
+/// assert_eq!(report.frame_time(), Duration::from_millis(2));
+/// assert_eq!(
+///     report.to_string(),
+///     "1000 sprites: 4 frames, 2.000ms/frame (record 1.000ms, submit 0.500ms), \
+///      1 draws, 6000 vertices, 1 bindings, 256 bytes uploaded"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    /// The scene measured.
+    pub scene: Scene,
+    /// Number of frames submitted.
+    pub frames: u32,
+    /// Total time spent, from building the first frame's batches to the GPU
+    /// completing the last frame.
+    pub elapsed: Duration,
+    /// Total time spent recording frames.
+    pub record: Duration,
+    /// Total time spent submitting frames.
+    pub submit: Duration,
+    /// Total bytes of texture and uniform data uploaded.
+    pub uploaded: u64,
+    /// Draw statistics of the last frame.
+    pub stats: PassStats,
+}
+
+impl Report {
+    /// The average time per frame.
+    pub fn frame_time(&self) -> Duration {
+        self.elapsed / self.frames.max(1)
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ms = |d: Duration| d.as_secs_f64() * 1000. / f64::from(self.frames.max(1));
+
+        write!(
+            f,
+            "{}: {} frames, {:.3}ms/frame (record {:.3}ms, submit {:.3}ms), \
+             {} draws, {} vertices, {} bindings, {} bytes uploaded",
+            self.scene,
+            self.frames,
+            ms(self.elapsed),
+            ms(self.record),
+            ms(self.submit),
+            self.stats.draws,
+            self.stats.vertices,
+            self.stats.bindings,
+            self.uploaded
+        )
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Bench
+///////////////////////////////////////////////////////////////////////////
+
+/// Runs stress scenes headlessly, on an offscreen renderer drawing to a
+/// framebuffer, so that changes to the batching and upload paths can be
+/// measured without a window, eg. in CI.
+///
+/// ```ignore
+/// let gpu = Gpu::new();
+/// let mut bench = Bench::new(&gpu, 1024, 768);
+///
+/// for scene in &[Scene::Sprites(10_000), Scene::Shapes(10_000), Scene::Uploads(256)] {
+///     println!("{}", bench.run(*scene, 120));
+/// }
+/// ```
+pub struct Bench {
+    renderer: core::Renderer,
+    target: core::Framebuffer,
+    sprites: sprite2d::Pipeline,
+    shapes: shape2d::Pipeline,
+    texture: core::Texture,
+    binding: core::BindingGroup,
+    tile: Vec<u8>,
+}
+
+impl Bench {
+    /// The size of the texture drawn by sprites and uploaded to.
+    pub const TEXTURE_SIZE: u32 = 256;
+    /// The size of the tiles uploaded by [`Scene::Uploads`].
+    pub const TILE_SIZE: u32 = 16;
+
+    pub fn new(gpu: &core::Gpu, w: u32, h: u32) -> Self {
+        let renderer = core::Renderer::offscreen(gpu);
+        let target = renderer.framebuffer(w, h);
+        let sprites: sprite2d::Pipeline = renderer.pipeline(w, h, Blending::default());
+        let shapes: shape2d::Pipeline = renderer.pipeline(w, h, Blending::default());
+
+        let size = Self::TEXTURE_SIZE;
+        let texture = renderer.texture(size, size);
+        let sampler = renderer.sampler(core::Filter::Nearest, core::Filter::Nearest);
+        let binding = sprites.binding(&renderer, &texture, &sampler);
+
+        let texels = vec![0xff; (size * size * 4) as usize];
+        let tile = vec![0x7f; (Self::TILE_SIZE * Self::TILE_SIZE * 4) as usize];
+
+        let mut bench = Self {
+            renderer,
+            target,
+            sprites,
+            shapes,
+            texture,
+            binding,
+            tile,
+        };
+        bench.renderer.prepare(&[Op::Fill(&bench.texture, &texels)]);
+        bench
+    }
+
+    /// Run a scene for the given number of frames, and wait for the GPU to
+    /// complete them.
+    pub fn run(&mut self, scene: Scene, frames: u32) -> Report {
+        let mut report = Report {
+            scene,
+            frames,
+            elapsed: Duration::default(),
+            record: Duration::default(),
+            submit: Duration::default(),
+            uploaded: 0,
+            stats: PassStats::default(),
+        };
+        let started = Instant::now();
+        let mut last = None;
+
+        for i in 0..frames {
+            let frame = self.frame(scene);
+
+            if i + 1 == frames {
+                last = Some(self.renderer.submit_with_signal(frame));
+            } else {
+                self.renderer.submit(frame);
+            }
+            self.renderer.poll();
+
+            let r = self.renderer.frame_report();
+            report.record += r.record;
+            report.submit += r.submit;
+            report.uploaded += r.uploaded;
+            report.stats = r.stats();
+        }
+
+        if let Some(handle) = last {
+            while !self.renderer.is_complete(handle) {
+                self.renderer.poll();
+            }
+        }
+        report.elapsed = started.elapsed();
+        report
+    }
+
+    fn frame(&mut self, scene: Scene) -> core::Frame {
+        let (w, h) = (self.target.width() as f32, self.target.height() as f32);
+
+        match scene {
+            Scene::Sprites(n) => {
+                let size = Self::TEXTURE_SIZE;
+                let mut batch = sprite2d::Batch::new(size, size);
+
+                for (i, dst) in grid(n, w, h).enumerate() {
+                    let src = Rect::new(0., 0., size as f32, size as f32);
+                    let color = Rgba::new(i as f32 / n as f32, 0.5, 0.5, 1.0);
+
+                    batch.add(src, dst, color, Tint::NONE, 1.0, Repeat::default());
+                }
+                let buffer = batch.finish(&self.renderer);
+                let mut frame = self.renderer.frame();
+
+                self.renderer
+                    .update_pipeline(&self.sprites, Matrix4::identity(), &mut frame);
+                {
+                    let mut pass = frame.pass(PassOp::Clear(Rgba::TRANSPARENT), &self.target);
+
+                    pass.set_pipeline(&self.sprites);
+                    pass.draw(&buffer, &self.binding);
+                }
+                frame.arena().keep(buffer);
+                frame
+            }
+            Scene::Shapes(n) => {
+                let mut batch = shape2d::Batch::new();
+                let stroke = shape2d::Stroke::new(1., Rgba::WHITE);
+                let fill = shape2d::Fill::Solid(Rgba::new(0.5, 0.5, 1.0, 1.0));
+
+                for (i, dst) in grid(n, w, h).enumerate() {
+                    if i % 2 == 0 {
+                        batch.add(shape2d::Shape::Rectangle(dst, stroke, fill));
+                    } else {
+                        let center =
+                            Point2::new(dst.x1 + dst.width() / 2., dst.y1 + dst.height() / 2.);
+                        let radius = dst.width().min(dst.height()) / 2.;
+
+                        batch.add(shape2d::Shape::Circle(center, radius, 16, stroke, fill));
+                    }
+                }
+                let buffer = batch.finish(&self.renderer);
+                let mut frame = self.renderer.frame();
+
+                self.renderer
+                    .update_pipeline(&self.shapes, Matrix4::identity(), &mut frame);
+                {
+                    let mut pass = frame.pass(PassOp::Clear(Rgba::TRANSPARENT), &self.target);
+
+                    pass.set_pipeline(&self.shapes);
+                    pass.draw_buffer(&buffer);
+                }
+                frame.arena().keep(buffer);
+                frame
+            }
+            Scene::Uploads(n) => {
+                let (size, tile, texture) = (Self::TILE_SIZE, &self.tile, &self.texture);
+                let cols = Self::TEXTURE_SIZE / size;
+                let ops: Vec<Op> = (0..n)
+                    .map(|i| {
+                        let (x, y) = (i % cols * size, i / cols % cols * size);
+                        let dst = Rect::new(x, y, x + size, y + size);
+                        Op::TransferAt(texture, tile, size, size, dst)
+                    })
+                    .collect();
+
+                self.renderer.prepare(&ops);
+
+                let mut frame = self.renderer.frame();
+                frame.pass(PassOp::Clear(Rgba::TRANSPARENT), &self.target);
+                frame
+            }
+        }
+    }
+}
+
+/// Lay out `n` cells in a square grid covering a `w` by `h` area.
+fn grid(n: u32, w: f32, h: f32) -> impl Iterator<Item = Rect<f32>> {
+    let cols = (n as f32).sqrt().ceil().max(1.) as u32;
+    let (cw, ch) = (w / cols as f32, h / cols as f32);
+
+    (0..n).map(move |i| {
+        let (x, y) = ((i % cols) as f32 * cw, (i / cols) as f32 * ch);
+        Rect::new(x, y, x + cw, y + ch)
+    })
+}
@@ -0,0 +1,104 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::math::*;
+
+/// A 2D camera, with a zoom level and a pan offset, mapping canvas (world)
+/// coordinates to the window.
+///
+/// Window coordinates, as found in input events, are in physical pixels.
+/// Pipelines project logical pixels, ie. physical pixels divided by the
+/// scale factor, as set with [`core::Renderer::set_scale_factor`]. The camera
+/// maps between the three, so that the math is done in one place.
+///
+/// ```
+/// use rgx::kit::camera::Camera2D;
+/// use rgx::math::Point2;
+///
+/// let mut cam = Camera2D::new(2.0);
+/// cam.zoom = 4.;
+///
+/// let p = cam.to_canvas(Point2::new(80., 40.));
+/// assert_eq!((p.x, p.y), (10., 5.));
+///
+/// let w = cam.to_window(p);
+/// assert_eq!((w.x, w.y), (80., 40.));
+///
+/// // Zooming keeps the anchor point fixed.
+/// cam.zoom_at(Point2::new(80., 40.), 8.);
+/// let p = cam.to_canvas(Point2::new(80., 40.));
+/// assert_eq!((p.x, p.y), (10., 5.));
+/// ```
+///
+/// [`core::Renderer::set_scale_factor`]: crate::core::Renderer::set_scale_factor
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera2D {
+    /// Logical pixels per canvas unit.
+    pub zoom: f32,
+    /// Position of the canvas origin, in logical pixels.
+    pub offset: Vector2<f32>,
+    /// Physical pixels per logical pixel.
+    pub scale_factor: f64,
+}
+
+impl Camera2D {
+    pub fn new(scale_factor: f64) -> Self {
+        Self {
+            zoom: 1.,
+            offset: Vector2::new(0., 0.),
+            scale_factor,
+        }
+    }
+
+    /// The transform to pass to kit pipelines, eg. via
+    /// [`core::Renderer::update_pipeline`](crate::core::Renderer::update_pipeline).
+    pub fn transform(&self) -> Matrix4<f32> {
+        Matrix4::from_translation(Vector3::new(self.offset.x, self.offset.y, 0.))
+            * Matrix4::from_nonuniform_scale(self.zoom, self.zoom, 1.)
+    }
+
+    /// Map a point in window coordinates, eg. a cursor position, to the canvas.
+    pub fn to_canvas(&self, p: Point2<f32>) -> Point2<f32> {
+        let logical = self.to_logical(p);
+
+        Point2::new(
+            (logical.x - self.offset.x) / self.zoom,
+            (logical.y - self.offset.y) / self.zoom,
+        )
+    }
+
+    /// Map a point on the canvas to window coordinates.
+    pub fn to_window(&self, p: Point2<f32>) -> Point2<f32> {
+        self.to_physical(Point2::new(
+            p.x * self.zoom + self.offset.x,
+            p.y * self.zoom + self.offset.y,
+        ))
+    }
+
+    /// Map a point in window coordinates to logical pixels.
+    pub fn to_logical(&self, p: Point2<f32>) -> Point2<f32> {
+        let s = self.scale_factor as f32;
+        Point2::new(p.x / s, p.y / s)
+    }
+
+    /// Map a point in logical pixels to window coordinates.
+    pub fn to_physical(&self, p: Point2<f32>) -> Point2<f32> {
+        let s = self.scale_factor as f32;
+        Point2::new(p.x * s, p.y * s)
+    }
+
+    /// Pan the camera by a distance in window coordinates, eg. a cursor drag.
+    pub fn pan(&mut self, delta: Vector2<f32>) {
+        let s = self.scale_factor as f32;
+        self.offset = Vector2::new(self.offset.x + delta.x / s, self.offset.y + delta.y / s);
+    }
+
+    /// Set the zoom level, keeping the canvas point under `anchor`, in window
+    /// coordinates, in place.
+    pub fn zoom_at(&mut self, anchor: Point2<f32>, zoom: f32) {
+        let fixed = self.to_canvas(anchor);
+        let logical = self.to_logical(anchor);
+
+        self.zoom = zoom;
+        self.offset = Vector2::new(logical.x - fixed.x * zoom, logical.y - fixed.y * zoom);
+    }
+}
@@ -0,0 +1,151 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// The number of present intervals used to detect the refresh interval.
+const SAMPLES: usize = 16;
+
+/// Measured frame times within this fraction of the refresh interval are
+/// snapped to it.
+const TOLERANCE: f64 = 0.1;
+
+/// Fixed updates to run for a frame, as returned by [`FrameClock::tick`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Ticks {
+    /// The number of fixed timestep updates to run.
+    pub updates: u32,
+    /// How far the rendered frame is between the last update and the next,
+    /// from `0` to `1`, for interpolating state.
+    pub alpha: f32,
+}
+
+/// A clock producing fixed timestep updates, decoupled from rendering.
+///
+/// Call [`FrameClock::tick`] once per presented frame. The clock measures
+/// the interval between presents to detect the display refresh interval,
+/// and snaps frame times which are close to it, so that jitter in the
+/// measured times doesn't make updates drift from vsync.
+///
+/// ```
+/// use rgx::kit::clock::FrameClock;
+/// use std::time::{Duration, Instant};
+///
+/// let step = Duration::from_micros(16_667);
+/// let mut clock = FrameClock::new(step);
+/// let mut now = Instant::now();
+///
+/// assert_eq!(clock.tick(now).updates, 0);
+///
+/// // Presents at 60 Hz, with some jitter.
+/// let jitter = Duration::from_micros(300);
+/// let mut updates = 0;
+/// for i in 0..60 {
+///     now += if i % 2 == 0 { step + jitter } else { step - jitter };
+///     updates += clock.tick(now).updates;
+/// }
+/// assert_eq!(updates, 60);
+/// assert!(clock.refresh_interval().is_some());
+/// ```
+pub struct FrameClock {
+    /// The duration of a fixed update.
+    pub timestep: Duration,
+    /// The maximum number of updates per frame, to avoid falling further
+    /// behind when updates are slower than real time.
+    pub max_updates: u32,
+
+    accumulator: Duration,
+    last: Option<Instant>,
+    intervals: VecDeque<Duration>,
+}
+
+impl FrameClock {
+    pub fn new(timestep: Duration) -> Self {
+        assert!(
+            timestep > Duration::from_secs(0),
+            "fatal: frame clock timestep must be non-zero"
+        );
+
+        Self {
+            timestep,
+            max_updates: 8,
+            accumulator: Duration::from_secs(0),
+            last: None,
+            intervals: VecDeque::with_capacity(SAMPLES),
+        }
+    }
+
+    /// Advance the clock to `now`, the time of the latest present, and return
+    /// the updates to run.
+    pub fn tick(&mut self, now: Instant) -> Ticks {
+        let last = self.last.replace(now);
+
+        if let Some(last) = last {
+            let delta = now.duration_since(last);
+
+            if self.intervals.len() == SAMPLES {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(delta);
+            self.accumulator += self.snap(delta);
+        }
+
+        let mut updates = 0;
+        while self.accumulator >= self.timestep {
+            self.accumulator -= self.timestep;
+            updates += 1;
+        }
+        if updates > self.max_updates {
+            updates = self.max_updates;
+        }
+
+        Ticks {
+            updates,
+            alpha: self.accumulator.as_secs_f32() / self.timestep.as_secs_f32(),
+        }
+    }
+
+    /// The detected refresh interval: the mean of the middle half of recent
+    /// present intervals, which ignores dropped frames and stalls. Returns
+    /// `None` until enough frames have been presented.
+    pub fn refresh_interval(&self) -> Option<Duration> {
+        if self.intervals.len() < SAMPLES / 2 {
+            return None;
+        }
+        let mut sorted: Vec<Duration> = self.intervals.iter().cloned().collect();
+        sorted.sort();
+
+        let n = sorted.len();
+        let middle = &sorted[n / 4..n - n / 4];
+        let sum: Duration = middle.iter().sum();
+
+        Some(sum / middle.len() as u32)
+    }
+
+    /// The detected refresh rate, in Hz.
+    pub fn refresh_rate(&self) -> Option<f64> {
+        self.refresh_interval().map(|i| 1. / i.as_secs_f64())
+    }
+
+    /// Forget the time of the last frame, eg. after the application was
+    /// paused, so that the pause doesn't produce a burst of updates.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.accumulator = Duration::from_secs(0);
+    }
+
+    /// Snap a frame time to a multiple of the refresh interval, if close.
+    fn snap(&self, delta: Duration) -> Duration {
+        if let Some(interval) = self.refresh_interval() {
+            let frames = (delta.as_secs_f64() / interval.as_secs_f64()).round();
+            let snapped = interval.as_secs_f64() * frames;
+
+            if frames >= 1.
+                && (delta.as_secs_f64() - snapped).abs() < interval.as_secs_f64() * TOLERANCE
+            {
+                return interval * frames as u32;
+            }
+        }
+        delta
+    }
+}
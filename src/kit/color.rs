@@ -0,0 +1,121 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core::{Rgba, Rgba8};
+
+/// An RGB color space, defined by its primaries and white point. Both
+/// spaces use the sRGB transfer function, so their encoded values only
+/// differ in gamut.
+///
+/// Colors are converted through CIE XYZ, which is enough to keep assets
+/// authored in a wide gamut, eg. Display P3 screenshots, from looking
+/// washed out or over-saturated when drawn on an sRGB display.
+///
+/// ```
+/// use rgx::core::Rgba;
+/// use rgx::kit::color::{self, ColorSpace};
+///
+/// // sRGB red is inside the P3 gamut, so it is less saturated there.
+/// let red = color::convert(Rgba::new(1., 0., 0., 1.), ColorSpace::Srgb, ColorSpace::DisplayP3);
+/// assert!((red.r - 0.9175).abs() < 1e-3);
+/// assert!((red.g - 0.2003).abs() < 1e-3);
+/// assert!((red.b - 0.1386).abs() < 1e-3);
+///
+/// let back = color::convert(red, ColorSpace::DisplayP3, ColorSpace::Srgb);
+/// assert!((back.r - 1.).abs() < 1e-3 && back.g.abs() < 1e-3 && back.b.abs() < 1e-3);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    /// The Display P3 color space, used by wide-gamut displays and
+    /// screenshots.
+    DisplayP3,
+}
+
+impl ColorSpace {
+    /// The matrix converting linear colors in this space to CIE XYZ, with a
+    /// D65 white point.
+    fn to_xyz(self) -> [[f32; 3]; 3] {
+        match self {
+            Self::Srgb => [
+                [0.412_456_4, 0.357_576_1, 0.180_437_5],
+                [0.212_672_9, 0.715_152_2, 0.072_175_0],
+                [0.019_333_9, 0.119_192, 0.950_304_1],
+            ],
+            Self::DisplayP3 => [
+                [0.486_570_9, 0.265_667_7, 0.198_217_3],
+                [0.228_974_6, 0.691_738_5, 0.079_286_9],
+                [0.000_000_0, 0.045_113_4, 1.043_944_4],
+            ],
+        }
+    }
+
+    /// The matrix converting linear colors from this space to `other`,
+    /// row by row.
+    pub fn matrix(self, other: Self) -> [[f32; 3]; 3] {
+        multiply(&invert(&other.to_xyz()), &self.to_xyz())
+    }
+}
+
+/// Convert an encoded color between color spaces. Colors outside of the
+/// target gamut are clamped.
+pub fn convert(color: Rgba, from: ColorSpace, to: ColorSpace) -> Rgba {
+    if from == to {
+        return color;
+    }
+    let m = from.matrix(to);
+    let lin = color.to_linear();
+    let channel = |row: &[f32; 3]| {
+        let c = row[0] * lin.r + row[1] * lin.g + row[2] * lin.b;
+        c.clamp(0., 1.)
+    };
+
+    Rgba::new(channel(&m[0]), channel(&m[1]), channel(&m[2]), color.a).to_srgb()
+}
+
+/// Convert texels between color spaces, eg. when importing an image
+/// authored in a wide gamut.
+pub fn convert_texels(texels: &mut [Rgba8], from: ColorSpace, to: ColorSpace) {
+    if from == to {
+        return;
+    }
+    for t in texels.iter_mut() {
+        *t = convert((*t).into(), from, to).into();
+    }
+}
+
+fn multiply(a: &[[f32; 3]; 3], b: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let mut m = [[0.; 3]; 3];
+
+    for (i, row) in m.iter_mut().enumerate() {
+        for (j, c) in row.iter_mut().enumerate() {
+            *c = (0..3).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    m
+}
+
+fn invert(m: &[[f32; 3]; 3]) -> [[f32; 3]; 3] {
+    let cofactor =
+        |r0: usize, r1: usize, c0: usize, c1: usize| m[r0][c0] * m[r1][c1] - m[r0][c1] * m[r1][c0];
+    let det = m[0][0] * cofactor(1, 2, 1, 2) - m[0][1] * cofactor(1, 2, 0, 2)
+        + m[0][2] * cofactor(1, 2, 0, 1);
+
+    [
+        [
+            cofactor(1, 2, 1, 2) / det,
+            -cofactor(0, 2, 1, 2) / det,
+            cofactor(0, 1, 1, 2) / det,
+        ],
+        [
+            -cofactor(1, 2, 0, 2) / det,
+            cofactor(0, 2, 0, 2) / det,
+            -cofactor(0, 1, 0, 2) / det,
+        ],
+        [
+            cofactor(1, 2, 0, 1) / det,
+            -cofactor(0, 2, 0, 1) / det,
+            cofactor(0, 1, 0, 1) / det,
+        ],
+    ]
+}
@@ -0,0 +1,17 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler   sam;
+
+layout(set = 1, binding = 0) uniform Group {
+	float opacity;
+} group;
+
+layout(location = 0) in  vec2 f_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+	// The layer holds premultiplied alpha, so opacity scales every channel.
+	fragColor = texture(sampler2D(tex, sam), f_uv) * group.opacity;
+}
@@ -0,0 +1,24 @@
+#version 450
+
+layout(set = 2, binding = 0) uniform texture2D tex;
+layout(set = 2, binding = 1) uniform sampler   sam;
+
+layout(set = 3, binding = 0) uniform texture2D mask;
+layout(set = 3, binding = 1) uniform sampler   mask_sam;
+
+layout(location = 0) in  vec2  f_uv;
+layout(location = 1) in  vec4  f_color;
+layout(location = 2) in  float f_opacity;
+layout(location = 3) in  vec2  f_mask_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+	vec4 texel = texture(sampler2D(tex, sam), vec2(f_uv.s, f_uv.t));
+	float coverage = texture(sampler2D(mask, mask_sam), f_mask_uv).a;
+
+	fragColor = vec4(
+		mix(texel.rgb, f_color.rgb, f_color.a),
+		texel.a * f_opacity * coverage
+	);
+}
@@ -0,0 +1,33 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform Globals {
+	mat4 ortho;
+	mat4 transform;
+	vec4 mask;
+} global;
+
+layout(set = 1, binding = 0) uniform Model {
+	mat4 transform;
+} model;
+
+layout(location = 0) in vec2  position;
+layout(location = 1) in vec2  uv;
+layout(location = 2) in vec4  color;
+layout(location = 3) in float opacity;
+
+layout(location = 0) out vec2  f_uv;
+layout(location = 1) out vec4  f_color;
+layout(location = 2) out float f_opacity;
+layout(location = 3) out vec2  f_mask_uv;
+
+void main() {
+	f_color = color;
+	f_uv = uv;
+	f_opacity = opacity;
+
+	gl_Position = global.ortho * global.transform * model.transform * vec4(position, 0.0, 1.0);
+
+	// Mask coordinates are in screen space, scaled and offset by `global.mask`.
+	vec2 screen = vec2(gl_Position.x * 0.5 + 0.5, 0.5 - gl_Position.y * 0.5);
+	f_mask_uv = screen * global.mask.xy + global.mask.zw;
+}
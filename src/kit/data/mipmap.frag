@@ -0,0 +1,14 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler   sam;
+
+layout(location = 0) in  vec2 f_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+	// Sampling the level above at the center of each destination texel
+	// with a linear filter averages the four source texels.
+	fragColor = texture(sampler2D(tex, sam), f_uv);
+}
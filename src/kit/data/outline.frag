@@ -0,0 +1,35 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler   sam;
+
+layout(set = 1, binding = 0) uniform Outline {
+	vec4  color;
+	vec2  texel;
+	float thickness;
+} outline;
+
+layout(location = 0) in  vec2 f_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+	vec4 texel = texture(sampler2D(tex, sam), f_uv);
+	float r = ceil(outline.thickness);
+
+	// Maximum alpha of the texels within `thickness` pixels, so that thick
+	// outlines have no gaps.
+	float a = 0.0;
+	for (float y = -r; y <= r; y += 1.0) {
+		for (float x = -r; x <= r; x += 1.0) {
+			vec2 d = vec2(x, y);
+			float inside = step(dot(d, d), outline.thickness * outline.thickness);
+			float alpha = textureLod(sampler2D(tex, sam), f_uv + outline.texel * d, 0.0).a;
+
+			a = max(a, alpha * inside);
+		}
+	}
+	vec4 line = vec4(outline.color.rgb, outline.color.a * a);
+
+	fragColor = mix(line, texel, texel.a);
+}
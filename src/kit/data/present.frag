@@ -0,0 +1,48 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler   sam;
+
+layout(set = 1, binding = 0) uniform Tonemap {
+	float exposure;
+	float enabled;
+	float decode;
+	float encode;
+	vec4  gamut[3];
+} tonemap;
+
+layout(location = 0) in  vec2 f_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+vec3 srgb_to_linear(vec3 c) {
+	c = max(c, 0.0);
+	return mix(c / 12.92, pow((c + 0.055) / 1.055, vec3(2.4)), step(0.04045, c));
+}
+
+vec3 linear_to_srgb(vec3 c) {
+	c = clamp(c, 0.0, 1.0);
+	return mix(c * 12.92, 1.055 * pow(c, vec3(1.0 / 2.4)) - 0.055, step(0.0031308, c));
+}
+
+void main() {
+	vec4 texel = texture(sampler2D(tex, sam), f_uv);
+
+	// Convert between color spaces in linear space. The rows of the gamut
+	// matrix map linear scene colors to linear display colors.
+	vec3 rgb = mix(texel.rgb, srgb_to_linear(texel.rgb), tonemap.decode);
+	rgb = vec3(
+		dot(tonemap.gamut[0].xyz, rgb),
+		dot(tonemap.gamut[1].xyz, rgb),
+		dot(tonemap.gamut[2].xyz, rgb)
+	);
+
+	// Reinhard tonemapping, for scenes rendered in a float format.
+	vec3 hdr = rgb * tonemap.exposure;
+	vec3 mapped = hdr / (hdr + 1.0);
+
+	rgb = mix(rgb, mapped, tonemap.enabled);
+	rgb = mix(rgb, linear_to_srgb(rgb), tonemap.encode);
+
+	fragColor = vec4(rgb, texel.a);
+}
@@ -0,0 +1,35 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform texture2D tex;
+layout(set = 0, binding = 1) uniform sampler   sam;
+
+layout(set = 1, binding = 0) uniform Shadow {
+	vec4  color;
+	vec2  direction;
+	vec2  offset;
+	float keep;
+} shadow;
+
+layout(location = 0) in  vec2 f_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+const float WEIGHTS[9] = float[](
+	0.016216, 0.054054, 0.121622, 0.194595, 0.227027,
+	0.194595, 0.121622, 0.054054, 0.016216
+);
+
+void main() {
+	vec2 uv = f_uv - shadow.offset;
+	vec4 texel = texture(sampler2D(tex, sam), uv);
+
+	// Gaussian blur of the alpha channel, along `direction`.
+	float a = 0.0;
+	for (int i = 0; i < 9; i++) {
+		a += texture(sampler2D(tex, sam), uv + shadow.direction * float(i - 4)).a * WEIGHTS[i];
+	}
+	vec4 tinted = vec4(shadow.color.rgb, shadow.color.a * a);
+
+	// When `keep` is set, the texel is passed through untouched.
+	fragColor = mix(tinted, texel, shadow.keep);
+}
@@ -1,8 +1,28 @@
 #version 450
 
-layout(location = 0) in  vec4 f_color;
-layout(location = 0) out vec4 fragColor;
+layout(set = 2, binding = 0) uniform Shading {
+	float gamma;
+	float width; // Width of the gradient texture, in texels.
+} shading;
+layout(set = 2, binding = 1) uniform texture2D gradient;
+layout(set = 2, binding = 2) uniform sampler   sam;
+
+layout(location = 0) in  vec4  f_color;
+layout(location = 1) in  float f_coverage;
+layout(location = 2) in  vec2  f_gradient;
+layout(location = 0) out vec4  fragColor;
 
 void main() {
-	fragColor = f_color;
+	// Offsets are mapped to texel centers, so that the ends of the ramp
+	// are its first and last stops.
+	float t = clamp(f_gradient.x, 0.0, 1.0);
+	vec2 uv = vec2((t * (shading.width - 1.0) + 0.5) / shading.width, 0.5);
+	vec4 color = mix(f_color, f_color * texture(sampler2D(gradient, sam), uv), f_gradient.y);
+
+	// Adjusting coverage by the target gamma approximates blending
+	// partially covered pixels in linear space. The fill's own alpha is
+	// left as-is.
+	float covered = pow(f_coverage, 1.0 / shading.gamma);
+
+	fragColor = vec4(color.rgb, color.a * covered);
 }
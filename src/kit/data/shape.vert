@@ -3,6 +3,7 @@
 layout(set = 0, binding = 0) uniform Globals {
 	mat4 ortho;
 	mat4 transform;
+	vec2 viewport; // Target size in physical pixels.
 } global;
 
 layout(set = 1, binding = 0) uniform Model {
@@ -11,11 +12,31 @@ layout(set = 1, binding = 0) uniform Model {
 
 layout(location = 0) in vec2 position;
 layout(location = 1) in vec4 color;
+layout(location = 2) in float snap;
+layout(location = 3) in vec2 extrude; // Offset in screen pixels.
+layout(location = 4) in float coverage;
+layout(location = 5) in vec2 gradient; // Gradient offset and weight.
 
 layout(location = 0) out vec4 f_color;
+layout(location = 1) out float f_coverage;
+layout(location = 2) out vec2 f_gradient;
 
 void main() {
 	f_color = color;
+	f_coverage = coverage;
+	f_gradient = gradient;
 
-	gl_Position = global.ortho * global.transform * model.transform * vec4(position, 0.0, 1.0);
+	// Screen space offsets are divided by the view scale, so that they
+	// stay the same size under zoom.
+	mat4 view = global.transform * model.transform;
+	float scale = length(view[0].xy);
+	vec2 p = position + extrude / scale;
+
+	vec4 pos = global.ortho * view * vec4(p, 0.0, 1.0);
+
+	// Round the position to device pixels, when snapping is enabled.
+	vec2 px = round((pos.xy * 0.5 + 0.5) * global.viewport);
+	vec2 snapped = px / global.viewport * 2.0 - 1.0;
+
+	gl_Position = vec4(mix(pos.xy, snapped, snap), pos.zw);
 }
@@ -3,17 +3,41 @@
 layout(set = 2, binding = 0) uniform texture2D tex;
 layout(set = 2, binding = 1) uniform sampler   sam;
 
+// Chroma key and palette substitution. Entries are only active when
+// their alpha is set.
+layout(set = 2, binding = 2) uniform Key {
+	vec4  color;
+	vec4  from[4];
+	vec4  to[4];
+	float tolerance;
+} key;
+
 layout(location = 0) in  vec2  f_uv;
 layout(location = 1) in  vec4  f_color;
 layout(location = 2) in  float f_opacity;
+layout(location = 3) in  vec4  f_tint;
+layout(location = 4) in  vec4  f_additive;
 
 layout(location = 0) out vec4 fragColor;
 
+float matches(vec3 rgb, vec4 entry) {
+	return (distance(rgb, entry.rgb) <= key.tolerance && entry.a > 0.5) ? 1.0 : 0.0;
+}
+
 void main() {
 	vec4 texel = texture(sampler2D(tex, sam), vec2(f_uv.s, f_uv.t));
+	float keyed = matches(texel.rgb, key.color);
+
+	vec3 rgb = texel.rgb;
+	for (int i = 0; i < 4; i++) {
+		rgb = mix(rgb, key.to[i].rgb, matches(texel.rgb, key.from[i]));
+	}
+
+	vec4 tinted = vec4(
+		mix(rgb, f_color.rgb, f_color.a),
+		texel.a * f_opacity * (1.0 - keyed)
+	) * f_tint;
 
-	fragColor = vec4(
-		mix(texel.rgb, f_color.rgb, f_color.a),
-		texel.a * f_opacity
-	);
+	// The additive term's alpha is its intensity, eg. for hit flashes.
+	fragColor = vec4(tinted.rgb + f_additive.rgb * f_additive.a, tinted.a);
 }
@@ -3,25 +3,44 @@
 layout(set = 0, binding = 0) uniform Globals {
 	mat4 ortho;
 	mat4 transform;
+	vec2 viewport; // Target size in physical pixels.
 } global;
 
 layout(set = 1, binding = 0) uniform Model {
 	mat4 transform;
+	vec4 tint;
 } model;
 
+layout(set = 3, binding = 0) uniform Material {
+	vec4 uv; // Scale (xy) and offset (zw) of the texture coordinates.
+} material;
+
 layout(location = 0) in vec2  position;
 layout(location = 1) in vec2  uv;
 layout(location = 2) in vec4  color;
 layout(location = 3) in float opacity;
+layout(location = 4) in vec4  tint;
+layout(location = 5) in vec4  additive;
+layout(location = 6) in float snap;
 
 layout(location = 0) out vec2  f_uv;
 layout(location = 1) out vec4  f_color;
 layout(location = 2) out float f_opacity;
+layout(location = 3) out vec4  f_tint;
+layout(location = 4) out vec4  f_additive;
 
 void main() {
 	f_color = color;
-	f_uv = uv;
+	f_uv = uv * material.uv.xy + material.uv.zw;
 	f_opacity = opacity;
+	f_tint = model.tint * tint;
+	f_additive = additive;
+
+	vec4 pos = global.ortho * global.transform * model.transform * vec4(position, 0.0, 1.0);
+
+	// Round the position to device pixels, when snapping is enabled.
+	vec2 px = round((pos.xy * 0.5 + 0.5) * global.viewport);
+	vec2 snapped = px / global.viewport * 2.0 - 1.0;
 
-	gl_Position = global.ortho * global.transform * model.transform * vec4(position, 0.0, 1.0);
+	gl_Position = vec4(mix(pos.xy, snapped, snap), pos.zw);
 }
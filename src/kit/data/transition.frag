@@ -0,0 +1,36 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform texture2D from;
+layout(set = 0, binding = 1) uniform texture2D to;
+layout(set = 0, binding = 2) uniform texture2D threshold;
+layout(set = 0, binding = 3) uniform sampler   sam;
+
+layout(set = 1, binding = 0) uniform Transition {
+	float edge;
+	float sharpness;
+	vec2  direction;
+	vec2  center;
+	float aspect;
+	float noise;
+	float radial;
+	float offset;
+} transition;
+
+layout(location = 0) in  vec2 f_uv;
+
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+	vec4 a = texture(sampler2D(from, sam), f_uv);
+	vec4 b = texture(sampler2D(to, sam), f_uv);
+	float n = texture(sampler2D(threshold, sam), f_uv).r;
+	vec2 d = (f_uv - transition.center) * vec2(transition.aspect, 1.0);
+
+	// The point in the transition at which the fragment switches scenes,
+	// from the threshold map, the position along the wipe direction and
+	// the distance from the center.
+	float v = transition.noise * n + dot(f_uv - 0.5, transition.direction)
+	        + transition.radial * length(d) + transition.offset;
+
+	fragColor = mix(a, b, clamp((transition.edge - v) * transition.sharpness, 0.0, 1.0));
+}
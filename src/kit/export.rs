@@ -0,0 +1,247 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::core;
+use crate::core::Rgba8;
+
+/// An image in host memory, eg. read back from a framebuffer, which can be
+/// exported to simple formats without further dependencies. Rows are
+/// stored from top to bottom.
+///
+/// Raw exports are the texels as RGBA bytes, with no header. QOI exports
+/// use the [Quite OK Image](https://qoiformat.org) format, which is
+/// lossless, much faster to encode than PNG, and supported by most image
+/// tools.
+///
+/// ```
+/// use rgx::core::Rgba8;
+/// use rgx::kit::export::Image;
+///
+/// let mut texels = vec![Rgba8::RED; 16];
+/// texels[5] = Rgba8::new(0x10, 0x20, 0x30, 0x40);
+/// texels[6] = Rgba8::new(0x11, 0x21, 0x2f, 0x40);
+/// texels[15] = Rgba8::RED;
+///
+/// let image = Image::new(4, 4, texels);
+/// let qoi = image.to_qoi();
+///
+/// assert_eq!(&qoi[..4], b"qoif");
+/// assert!(qoi.len() < image.to_raw().len());
+/// assert_eq!(Image::from_qoi(&qoi), Some(image));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub texels: Vec<Rgba8>,
+}
+
+impl Image {
+    pub fn new(width: u32, height: u32, texels: Vec<Rgba8>) -> Self {
+        let len = (width as usize)
+            .checked_mul(height as usize)
+            .unwrap_or_else(|| panic!("fatal: a {}x{} image is too large", width, height));
+        assert_eq!(
+            texels.len(),
+            len,
+            "fatal: a {}x{} image should have {} texels",
+            width,
+            height,
+            len
+        );
+        Self {
+            width,
+            height,
+            texels,
+        }
+    }
+
+    /// Create an image from texels of the given format, eg. as read back
+    /// with [`core::Renderer::read`]. Only 8-bit RGBA and BGRA formats are
+    /// supported.
+    pub fn from_bytes(width: u32, height: u32, data: &[u8], format: wgpu::TextureFormat) -> Self {
+        let texels = match format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => data
+                .chunks_exact(4)
+                .map(|c| Rgba8::new(c[0], c[1], c[2], c[3]))
+                .collect(),
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => data
+                .chunks_exact(4)
+                .map(|c| Rgba8::new(c[2], c[1], c[0], c[3]))
+                .collect(),
+            other => panic!("fatal: can't export {:?} texels", other),
+        };
+        Self::new(width, height, texels)
+    }
+
+    /// Read back a framebuffer, and call `f` with its image once the read
+    /// completes.
+    pub fn read<F>(renderer: &mut core::Renderer, fb: &core::Framebuffer, f: F)
+    where
+        F: 'static + FnOnce(Self),
+    {
+        let (w, h, format) = (fb.texture.w, fb.texture.h, fb.texture.format().to_wgpu());
+
+        renderer.read(fb, move |data| f(Self::from_bytes(w, h, data, format)));
+    }
+
+    /// The texels as RGBA bytes.
+    pub fn to_raw(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.texels.len() * 4);
+
+        for c in &self.texels {
+            out.extend_from_slice(&[c.r, c.g, c.b, c.a]);
+        }
+        out
+    }
+
+    /// Encode the image as QOI, with sRGB color values.
+    pub fn to_qoi(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(QOI_HEADER + self.texels.len() + QOI_END.len());
+
+        out.extend_from_slice(b"qoif");
+        out.extend_from_slice(&self.width.to_be_bytes());
+        out.extend_from_slice(&self.height.to_be_bytes());
+        out.extend_from_slice(&[4, 0]);
+
+        let mut index = [Rgba8::TRANSPARENT; 64];
+        let mut prev = Rgba8::BLACK;
+        let mut run = 0u8;
+
+        for (i, &px) in self.texels.iter().enumerate() {
+            if px == prev {
+                run += 1;
+                if run == 62 || i == self.texels.len() - 1 {
+                    out.push(QOI_OP_RUN | (run - 1));
+                    run = 0;
+                }
+                continue;
+            }
+            if run > 0 {
+                out.push(QOI_OP_RUN | (run - 1));
+                run = 0;
+            }
+
+            let slot = qoi_hash(px);
+            if index[slot] == px {
+                out.push(QOI_OP_INDEX | slot as u8);
+            } else {
+                index[slot] = px;
+
+                if px.a == prev.a {
+                    let dr = px.r.wrapping_sub(prev.r) as i8;
+                    let dg = px.g.wrapping_sub(prev.g) as i8;
+                    let db = px.b.wrapping_sub(prev.b) as i8;
+                    let (dr_dg, db_dg) = (dr.wrapping_sub(dg), db.wrapping_sub(dg));
+
+                    if (-2..=1).contains(&dr) && (-2..=1).contains(&dg) && (-2..=1).contains(&db) {
+                        out.push(
+                            QOI_OP_DIFF
+                                | ((dr + 2) as u8) << 4
+                                | ((dg + 2) as u8) << 2
+                                | (db + 2) as u8,
+                        );
+                    } else if (-32..=31).contains(&dg)
+                        && (-8..=7).contains(&dr_dg)
+                        && (-8..=7).contains(&db_dg)
+                    {
+                        out.push(QOI_OP_LUMA | (dg + 32) as u8);
+                        out.push(((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8);
+                    } else {
+                        out.extend_from_slice(&[QOI_OP_RGB, px.r, px.g, px.b]);
+                    }
+                } else {
+                    out.extend_from_slice(&[QOI_OP_RGBA, px.r, px.g, px.b, px.a]);
+                }
+            }
+            prev = px;
+        }
+        out.extend_from_slice(&QOI_END);
+        out
+    }
+
+    /// Decode a QOI image. Returns `None` if the data isn't a valid QOI
+    /// image.
+    pub fn from_qoi(data: &[u8]) -> Option<Self> {
+        if data.len() < QOI_HEADER + QOI_END.len() || &data[..4] != b"qoif" {
+            return None;
+        }
+        let u32_at =
+            |i: usize| u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+        let (width, height) = (u32_at(4), u32_at(8));
+        let len = (width as usize).checked_mul(height as usize)?;
+
+        // Each byte decodes to at most a run of 62 texels, which bounds the
+        // allocation for forged headers.
+        let mut texels = Vec::with_capacity(len.min(data.len().saturating_mul(62)));
+        let mut index = [Rgba8::TRANSPARENT; 64];
+        let mut px = Rgba8::BLACK;
+        let mut bytes = data[QOI_HEADER..data.len() - QOI_END.len()].iter().copied();
+
+        while texels.len() < len {
+            let op = bytes.next()?;
+
+            match op {
+                QOI_OP_RGB => {
+                    px = Rgba8::new(bytes.next()?, bytes.next()?, bytes.next()?, px.a);
+                }
+                QOI_OP_RGBA => {
+                    px = Rgba8::new(bytes.next()?, bytes.next()?, bytes.next()?, bytes.next()?);
+                }
+                _ => match op & 0xc0 {
+                    QOI_OP_INDEX => px = index[op as usize],
+                    QOI_OP_DIFF => {
+                        px.r = px.r.wrapping_add((op >> 4 & 3).wrapping_sub(2));
+                        px.g = px.g.wrapping_add((op >> 2 & 3).wrapping_sub(2));
+                        px.b = px.b.wrapping_add((op & 3).wrapping_sub(2));
+                    }
+                    QOI_OP_LUMA => {
+                        let next = bytes.next()?;
+                        let dg = (op & 0x3f).wrapping_sub(32);
+
+                        px.r =
+                            px.r.wrapping_add(dg.wrapping_add(next >> 4).wrapping_sub(8));
+                        px.g = px.g.wrapping_add(dg);
+                        px.b =
+                            px.b.wrapping_add(dg.wrapping_add(next & 0xf).wrapping_sub(8));
+                    }
+                    _ => {
+                        let run = (op & 0x3f) as usize + 1;
+                        texels.resize((texels.len() + run).min(len), px);
+                        continue;
+                    }
+                },
+            }
+            index[qoi_hash(px)] = px;
+            texels.push(px);
+        }
+        Some(Self::new(width, height, texels))
+    }
+
+    /// Save the image as raw RGBA bytes.
+    pub fn save_raw<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_raw())
+    }
+
+    /// Save the image as QOI.
+    pub fn save_qoi<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_qoi())
+    }
+}
+
+const QOI_HEADER: usize = 14;
+const QOI_END: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+const QOI_OP_INDEX: u8 = 0x00;
+const QOI_OP_DIFF: u8 = 0x40;
+const QOI_OP_LUMA: u8 = 0x80;
+const QOI_OP_RUN: u8 = 0xc0;
+const QOI_OP_RGB: u8 = 0xfe;
+const QOI_OP_RGBA: u8 = 0xff;
+
+fn qoi_hash(c: Rgba8) -> usize {
+    (c.r as usize * 3 + c.g as usize * 5 + c.b as usize * 7 + c.a as usize * 11) % 64
+}
@@ -0,0 +1,139 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{Filter, Op, Rgba, Rgba8};
+
+/// How colors are interpolated between gradient stops.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Each stop's color is held until the next stop.
+    Step,
+    /// Colors are blended linearly between stops.
+    Linear,
+    /// Colors are blended with an ease-in-out curve between stops.
+    Smooth,
+}
+
+/// A gradient color stop, at an offset between `0` and `1`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Stop {
+    pub offset: f32,
+    pub color: Rgba,
+}
+
+/// A gradient with any number of color stops, uploaded as a `width` by `1`
+/// texture, to fill shapes with
+/// [`shape2d::Pipeline::set_gradient`](crate::kit::shape2d::Pipeline::set_gradient),
+/// or to be bound like any other texture, eg. with
+/// [`sprite2d::Pipeline::binding`](crate::kit::sprite2d::Pipeline::binding).
+///
+/// Texel `i` holds the color at `i / (width - 1)`, so that with the
+/// gradient's [`filter`](Gradient::filter), offset `t` is found at
+/// `u = (t * (width - 1) + 0.5) / width`.
+///
+/// ```
+/// use rgx::core::Rgba;
+/// use rgx::kit::gradient::{Gradient, Interpolation};
+///
+/// let g = Gradient::new(Interpolation::Linear)
+///     .stop(0., Rgba::BLACK)
+///     .stop(1., Rgba::WHITE)
+///     .stop(0.5, Rgba::new(1., 0., 0., 1.));
+///
+/// assert_eq!(g.sample(0.5), Rgba::new(1., 0., 0., 1.));
+/// assert_eq!(g.sample(0.75), Rgba::new(1., 0.5, 0.5, 1.));
+/// assert_eq!(g.sample(2.), Rgba::WHITE);
+///
+/// let texels = g.texels(5);
+/// assert_eq!(texels[0], Rgba::BLACK.into());
+/// assert_eq!(texels[2], Rgba::new(1., 0., 0., 1.).into());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub interpolation: Interpolation,
+
+    stops: Vec<Stop>,
+}
+
+impl Gradient {
+    pub fn new(interpolation: Interpolation) -> Self {
+        Self {
+            interpolation,
+            stops: Vec::new(),
+        }
+    }
+
+    /// Return the gradient with a stop added at `offset`, clamped to
+    /// `[0, 1]`. Stops at the same offset keep the order they were added in.
+    pub fn stop(mut self, offset: f32, color: Rgba) -> Self {
+        let offset = offset.clamp(0., 1.);
+        let index = self.stops.iter().take_while(|s| s.offset <= offset).count();
+
+        self.stops.insert(index, Stop { offset, color });
+        self
+    }
+
+    /// The stops, ordered by offset.
+    pub fn stops(&self) -> &[Stop] {
+        &self.stops
+    }
+
+    /// The color at offset `t`. Offsets before the first stop or after the
+    /// last take the color of that stop.
+    pub fn sample(&self, t: f32) -> Rgba {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Rgba::TRANSPARENT,
+        };
+        if t <= first.offset {
+            return first.color;
+        }
+        if t >= last.offset {
+            return last.color;
+        }
+
+        let i = self.stops.iter().take_while(|s| s.offset <= t).count();
+        let (a, b) = (self.stops[i - 1], self.stops[i]);
+        let x = (t - a.offset) / (b.offset - a.offset);
+
+        match self.interpolation {
+            Interpolation::Step => a.color,
+            Interpolation::Linear => a.color.lerp(b.color, x),
+            Interpolation::Smooth => a.color.lerp(b.color, x * x * (3. - 2. * x)),
+        }
+    }
+
+    /// The gradient's texels, for a texture `width` texels wide.
+    pub fn texels(&self, width: u32) -> Vec<Rgba8> {
+        assert!(
+            width >= 2,
+            "fatal: gradient textures must be at least 2 texels wide"
+        );
+
+        (0..width)
+            .map(|i| self.sample(i as f32 / (width - 1) as f32).into())
+            .collect()
+    }
+
+    /// The filter to sample the gradient texture with. Stepped gradients are
+    /// sampled without filtering, to keep their edges sharp.
+    pub fn filter(&self) -> Filter {
+        match self.interpolation {
+            Interpolation::Step => Filter::Nearest,
+            Interpolation::Linear | Interpolation::Smooth => Filter::Linear,
+        }
+    }
+
+    /// Create a gradient texture `width` texels wide, and upload the
+    /// gradient to it. Wider textures follow the interpolation curve and
+    /// step edges more closely.
+    pub fn upload(&self, renderer: &mut core::Renderer, width: u32) -> core::Texture {
+        let texture = renderer.texture(width, 1);
+        let texels = self.texels(width);
+        let (head, body, tail) = unsafe { texels.align_to::<u8>() };
+        assert!(head.is_empty() && tail.is_empty());
+
+        renderer.prepare(&[Op::Fill(&texture, body)]);
+        texture
+    }
+}
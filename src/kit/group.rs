@@ -0,0 +1,166 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{PassOp, Rgba};
+
+use crate::kit::present;
+use crate::kit::present::Fullscreen;
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// Composites a texture with premultiplied alpha over the target, with a
+/// uniform opacity.
+pub struct Pipeline {
+    fullscreen: Fullscreen,
+}
+
+impl Pipeline {
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.fullscreen.binding(renderer, texture, sampler)
+    }
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    /// The group opacity.
+    type PrepareContext = f32;
+    type Uniforms = f32;
+
+    fn description() -> core::PipelineDescription<'a> {
+        Fullscreen::description(
+            &[Fullscreen::TEXTURE, Fullscreen::UNIFORMS],
+            kit_shader!("data/composite.frag"),
+        )
+    }
+
+    fn setup(pipeline: core::Pipeline, dev: &core::Device, width: u32, height: u32) -> Self {
+        Self {
+            fullscreen: Fullscreen::setup(pipeline, dev, width, height, 1.0f32),
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.fullscreen.resize(w, h);
+    }
+
+    fn width(&self) -> u32 {
+        self.fullscreen.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.fullscreen.height()
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        self.fullscreen.apply(pass);
+    }
+
+    fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
+        &'a self,
+        opacity: f32,
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[f32; 1]>)> {
+        Some((
+            self.fullscreen.uniforms(),
+            core::SmallVec::from_buf([opacity]),
+        ))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// OpacityGroup
+///////////////////////////////////////////////////////////////////////////
+
+/// A group of drawables rendered at a shared opacity.
+///
+/// Multiplying the alpha of every element of a group lets overlapping
+/// elements show through each other. Instead, elements are rendered as-is
+/// to the group's [`target`](OpacityGroup::target), which is then composited
+/// once at the group opacity.
+///
+/// The target holds premultiplied alpha, so that translucent elements
+/// aren't faded twice when the group is composited. Pipelines drawing to it
+/// must be created with [`OpacityGroup::blending`].
+pub struct OpacityGroup {
+    pub opacity: f32,
+
+    layer: core::Framebuffer,
+    pipeline: Pipeline,
+    binding: core::BindingGroup,
+    sampler: core::Sampler,
+    quad: core::VertexBuffer,
+}
+
+impl OpacityGroup {
+    pub fn new(renderer: &core::Renderer, w: u32, h: u32, opacity: f32) -> Self {
+        let pipeline: Pipeline = renderer.pipeline(w, h, core::Blending::premultiplied());
+        let layer = renderer.framebuffer(w, h);
+        let sampler = renderer.sampler(core::Filter::Nearest, core::Filter::Nearest);
+        let binding = pipeline.binding(renderer, &layer.texture, &sampler);
+
+        Self {
+            opacity,
+            layer,
+            pipeline,
+            binding,
+            sampler,
+            quad: present::quad(renderer),
+        }
+    }
+
+    /// The blending of pipelines drawing the group's elements, which outputs
+    /// straight alpha, eg. the kit's sprite and shape pipelines. Colors are
+    /// accumulated in the target with premultiplied alpha.
+    pub fn blending() -> core::Blending {
+        core::Blending::translucent()
+    }
+
+    /// The offscreen target the group's elements should be rendered to.
+    pub fn target(&self) -> &core::Framebuffer {
+        &self.layer
+    }
+
+    /// Begin a pass clearing the group's target, in which the group's
+    /// elements can be drawn.
+    pub fn begin<'a>(&'a self, frame: &'a mut core::Frame) -> core::Pass<'a> {
+        frame.pass(PassOp::Clear(Rgba::TRANSPARENT), &self.layer)
+    }
+
+    /// Recreate the offscreen target with a new size.
+    pub fn resize(&mut self, renderer: &core::Renderer, w: u32, h: u32) {
+        use core::AbstractPipeline;
+
+        self.layer = renderer.framebuffer(w, h);
+        self.binding = self
+            .pipeline
+            .binding(renderer, &self.layer.texture, &self.sampler);
+        self.pipeline.resize(w, h);
+    }
+
+    /// Composite the group over the contents of `out`.
+    pub fn draw<T: core::TextureView>(
+        &self,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+        out: &T,
+    ) {
+        renderer.update_pipeline(&self.pipeline, self.opacity, frame);
+
+        let mut pass = frame.pass(PassOp::Load(), out);
+        pass.set_pipeline(&self.pipeline);
+        pass.draw(&self.quad, &self.binding);
+    }
+}
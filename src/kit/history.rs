@@ -0,0 +1,93 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+
+/// A ring of the last `N` rendered frames, kept as textures for temporal
+/// effects such as motion blur and trails.
+///
+/// Frames are copied into the history with [`FrameHistory::push`], usually
+/// right before presenting. Since swap chain textures can't be copied from,
+/// the scene should be rendered to a [`core::Framebuffer`] first.
+pub struct FrameHistory {
+    frames: Vec<core::Framebuffer>,
+    /// Index of the most recent frame.
+    head: usize,
+    /// Number of frames pushed, up to the capacity.
+    len: usize,
+}
+
+impl FrameHistory {
+    pub fn new(renderer: &core::Renderer, w: u32, h: u32, capacity: usize) -> Self {
+        Self::with_format(renderer, w, h, capacity, renderer.format())
+    }
+
+    pub fn with_format(
+        renderer: &core::Renderer,
+        w: u32,
+        h: u32,
+        capacity: usize,
+        format: core::TextureFormat,
+    ) -> Self {
+        assert!(
+            capacity > 0,
+            "fatal: frame history capacity must be non-zero"
+        );
+
+        Self {
+            frames: (0..capacity)
+                .map(|_| renderer.framebuffer_with_format(w, h, format))
+                .collect(),
+            head: capacity - 1,
+            len: 0,
+        }
+    }
+
+    /// Copy `src` into the history, replacing the oldest frame.
+    pub fn push(&mut self, frame: &mut core::Frame, src: &core::Framebuffer) {
+        self.head = (self.head + 1) % self.frames.len();
+        self.len = usize::min(self.len + 1, self.frames.len());
+
+        frame.copy_texture(&src.texture, &self.frames[self.head].texture);
+    }
+
+    /// Get the frame pushed `age` frames ago, where `0` is the most recent.
+    pub fn get(&self, age: usize) -> Option<&core::Framebuffer> {
+        if age >= self.len {
+            return None;
+        }
+        let n = self.frames.len();
+        self.frames.get((self.head + n - age) % n)
+    }
+
+    /// Create a binding for the frame pushed `age` frames ago, for a layout
+    /// consisting of a texture and a sampler.
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        layout: &core::BindingGroupLayout,
+        age: usize,
+        sampler: &core::Sampler,
+    ) -> Option<core::BindingGroup> {
+        self.get(age)
+            .map(|fb| renderer.binding_group(layout, &[&fb.texture, sampler]))
+    }
+
+    /// The number of frames in the history.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The maximum number of frames kept.
+    pub fn capacity(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Discard all frames, eg. after a resize or a scene change.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+}
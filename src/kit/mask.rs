@@ -0,0 +1,218 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{Binding, BindingType, Rect, Set, ShaderStage};
+
+use crate::math::*;
+
+use crate::kit;
+use crate::kit::Model;
+
+///////////////////////////////////////////////////////////////////////////
+// Uniforms
+///////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Uniforms {
+    pub ortho: Matrix4<f32>,
+    pub transform: Matrix4<f32>,
+    /// Scale (`x`, `y`) and offset (`z`, `w`) from screen to mask coordinates.
+    pub mask: Vector4<f32>,
+}
+
+/// Return the mask transform stretching the mask over `area`, a rectangle
+/// in screen pixels, on a `w` by `h` target.
+///
+/// ```
+/// use rgx::core::Rect;
+/// use rgx::kit::mask;
+///
+/// let t = mask::transform(Rect::new(0., 0., 200., 100.), 400, 100);
+/// assert_eq!((t.x, t.y, t.z, t.w), (2., 1., 0., 0.));
+///
+/// let t = mask::transform(Rect::new(100., 50., 300., 150.), 400, 200);
+/// assert_eq!((t.x, t.y, t.z, t.w), (2., 2., -0.5, -0.5));
+/// ```
+pub fn transform(area: Rect<f32>, w: u32, h: u32) -> Vector4<f32> {
+    let (aw, ah) = (area.width(), area.height());
+
+    Vector4::new(w as f32 / aw, h as f32 / ah, -area.x1 / aw, -area.y1 / ah)
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// A variant of the sprite pipeline which multiplies the alpha of every
+/// fragment by the alpha of a mask texture. The mask is mapped in screen
+/// space, eg. for brush masks, fog-of-war or vignettes.
+///
+/// Vertices are [`sprite2d::Vertex`](crate::kit::sprite2d::Vertex), as
+/// generated by sprite batches. The sprite texture is bound at set `2`,
+/// and the mask at set `3`.
+pub struct Pipeline {
+    pipeline: core::Pipeline,
+    bindings: core::BindingGroup,
+    buf: core::UniformBuffer,
+    width: u32,
+    height: u32,
+    scale: f64,
+    ortho: Matrix4<f32>,
+    model: Model,
+}
+
+impl Pipeline {
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.pipeline
+            .binding_group(2)
+            .texture("tex", texture)
+            .sampler("sam", sampler)
+            .build(&renderer.device)
+            .expect("fatal: kit shaders declare their bindings")
+    }
+
+    /// Create a binding for a mask texture. Bind it with [`core::Pass::set_binding`]
+    /// before drawing.
+    pub fn mask_binding(
+        &self,
+        renderer: &core::Renderer,
+        mask: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.pipeline
+            .binding_group(3)
+            .texture("mask", mask)
+            .sampler("mask_sam", sampler)
+            .build(&renderer.device)
+            .expect("fatal: kit shaders declare their bindings")
+    }
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    /// The global transform, and the mask transform.
+    type PrepareContext = (Matrix4<f32>, Vector4<f32>);
+    type Uniforms = self::Uniforms;
+
+    fn description() -> core::PipelineDescription<'a> {
+        core::PipelineDescription {
+            vertex_layout: &[
+                core::VertexFormat::Float2,
+                core::VertexFormat::Float2,
+                core::VertexFormat::UByte4,
+                core::VertexFormat::Float,
+                core::VertexFormat::UByte4,
+                core::VertexFormat::UByte4,
+                core::VertexFormat::Float,
+            ],
+            pipeline_layout: &[
+                Set(&[Binding {
+                    binding: BindingType::UniformBuffer,
+                    stage: ShaderStage::Vertex,
+                }]),
+                Set(&[Binding {
+                    binding: BindingType::UniformBuffer,
+                    stage: ShaderStage::Vertex,
+                }]),
+                Set(&[
+                    Binding {
+                        binding: BindingType::SampledTexture,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::Sampler,
+                        stage: ShaderStage::Fragment,
+                    },
+                ]),
+                Set(&[
+                    Binding {
+                        binding: BindingType::SampledTexture,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::Sampler,
+                        stage: ShaderStage::Fragment,
+                    },
+                ]),
+            ],
+            vertex_shader: kit_shader!("data/mask.vert"),
+            fragment_shader: kit_shader!("data/mask.frag"),
+        }
+    }
+
+    fn setup(pipeline: core::Pipeline, dev: &core::Device, width: u32, height: u32) -> Self {
+        let ortho = kit::ortho(width, height);
+        let transform = Matrix4::identity();
+        let mask = Vector4::new(1., 1., 0., 0.);
+        let model = Model::new(&pipeline.layout.sets[1], &[Matrix4::identity()], dev);
+        let buf = dev.create_uniform_buffer(&[self::Uniforms {
+            ortho,
+            transform,
+            mask,
+        }]);
+        let bindings = dev.create_binding_group(&pipeline.layout.sets[0], &[&buf]);
+
+        Self {
+            pipeline,
+            buf,
+            bindings,
+            model,
+            ortho,
+            width,
+            height,
+            scale: 1.0,
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.width = w;
+        self.height = h;
+        self.ortho = kit::ortho_scaled(w, h, self.scale);
+    }
+
+    fn set_scale_factor(&mut self, factor: f64) {
+        self.scale = factor;
+        self.ortho = kit::ortho_scaled(self.width, self.height, factor);
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_binding(&self.bindings, &[]);
+        pass.set_binding(&self.model.binding, &[]);
+    }
+
+    fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
+        &'a self,
+        (transform, mask): (Matrix4<f32>, Vector4<f32>),
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
+        Some((
+            &self.buf,
+            core::SmallVec::from_buf([self::Uniforms {
+                transform,
+                ortho: self.ortho,
+                mask,
+            }]),
+        ))
+    }
+}
@@ -0,0 +1,146 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::time::{Duration, Instant};
+
+use crate::core;
+use crate::core::{PassOp, Rect, Rgba};
+
+use crate::math::*;
+
+/// A reduced view of the scene, rendered into its own framebuffer on an
+/// interval, for display in the UI, eg. as a sprite.
+///
+/// The scene is drawn by the application, with the pipelines it uses for the
+/// main view, in the callback passed to [`Minimap::update`]. The callback
+/// receives a transform which fits the minimap [`bounds`](Minimap::bounds)
+/// into the framebuffer, to be passed to the pipelines instead of the camera
+/// transform.
+pub struct Minimap {
+    /// The region of the canvas shown, in canvas units.
+    pub bounds: Rect<f32>,
+    /// The time between refreshes.
+    pub interval: Duration,
+    pub background: Rgba,
+
+    target: core::Framebuffer,
+    last: Option<Instant>,
+}
+
+impl Minimap {
+    /// Create a `w` by `h` minimap, in physical pixels.
+    pub fn new(
+        renderer: &core::Renderer,
+        w: u32,
+        h: u32,
+        bounds: Rect<f32>,
+        interval: Duration,
+    ) -> Self {
+        Self {
+            bounds,
+            interval,
+            background: Rgba::TRANSPARENT,
+            target: renderer.framebuffer(w, h),
+            last: None,
+        }
+    }
+
+    /// The minimap texture, to be bound for display.
+    pub fn texture(&self) -> &core::Texture {
+        &self.target.texture
+    }
+
+    pub fn width(&self) -> u32 {
+        self.target.width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.target.height()
+    }
+
+    /// Recreate the framebuffer with a new size. The minimap is refreshed on
+    /// the next update.
+    pub fn resize(&mut self, renderer: &core::Renderer, w: u32, h: u32) {
+        self.target = renderer.framebuffer(w, h);
+        self.invalidate();
+    }
+
+    /// Refresh the minimap on the next update, eg. after the scene changed.
+    pub fn invalidate(&mut self) {
+        self.last = None;
+    }
+
+    /// Whether the minimap is due for a refresh at `now`.
+    pub fn is_due(&self, now: Instant) -> bool {
+        match self.last {
+            Some(last) => now.duration_since(last) >= self.interval,
+            None => true,
+        }
+    }
+
+    /// Refresh the minimap if it is due. The target is cleared, and `draw` is
+    /// called with the target and the transform to draw the scene with,
+    /// given the `viewport` size in logical pixels projected by the scene's
+    /// pipelines. Returns whether the minimap was refreshed.
+    pub fn update<F>(
+        &mut self,
+        now: Instant,
+        viewport: Vector2<f32>,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+        draw: F,
+    ) -> bool
+    where
+        F: FnOnce(&mut core::Renderer, &mut core::Frame, &core::Framebuffer, Matrix4<f32>),
+    {
+        if !self.is_due(now) {
+            return false;
+        }
+        self.last = Some(now);
+
+        frame.pass(PassOp::Clear(self.background), &self.target);
+        draw(renderer, frame, &self.target, self.transform(viewport));
+
+        true
+    }
+
+    /// The transform fitting the bounds into the minimap, centered, for
+    /// pipelines projecting a `viewport` of the given logical size.
+    ///
+    /// Since the pipelines map the whole viewport to the target, the fitted
+    /// bounds are scaled from minimap pixels to the viewport.
+    pub fn transform(&self, viewport: Vector2<f32>) -> Matrix4<f32> {
+        let (scale, offset) = self.fit();
+        let (w, h) = (self.width() as f32, self.height() as f32);
+
+        Matrix4::from_nonuniform_scale(viewport.x / w, viewport.y / h, 1.)
+            * Matrix4::from_translation(Vector3::new(offset.x, offset.y, 0.))
+            * Matrix4::from_scale(scale)
+            * Matrix4::from_translation(Vector3::new(-self.bounds.x1, -self.bounds.y1, 0.))
+    }
+
+    /// Map a point in minimap pixels, eg. a click on the minimap, to the
+    /// canvas.
+    pub fn to_canvas(&self, p: Point2<f32>) -> Point2<f32> {
+        let (scale, offset) = self.fit();
+
+        Point2::new(
+            (p.x - offset.x) / scale + self.bounds.x1,
+            (p.y - offset.y) / scale + self.bounds.y1,
+        )
+    }
+
+    /// The scale from canvas units to minimap pixels, and the offset of the
+    /// bounds within the minimap, centering them.
+    fn fit(&self) -> (f32, Vector2<f32>) {
+        let (w, h) = (self.width() as f32, self.height() as f32);
+        let scale = f32::min(w / self.bounds.width(), h / self.bounds.height());
+
+        (
+            scale,
+            Vector2::new(
+                (w - self.bounds.width() * scale) / 2.,
+                (h - self.bounds.height() * scale) / 2.,
+            ),
+        )
+    }
+}
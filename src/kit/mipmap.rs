@@ -0,0 +1,129 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{Binding, BindingType, PassOp, Rgba, Set, ShaderStage};
+
+use crate::kit::present;
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// Renders a mip level by downscaling the level above it.
+pub struct Pipeline {
+    pipeline: core::Pipeline,
+    width: u32,
+    height: u32,
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    type PrepareContext = ();
+    type Uniforms = ();
+
+    fn description() -> core::PipelineDescription<'a> {
+        core::PipelineDescription {
+            vertex_layout: &[core::VertexFormat::Float2, core::VertexFormat::Float2],
+            pipeline_layout: &[Set(&[
+                Binding {
+                    binding: BindingType::SampledTexture,
+                    stage: ShaderStage::Fragment,
+                },
+                Binding {
+                    binding: BindingType::Sampler,
+                    stage: ShaderStage::Fragment,
+                },
+            ])],
+            vertex_shader: kit_shader!("data/present.vert"),
+            fragment_shader: kit_shader!("data/mipmap.frag"),
+        }
+    }
+
+    fn setup(pipeline: core::Pipeline, _dev: &core::Device, width: u32, height: u32) -> Self {
+        Self {
+            pipeline,
+            width,
+            height,
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.width = w;
+        self.height = h;
+    }
+
+    fn width(&self) -> u32 {
+        self.width
+    }
+
+    fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        pass.set_pipeline(&self.pipeline);
+    }
+
+    fn prepare(&'a self, _unused: ()) -> Option<(&'a core::UniformBuffer, Vec<()>)> {
+        None
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// MipmapGenerator
+///////////////////////////////////////////////////////////////////////////
+
+/// Fills the mip levels of textures created with
+/// [`core::Renderer::texture_with_mipmaps`], so that sprites drawn
+/// downscaled with linear filtering don't shimmer.
+///
+/// Each level is rendered from the one above it with a linear filter, so
+/// the base level must be uploaded before mipmaps are generated, and
+/// generated again whenever it changes.
+pub struct MipmapGenerator {
+    pipeline: Pipeline,
+    sampler: core::Sampler,
+    quad: core::VertexBuffer,
+}
+
+impl MipmapGenerator {
+    pub fn new(renderer: &core::Renderer) -> Self {
+        Self {
+            pipeline: renderer.pipeline_with_format(
+                1,
+                1,
+                core::Blending::replace(),
+                core::TextureFormat::Rgba8Unorm,
+            ),
+            sampler: renderer.sampler_with_address_mode(
+                core::Filter::Linear,
+                core::Filter::Linear,
+                core::AddressMode::ClampToEdge,
+            ),
+            quad: present::quad(renderer),
+        }
+    }
+
+    /// Generate all mip levels of `texture` below the base level, in
+    /// order, with one pass per level.
+    pub fn generate_mipmaps(
+        &self,
+        renderer: &core::Renderer,
+        frame: &mut core::Frame,
+        texture: &core::Texture,
+    ) {
+        let arena = frame.arena();
+
+        for level in 1..texture.mip_levels() {
+            let src = arena.keep(texture.mip_view(level - 1));
+            let dst = arena.keep(texture.mip_view(level));
+            let binding = arena.keep(renderer.binding_group(
+                &self.pipeline.pipeline.layout.sets[0],
+                &[src, &self.sampler],
+            ));
+
+            let mut pass = frame.pass(PassOp::Clear(Rgba::TRANSPARENT), dst);
+            pass.set_pipeline(&self.pipeline);
+            pass.draw(&self.quad, binding);
+        }
+    }
+}
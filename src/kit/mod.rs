@@ -1,11 +1,54 @@
 #![allow(dead_code)]
 pub use crate::core;
-pub use crate::core::{Bgra8, Rgba, Rgba8};
-
+pub use crate::core::{Bgra8, Rgba, Rgba16F, Rgba8};
+
+#[cfg(feature = "sprite2d")]
+pub mod atlas;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod camera;
+pub mod clock;
+pub mod color;
+pub mod export;
+pub mod gradient;
+#[cfg(feature = "post")]
+pub mod group;
+pub mod history;
+#[cfg(feature = "post")]
+pub mod mask;
+pub mod minimap;
+#[cfg(feature = "post")]
+pub mod mipmap;
+#[cfg(feature = "sprite2d")]
+pub mod overlay;
+#[cfg(feature = "post")]
+pub mod outline;
+pub mod palette;
+#[cfg(feature = "post")]
+pub mod present;
+#[cfg(feature = "sprite2d")]
+pub mod queue;
+#[cfg(feature = "shape2d")]
+pub mod ruler;
+#[cfg(feature = "shape2d")]
+pub mod selection;
+#[cfg(feature = "post")]
+pub mod shadow;
+#[cfg(feature = "shape2d")]
 pub mod shape2d;
+#[cfg(feature = "sprite2d")]
+pub mod skeleton;
+#[cfg(feature = "sprite2d")]
 pub mod sprite2d;
+#[cfg(feature = "shape2d")]
+pub mod trail;
+#[cfg(feature = "post")]
+pub mod transition;
+#[cfg(feature = "sprite2d")]
+pub mod warp;
 
-use crate::math::{Matrix4, Ortho};
+use crate::core::Rect;
+use crate::math::{Matrix4, Ortho, Point2, Transform2D, Vector2};
 
 use std::time;
 
@@ -143,10 +186,17 @@ impl<T> Animation<T> {
 ///////////////////////////////////////////////////////////////////////////////
 
 pub fn ortho(w: u32, h: u32) -> Matrix4<f32> {
+    crate::math::ortho(w, h)
+}
+
+/// Return an orthographic projection for a `w` by `h` target in physical
+/// pixels, which maps logical coordinates, ie. physical pixels divided by
+/// the `scale` factor.
+pub fn ortho_scaled(w: u32, h: u32, scale: f64) -> Matrix4<f32> {
     Ortho::<f32> {
         left: 0.0,
-        right: w as f32,
-        bottom: h as f32,
+        right: (w as f64 / scale) as f32,
+        bottom: (h as f64 / scale) as f32,
         top: 0.0,
         near: -1.0,
         far: 1.0,
@@ -154,22 +204,215 @@ pub fn ortho(w: u32, h: u32) -> Matrix4<f32> {
     .into()
 }
 
+/// Snap a logical coordinate to the nearest physical pixel boundary, eg.
+/// for the origin of text or sprites drawn at a `scale` factor.
+///
+/// ```
+/// use rgx::kit;
+///
+/// assert_eq!(kit::snap(10.3, 1.0), 10.0);
+/// assert_eq!(kit::snap(10.3, 2.0), 10.5);
+/// assert_eq!(kit::snap(10.2, 1.5), 10.0);
+/// ```
+pub fn snap(v: f32, scale: f64) -> f32 {
+    ((v as f64 * scale).round() / scale) as f32
+}
+
+/// Snap a logical coordinate to the center of the nearest physical pixel,
+/// so that lines one physical pixel wide along it are drawn crisply.
+///
+/// ```
+/// use rgx::kit;
+///
+/// assert_eq!(kit::snap_line(10.0, 1.0), 10.5);
+/// assert_eq!(kit::snap_line(10.0, 2.0), 10.25);
+/// ```
+pub fn snap_line(v: f32, scale: f64) -> f32 {
+    (((v as f64 * scale).floor() + 0.5) / scale) as f32
+}
+
+/// Snap a point to the nearest intersection of a grid with the given cell
+/// size, eg. for placing objects in an editor.
+///
+/// ```
+/// use rgx::kit;
+/// use rgx::math::{Point2, Vector2};
+///
+/// let p = kit::snap_to_grid(Point2::new(13., -7.), Vector2::new(8., 8.));
+/// assert_eq!((p.x, p.y), (16., -8.));
+/// ```
+pub fn snap_to_grid(p: Point2<f32>, cell: Vector2<f32>) -> Point2<f32> {
+    Point2::new(
+        (p.x / cell.x).round() * cell.x,
+        (p.y / cell.y).round() * cell.y,
+    )
+}
+
+/// Snap the corners of a rectangle to the nearest grid intersections. The
+/// rectangle is never collapsed: each side spans at least one cell.
+///
+/// ```
+/// use rgx::core::Rect;
+/// use rgx::kit;
+/// use rgx::math::Vector2;
+///
+/// let cell = Vector2::new(10., 10.);
+///
+/// assert_eq!(
+///     kit::snap_rect(Rect::new(3., 14., 28., 16.), cell),
+///     Rect::new(0., 10., 30., 20.)
+/// );
+/// ```
+pub fn snap_rect(r: Rect<f32>, cell: Vector2<f32>) -> Rect<f32> {
+    let p1 = snap_to_grid(Point2::new(r.x1, r.y1), cell);
+    let p2 = snap_to_grid(Point2::new(r.x2, r.y2), cell);
+
+    // Keep the orientation of the rectangle when extending collapsed sides.
+    let extend = |a: f32, b: f32, size: f32, orig: f32| {
+        if a != b {
+            b
+        } else if orig < 0. {
+            a - size
+        } else {
+            a + size
+        }
+    };
+
+    Rect::new(
+        p1.x,
+        p1.y,
+        extend(p1.x, p2.x, cell.x, r.x2 - r.x1),
+        extend(p1.y, p2.y, cell.y, r.y2 - r.y1),
+    )
+}
+
+/// Clip triangles, of three vertices each, to a rectangle, and append the
+/// result to `out`. Vertices created on the clip edges are interpolated
+/// with `lerp`. This lets sprites and shapes be clipped individually
+/// without splitting their batch.
+pub(crate) fn clip_triangles<V, P, L>(
+    vertices: &[V],
+    clip: Rect<f32>,
+    position: P,
+    lerp: L,
+    out: &mut Vec<V>,
+) where
+    V: Copy,
+    P: Fn(&V) -> Vector2<f32>,
+    L: Fn(&V, &V, f32) -> V,
+{
+    let (x1, x2) = (clip.x1.min(clip.x2), clip.x1.max(clip.x2));
+    let (y1, y2) = (clip.y1.min(clip.y2), clip.y1.max(clip.y2));
+
+    // Distance of a point to the inside of each clip edge.
+    let edges: [&dyn Fn(Vector2<f32>) -> f32; 4] =
+        [&|p| p.x - x1, &|p| x2 - p.x, &|p| p.y - y1, &|p| y2 - p.y];
+    let mut polygon = Vec::with_capacity(8);
+    let mut clipped = Vec::with_capacity(8);
+
+    for triangle in vertices.chunks_exact(3) {
+        polygon.clear();
+        polygon.extend_from_slice(triangle);
+
+        for edge in edges.iter() {
+            clipped.clear();
+
+            for (i, a) in polygon.iter().enumerate() {
+                let b = &polygon[(i + 1) % polygon.len()];
+                let (da, db) = (edge(position(a)), edge(position(b)));
+
+                if da >= 0. {
+                    clipped.push(*a);
+                }
+                if (da >= 0.) != (db >= 0.) {
+                    clipped.push(lerp(a, b, da / (da - db)));
+                }
+            }
+            std::mem::swap(&mut polygon, &mut clipped);
+        }
+
+        // The clipped polygon is convex, so it can be drawn as a fan.
+        for i in 1..polygon.len().saturating_sub(1) {
+            out.extend_from_slice(&[polygon[0], polygon[i], polygon[i + 1]]);
+        }
+    }
+}
+
+/// Snap an angle, in radians, to the nearest multiple of `step`, eg. for
+/// constraining lines to 15 degree increments.
+///
+/// ```
+/// use rgx::kit;
+/// use std::f32::consts::PI;
+///
+/// let a = kit::snap_angle(0.3, PI / 12.);
+/// assert!((a - PI / 12.).abs() < 1e-6);
+/// ```
+pub fn snap_angle(angle: f32, step: f32) -> f32 {
+    (angle / step).round() * step
+}
+
+/// Create a `w` by `h` grayscale texture from a noise function, eg. one of
+/// [`math::noise`](crate::math::noise), with `scale` texels per noise unit.
+/// Values in `[-1, 1]` are mapped to black through white, eg. as a
+/// dissolve threshold map.
+///
+/// The texture is generated on the CPU, so it is best created once and
+/// reused, or kept small and tiled with a repeating sampler.
+pub fn noise_texture<F>(
+    renderer: &mut core::Renderer,
+    w: u32,
+    h: u32,
+    scale: f32,
+    noise: F,
+) -> core::Texture
+where
+    F: Fn(f32, f32) -> f32,
+{
+    let texels: Vec<Rgba8> = (0..w * h)
+        .map(|i| {
+            let (x, y) = ((i % w) as f32 + 0.5, (i / w) as f32 + 0.5);
+            let n = ((noise(x / scale, y / scale) + 1.) / 2.).clamp(0., 1.);
+            let v = (n * 255.).round() as u8;
+
+            Rgba8::new(v, v, v, 0xff)
+        })
+        .collect();
+    let (head, body, tail) = unsafe { texels.align_to::<u8>() };
+    assert!(head.is_empty() && tail.is_empty());
+
+    let texture = renderer.texture_with_format(w, h, core::TextureFormat::Rgba8Unorm);
+    renderer.prepare(&[core::Op::Fill(&texture, body)]);
+    texture
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 
+#[repr(C)]
 #[derive(Copy, Clone)]
 pub struct AlignedBuffer {
     // TODO: Make this generic when rust-lang#43408 is fixed.
     data: Matrix4<f32>,
+    tint: Rgba,
     padding: [u8; AlignedBuffer::PAD],
 }
 
 impl AlignedBuffer {
     pub const ALIGNMENT: u64 = 256;
-    pub const PAD: usize = Self::ALIGNMENT as usize - std::mem::size_of::<Matrix4<f32>>();
+    pub const PAD: usize = Self::ALIGNMENT as usize
+        - std::mem::size_of::<Matrix4<f32>>()
+        - std::mem::size_of::<Rgba>();
 
     pub fn new(data: Matrix4<f32>) -> Self {
+        Self::with_tint(data, Rgba::WHITE)
+    }
+
+    /// Create a buffer with a tint, multiplied with the output color of
+    /// pipelines which support it.
+    pub fn with_tint(data: Matrix4<f32>, tint: Rgba) -> Self {
         Self {
             data,
+            tint,
             padding: [0u8; AlignedBuffer::PAD],
         }
     }
@@ -202,3 +445,49 @@ impl Model {
         aligned
     }
 }
+
+/// A model transform bound per draw, so that batches can be drawn with an
+/// arbitrary [`Transform2D`] rather than pre-transformed vertices. Create one
+/// with [`sprite2d::Pipeline::model`] or [`shape2d::Pipeline::model`].
+///
+/// Binding the transform replaces the pipeline's own model transform, until
+/// the pipeline is set again:
+///
+/// ```ignore
+/// let mut model = pipeline.model(&renderer, Transform2D::IDENTITY);
+///
+/// model.transform.rotation += 0.1;
+/// model.update(&mut renderer, &mut frame);
+///
+/// pass.set_pipeline(&pipeline);
+/// model.bind(&mut pass);
+/// pass.draw(&buffer, &binding);
+/// ```
+pub struct ModelTransform {
+    pub transform: Transform2D,
+
+    model: Model,
+}
+
+impl ModelTransform {
+    fn new(layout: &core::BindingGroupLayout, transform: Transform2D, dev: &core::Device) -> Self {
+        Self {
+            transform,
+            model: Model::new(layout, &[transform.to_matrix()], dev),
+        }
+    }
+
+    /// Upload the transform to the GPU.
+    pub fn update(&self, renderer: &mut core::Renderer, frame: &mut core::Frame) {
+        renderer.update_uniform_buffer(
+            &self.model.buf,
+            &[AlignedBuffer::new(self.transform.to_matrix())],
+            frame,
+        );
+    }
+
+    /// Bind the transform for the following draws of the pass.
+    pub fn bind(&self, pass: &mut core::Pass) {
+        pass.set_binding(&self.model.binding, &[]);
+    }
+}
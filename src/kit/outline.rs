@@ -0,0 +1,173 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{PassOp, Rgba};
+
+use crate::kit::present;
+use crate::kit::present::Fullscreen;
+
+///////////////////////////////////////////////////////////////////////////
+// Uniforms
+///////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Uniforms {
+    pub color: Rgba,
+    pub texel: [f32; 2],
+    pub thickness: f32,
+    _padding: f32,
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// Draws a sprite layer with an outline around its non-transparent pixels.
+pub struct Pipeline {
+    fullscreen: Fullscreen,
+}
+
+impl Pipeline {
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.fullscreen.binding(renderer, texture, sampler)
+    }
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    /// The outline color and thickness in pixels.
+    type PrepareContext = (Rgba, f32);
+    type Uniforms = self::Uniforms;
+
+    fn description() -> core::PipelineDescription<'a> {
+        Fullscreen::description(
+            &[Fullscreen::TEXTURE, Fullscreen::UNIFORMS],
+            kit_shader!("data/outline.frag"),
+        )
+    }
+
+    fn setup(pipeline: core::Pipeline, dev: &core::Device, width: u32, height: u32) -> Self {
+        let uniforms = self::Uniforms {
+            color: Rgba::TRANSPARENT,
+            texel: [1. / width as f32, 1. / height as f32],
+            thickness: 0.,
+            _padding: 0.,
+        };
+
+        Self {
+            fullscreen: Fullscreen::setup(pipeline, dev, width, height, uniforms),
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.fullscreen.resize(w, h);
+    }
+
+    fn width(&self) -> u32 {
+        self.fullscreen.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.fullscreen.height()
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        self.fullscreen.apply(pass);
+    }
+
+    fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
+        &'a self,
+        (color, thickness): (Rgba, f32),
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
+        let (w, h) = (self.fullscreen.width(), self.fullscreen.height());
+
+        Some((
+            self.fullscreen.uniforms(),
+            core::SmallVec::from_buf([self::Uniforms {
+                color,
+                texel: [1. / w as f32, 1. / h as f32],
+                thickness,
+                _padding: 0.,
+            }]),
+        ))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Outline
+///////////////////////////////////////////////////////////////////////////
+
+/// An outline effect, eg. for highlighting selected sprites.
+///
+/// Sprites to outline are rendered to a separate layer, which is then drawn
+/// on top of the scene with an outline of `thickness` pixels around every
+/// non-transparent pixel. Every texel within `thickness` pixels is sampled,
+/// so thick outlines are more costly.
+pub struct Outline {
+    pub color: Rgba,
+    pub thickness: f32,
+
+    pipeline: Pipeline,
+    sampler: core::Sampler,
+    quad: core::VertexBuffer,
+}
+
+impl Outline {
+    pub fn new(renderer: &core::Renderer, w: u32, h: u32, color: Rgba, thickness: f32) -> Self {
+        Self {
+            color,
+            thickness,
+            pipeline: renderer.pipeline(w, h, core::Blending::default()),
+            sampler: renderer.sampler_with_address_mode(
+                core::Filter::Nearest,
+                core::Filter::Nearest,
+                core::AddressMode::ClampToEdge,
+            ),
+            quad: present::quad(renderer),
+        }
+    }
+
+    /// Create a binding for the layer to outline.
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        layer: &core::Framebuffer,
+    ) -> core::BindingGroup {
+        self.pipeline
+            .binding(renderer, &layer.texture, &self.sampler)
+    }
+
+    pub fn resize(&mut self, w: u32, h: u32) {
+        use core::AbstractPipeline;
+
+        self.pipeline.resize(w, h);
+    }
+
+    /// Draw the outlined layer bound by `binding` over the contents of `out`.
+    pub fn draw<T: core::TextureView>(
+        &self,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+        binding: &core::BindingGroup,
+        out: &T,
+    ) {
+        renderer.update_pipeline(&self.pipeline, (self.color, self.thickness), frame);
+
+        let mut pass = frame.pass(PassOp::Load(), out);
+        pass.set_pipeline(&self.pipeline);
+        pass.draw(&self.quad, binding);
+    }
+}
@@ -0,0 +1,97 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{PassOp, Rect, Rgba};
+
+use crate::kit::sprite2d::{self, Tint};
+use crate::kit::Repeat;
+
+use crate::math::*;
+
+/// A final layer for cursors, custom pointers and drag previews.
+///
+/// The overlay is drawn over the swap chain texture after everything else,
+/// including post-processing and UI, so that effects like CRT warping don't
+/// distort the cursor. This is guaranteed by [`Overlay::submit`], which draws
+/// the overlay and submits the frame.
+///
+/// Sprites are taken from a single texture, eg. a cursor atlas, and are
+/// cleared after every frame.
+pub struct Overlay {
+    pipeline: sprite2d::Pipeline,
+    binding: core::BindingGroup,
+    batch: sprite2d::Batch,
+}
+
+impl Overlay {
+    pub fn new(
+        renderer: &core::Renderer,
+        w: u32,
+        h: u32,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> Self {
+        let pipeline: sprite2d::Pipeline = renderer.pipeline(w, h, core::Blending::default());
+        let binding = pipeline.binding(renderer, texture, sampler);
+
+        Self {
+            pipeline,
+            binding,
+            batch: sprite2d::Batch::new(texture.w, texture.h),
+        }
+    }
+
+    /// Add a cursor sprite, positioned so that its `hotspot`, relative to the
+    /// top-left corner of the sprite, is at `pos`.
+    pub fn cursor(&mut self, src: Rect<f32>, pos: Point2<f32>, hotspot: Vector2<f32>) {
+        let dst = Rect::new(
+            pos.x - hotspot.x,
+            pos.y - hotspot.y,
+            pos.x - hotspot.x + src.width(),
+            pos.y - hotspot.y + src.height(),
+        );
+        self.sprite(src, dst, 1.0);
+    }
+
+    /// Add a sprite, eg. a drag preview.
+    pub fn sprite(&mut self, src: Rect<f32>, dst: Rect<f32>, opacity: f32) {
+        self.batch.add(
+            src,
+            dst,
+            Rgba::TRANSPARENT,
+            Tint::NONE,
+            opacity,
+            Repeat::default(),
+        );
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batch.size == 0
+    }
+
+    pub fn resize(&mut self, w: u32, h: u32) {
+        use core::AbstractPipeline;
+
+        self.pipeline.resize(w, h);
+    }
+
+    /// Draw the overlay over `out` and submit the frame. The overlay is
+    /// cleared afterwards.
+    pub fn submit(
+        &mut self,
+        renderer: &mut core::Renderer,
+        mut frame: core::Frame,
+        out: &core::SwapChainTexture,
+    ) {
+        if !self.is_empty() {
+            let buffer = renderer.vertex_buffer(self.batch.vertices().as_slice());
+            renderer.update_pipeline(&self.pipeline, Matrix4::identity(), &mut frame);
+
+            let mut pass = frame.pass(PassOp::Load(), out);
+            pass.set_pipeline(&self.pipeline);
+            pass.draw(&buffer, &self.binding);
+        }
+        renderer.submit(frame);
+        self.batch.clear();
+    }
+}
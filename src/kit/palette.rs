@@ -0,0 +1,178 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::core;
+use crate::core::{ColorParseError, Op, Rgba8};
+
+/// An error returned when loading or parsing a [`Palette`].
+#[derive(Debug)]
+pub enum PaletteError {
+    Io(io::Error),
+    /// A line doesn't hold a valid color code.
+    Color(usize, ColorParseError),
+    /// There are more than [`Palette::SIZE`] colors.
+    Overflow,
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{}", err),
+            Self::Color(line, err) => write!(f, "line {}: {}", line, err),
+            Self::Overflow => write!(f, "palette has more than {} colors", Palette::SIZE),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+impl From<io::Error> for PaletteError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// An indexed color palette of up to 256 colors, eg. for pixel art.
+///
+/// Palettes are stored as text, with one color code per line, as parsed by
+/// [`Rgba8::from_str`], and can be uploaded as a 256x1 texture for
+/// palette-swap shaders to look up colors by index.
+///
+/// ```
+/// use rgx::core::Rgba8;
+/// use rgx::kit::palette::Palette;
+///
+/// let palette: Palette = "#000\n#ff0000\n\n#0000ff80\n".parse().unwrap();
+///
+/// assert_eq!(palette.len(), 3);
+/// assert_eq!(palette.get(1), Some(Rgba8::RED));
+/// assert_eq!(palette.nearest(Rgba8::new(0xe0, 0x10, 0x10, 0xff)), Some(1));
+/// assert_eq!(palette.to_string().parse::<Palette>().unwrap(), palette);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Palette {
+    colors: Vec<Rgba8>,
+}
+
+impl Palette {
+    /// The maximum number of colors, and the width of palette textures.
+    pub const SIZE: usize = 256;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a color, returning its index.
+    pub fn add(&mut self, color: Rgba8) -> u8 {
+        assert!(self.colors.len() < Self::SIZE, "fatal: palette is full");
+
+        self.colors.push(color);
+        (self.colors.len() - 1) as u8
+    }
+
+    pub fn get(&self, index: u8) -> Option<Rgba8> {
+        self.colors.get(index as usize).copied()
+    }
+
+    /// Replace the color at `index`, eg. for palette swaps.
+    pub fn set(&mut self, index: u8, color: Rgba8) {
+        self.colors[index as usize] = color;
+    }
+
+    pub fn colors(&self) -> &[Rgba8] {
+        &self.colors
+    }
+
+    pub fn len(&self) -> usize {
+        self.colors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.colors.is_empty()
+    }
+
+    /// The index of the color closest to `color`, by distance between
+    /// channels, including alpha. Returns `None` if the palette is empty.
+    pub fn nearest(&self, color: Rgba8) -> Option<u8> {
+        let distance = |c: &Rgba8| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(c.r, color.r) + d(c.g, color.g) + d(c.b, color.b) + d(c.a, color.a)
+        };
+
+        self.colors
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, c)| distance(c))
+            .map(|(i, _)| i as u8)
+    }
+
+    /// Load a palette from a file.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, PaletteError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    /// Save the palette to a file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// The palette texels, padded to [`Palette::SIZE`] with transparent
+    /// texels.
+    pub fn texels(&self) -> Vec<Rgba8> {
+        let mut texels = self.colors.clone();
+        texels.resize(Self::SIZE, Rgba8::TRANSPARENT);
+        texels
+    }
+
+    /// Create a 256x1 texture and upload the palette to it. Texel `i` holds
+    /// color `i`, so shaders should sample it without filtering, at
+    /// `u = (i + 0.5) / 256`.
+    pub fn upload(&self, renderer: &mut core::Renderer) -> core::Texture {
+        let texture = renderer.texture(Self::SIZE as u32, 1);
+        let texels = self.texels();
+        let (head, body, tail) = unsafe { texels.align_to::<u8>() };
+        assert!(head.is_empty() && tail.is_empty());
+
+        renderer.prepare(&[Op::Fill(&texture, body)]);
+        texture
+    }
+}
+
+impl FromStr for Palette {
+    type Err = PaletteError;
+
+    /// Parse a palette with one color code per line. Blank lines are
+    /// ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut colors = Vec::new();
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if colors.len() == Self::SIZE {
+                return Err(PaletteError::Overflow);
+            }
+            colors.push(
+                line.parse()
+                    .map_err(|err| PaletteError::Color(i + 1, err))?,
+            );
+        }
+        Ok(Self { colors })
+    }
+}
+
+impl fmt::Display for Palette {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for c in self.colors.iter() {
+            writeln!(f, "{}", c)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,399 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{Binding, BindingType, PassOp, Set, ShaderStage, TextureFormat};
+
+use crate::kit::color::ColorSpace;
+
+use crate::math::*;
+
+///////////////////////////////////////////////////////////////////////////
+// Uniforms
+///////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Uniforms {
+    pub exposure: f32,
+    pub tonemap: f32,
+    /// Whether scene texels are sRGB-encoded, and must be decoded before
+    /// converting color spaces.
+    pub decode: f32,
+    /// Whether output texels must be sRGB-encoded after converting color
+    /// spaces.
+    pub encode: f32,
+    /// Rows of the matrix converting linear scene colors to the display
+    /// color space, padded to four components.
+    pub gamut: [[f32; 4]; 3],
+}
+
+impl Uniforms {
+    const IDENTITY: [[f32; 4]; 3] = [[1., 0., 0., 0.], [0., 1., 0., 0.], [0., 0., 1., 0.]];
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Vertex
+///////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct Vertex {
+    position: Vector2<f32>,
+    uv: Vector2<f32>,
+}
+
+impl Vertex {
+    fn new(x: f32, y: f32, u: f32, v: f32) -> Self {
+        Self {
+            position: Vector2::new(x, y),
+            uv: Vector2::new(u, v),
+        }
+    }
+}
+
+/// Create a vertex buffer covering the whole render target, with texture
+/// coordinates covering the whole texture.
+pub fn quad(renderer: &core::Renderer) -> core::VertexBuffer {
+    renderer.vertex_buffer(&[
+        Vertex::new(-1.0, -1.0, 0.0, 1.0),
+        Vertex::new(1.0, -1.0, 1.0, 1.0),
+        Vertex::new(1.0, 1.0, 1.0, 0.0),
+        Vertex::new(-1.0, -1.0, 0.0, 1.0),
+        Vertex::new(-1.0, 1.0, 0.0, 0.0),
+        Vertex::new(1.0, 1.0, 1.0, 0.0),
+    ])
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Fullscreen
+///////////////////////////////////////////////////////////////////////////
+
+/// The state shared by post pipelines drawing a [`quad`] over the whole
+/// target: the pipeline, and the uniform buffer of its fragment shader,
+/// bound in set `1`. Post pipelines wrap it, and delegate to it from their
+/// [`core::AbstractPipeline`] implementation.
+pub struct Fullscreen {
+    pub pipeline: core::Pipeline,
+
+    bindings: core::BindingGroup,
+    buf: core::UniformBuffer,
+    width: u32,
+    height: u32,
+}
+
+impl Fullscreen {
+    /// A set with a texture named `tex` and a sampler named `sam`, eg. for
+    /// sampling a scene.
+    pub const TEXTURE: Set<'static> = Set(&[
+        Binding {
+            binding: BindingType::SampledTexture,
+            stage: ShaderStage::Fragment,
+        },
+        Binding {
+            binding: BindingType::Sampler,
+            stage: ShaderStage::Fragment,
+        },
+    ]);
+
+    /// A set with the uniform buffer of the fragment shader.
+    pub const UNIFORMS: Set<'static> = Set(&[Binding {
+        binding: BindingType::UniformBuffer,
+        stage: ShaderStage::Fragment,
+    }]);
+
+    /// Describe a pipeline drawing a [`quad`] with the given fragment shader.
+    /// The uniforms must be in set `1`, as with [`Fullscreen::UNIFORMS`].
+    pub fn description<'a>(
+        pipeline_layout: &'a [Set<'a>],
+        fragment_shader: &'static [u8],
+    ) -> core::PipelineDescription<'a> {
+        core::PipelineDescription {
+            vertex_layout: &[core::VertexFormat::Float2, core::VertexFormat::Float2],
+            pipeline_layout,
+            vertex_shader: kit_shader!("data/present.vert"),
+            fragment_shader,
+        }
+    }
+
+    /// Set up the pipeline, with a uniform buffer holding `uniforms`.
+    pub fn setup<T: 'static + Copy>(
+        pipeline: core::Pipeline,
+        dev: &core::Device,
+        width: u32,
+        height: u32,
+        uniforms: T,
+    ) -> Self {
+        let buf = dev.create_uniform_buffer(&[uniforms]);
+        let bindings = dev.create_binding_group(&pipeline.layout.sets[1], &[&buf]);
+
+        Self {
+            pipeline,
+            bindings,
+            buf,
+            width,
+            height,
+        }
+    }
+
+    /// Bind a texture and sampler, for pipelines whose set `0` is
+    /// [`Fullscreen::TEXTURE`].
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.pipeline
+            .binding_group(0)
+            .texture("tex", texture)
+            .sampler("sam", sampler)
+            .build(&renderer.device)
+            .expect("fatal: kit shaders declare their bindings")
+    }
+
+    /// The uniform buffer of the fragment shader.
+    pub fn uniforms(&self) -> &core::UniformBuffer {
+        &self.buf
+    }
+
+    pub fn resize(&mut self, w: u32, h: u32) {
+        self.width = w;
+        self.height = h;
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn apply(&self, pass: &mut core::Pass) {
+        pass.set_pipeline(&self.pipeline);
+        pass.set_binding(&self.bindings, &[]);
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// Copies a scene texture to the output, converting between formats and
+/// color spaces, and tonemapping float scenes when the output can't
+/// represent values above `1.0`.
+pub struct Pipeline {
+    fullscreen: Fullscreen,
+    tonemap: bool,
+    gamut: Option<([[f32; 4]; 3], bool, bool)>,
+}
+
+impl Pipeline {
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.fullscreen.binding(renderer, texture, sampler)
+    }
+
+    /// Enable or disable tonemapping of the scene.
+    pub fn set_tonemap(&mut self, tonemap: bool) {
+        self.tonemap = tonemap;
+    }
+
+    /// Convert the scene from one color space to another. Conversion
+    /// happens on linear colors, so `decode` should be set if the sampled
+    /// scene is sRGB-encoded, and `encode` if the output expects encoded
+    /// values, ie. for formats which aren't float or sRGB formats.
+    pub fn set_gamut(&mut self, from: ColorSpace, to: ColorSpace, decode: bool, encode: bool) {
+        if from == to {
+            self.gamut = None;
+            return;
+        }
+        let m = from.matrix(to);
+        let row = |r: [f32; 3]| [r[0], r[1], r[2], 0.];
+
+        self.gamut = Some(([row(m[0]), row(m[1]), row(m[2])], decode, encode));
+    }
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    type PrepareContext = f32;
+    type Uniforms = self::Uniforms;
+
+    fn description() -> core::PipelineDescription<'a> {
+        Fullscreen::description(
+            &[Fullscreen::TEXTURE, Fullscreen::UNIFORMS],
+            kit_shader!("data/present.frag"),
+        )
+    }
+
+    fn setup(pipeline: core::Pipeline, dev: &core::Device, width: u32, height: u32) -> Self {
+        let uniforms = self::Uniforms {
+            exposure: 1.0,
+            tonemap: 0.0,
+            decode: 0.0,
+            encode: 0.0,
+            gamut: self::Uniforms::IDENTITY,
+        };
+
+        Self {
+            fullscreen: Fullscreen::setup(pipeline, dev, width, height, uniforms),
+            tonemap: false,
+            gamut: None,
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.fullscreen.resize(w, h);
+    }
+
+    fn width(&self) -> u32 {
+        self.fullscreen.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.fullscreen.height()
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        self.fullscreen.apply(pass);
+    }
+
+    fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
+        &'a self,
+        exposure: f32,
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
+        let flag = |b: bool| if b { 1.0 } else { 0.0 };
+        let (gamut, decode, encode) =
+            self.gamut
+                .unwrap_or((self::Uniforms::IDENTITY, false, false));
+
+        Some((
+            self.fullscreen.uniforms(),
+            core::SmallVec::from_buf([self::Uniforms {
+                exposure,
+                tonemap: flag(self.tonemap),
+                decode: flag(decode),
+                encode: flag(encode),
+                gamut,
+            }]),
+        ))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Presenter
+///////////////////////////////////////////////////////////////////////////
+
+/// Renders the scene to an offscreen target in `scene` format, and presents
+/// it to a swap chain of a possibly different format.
+///
+/// When the scene is rendered in `Rgba16Float` and the output is an integer
+/// format, eg. `Bgra8Unorm` or `Rgb10a2Unorm`, the scene is tonemapped on
+/// presentation. Otherwise, it is simply converted. Scenes authored in
+/// another color space than the display's are converted with
+/// [`Presenter::set_color_spaces`].
+pub struct Presenter {
+    pub scene: TextureFormat,
+    pub output: TextureFormat,
+
+    exposure: f32,
+    pipeline: Pipeline,
+    target: core::Framebuffer,
+    sampler: core::Sampler,
+    binding: core::BindingGroup,
+    quad: core::VertexBuffer,
+}
+
+impl Presenter {
+    pub fn new(
+        renderer: &core::Renderer,
+        w: u32,
+        h: u32,
+        scene: TextureFormat,
+        output: TextureFormat,
+    ) -> Self {
+        let mut pipeline: Pipeline =
+            renderer.pipeline_with_format(w, h, core::Blending::replace(), output);
+        pipeline.set_tonemap(scene.is_float() && !output.is_float());
+
+        let target = renderer.framebuffer_with_format(w, h, scene);
+        let sampler = renderer.sampler(core::Filter::Nearest, core::Filter::Nearest);
+        let binding = pipeline.binding(renderer, &target.texture, &sampler);
+        let quad = self::quad(renderer);
+
+        Self {
+            scene,
+            output,
+            exposure: 1.0,
+            pipeline,
+            target,
+            sampler,
+            binding,
+            quad,
+        }
+    }
+
+    /// The offscreen target the scene should be rendered to.
+    pub fn target(&self) -> &core::Framebuffer {
+        &self.target
+    }
+
+    /// Recreate the offscreen target with a new size.
+    pub fn resize(&mut self, renderer: &core::Renderer, w: u32, h: u32) {
+        use core::AbstractPipeline;
+
+        self.target = renderer.framebuffer_with_format(w, h, self.scene);
+        self.binding = self
+            .pipeline
+            .binding(renderer, &self.target.texture, &self.sampler);
+        self.pipeline.resize(w, h);
+    }
+
+    /// Convert the scene from the `scene` color space to the `display`
+    /// color space when presenting, eg. to show an sRGB scene correctly on
+    /// a Display P3 output.
+    pub fn set_color_spaces(&mut self, scene: ColorSpace, display: ColorSpace) {
+        let encoded = |f: TextureFormat| !f.is_srgb() && !f.is_float();
+
+        self.pipeline
+            .set_gamut(scene, display, encoded(self.scene), encoded(self.output));
+    }
+
+    /// The exposure applied to the scene before tonemapping.
+    pub fn exposure(&self) -> f32 {
+        self.exposure
+    }
+
+    /// Set the exposure applied to the scene before tonemapping.
+    pub fn set_exposure(&mut self, exposure: f32) {
+        self.exposure = exposure;
+    }
+
+    /// Present the scene to the output view, which must be of the `output` format.
+    pub fn present<T: core::TextureView>(
+        &self,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+        out: &T,
+    ) {
+        renderer.update_pipeline(&self.pipeline, self.exposure, frame);
+
+        // The quad covers the whole target and replaces its texels, including
+        // alpha, so its previous contents don't matter.
+        let mut pass = frame.pass(PassOp::DontCare(), out);
+        pass.set_pipeline(&self.pipeline);
+        pass.draw(&self.quad, &self.binding);
+    }
+}
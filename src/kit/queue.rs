@@ -0,0 +1,165 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::cmp::Ordering;
+
+use crate::core;
+use crate::kit::{shape2d, sprite2d};
+
+/// A drawable in a [`DrawQueue`].
+#[derive(Copy, Clone)]
+pub enum Item<'a> {
+    /// A sprite batch buffer, with its texture binding.
+    Sprite(&'a core::VertexBuffer, &'a core::BindingGroup),
+    /// A shape batch buffer.
+    Shape(&'a core::VertexBuffer),
+}
+
+impl Item<'_> {
+    /// Sort key grouping items by pipeline, then by binding.
+    fn group(&self) -> (u8, usize) {
+        match self {
+            Self::Shape(_) => (0, 0),
+            Self::Sprite(_, binding) => (1, *binding as *const core::BindingGroup as usize),
+        }
+    }
+}
+
+/// How items of a [`DrawQueue`] are ordered.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Sort {
+    /// Items are drawn in depth order, and only items of equal depth are
+    /// grouped by pipeline and binding.
+    #[default]
+    Depth,
+    /// Items are grouped by pipeline and binding first, and drawn in depth
+    /// order within each group. This issues the fewest switches, but
+    /// overlapping items with different bindings may be drawn out of depth
+    /// order, so it is best suited to items which don't overlap, eg. tiles
+    /// or UI from different textures.
+    Texture,
+}
+
+/// Pipeline and binding switches issued when drawing a [`DrawQueue`].
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct QueueStats {
+    pub draws: u32,
+    pub pipeline_switches: u32,
+    pub binding_switches: u32,
+}
+
+/// A queue of sprite and shape batches ordered by depth.
+///
+/// Items are drawn in increasing `z` order, across kit pipelines. Items of
+/// equal depth are grouped by pipeline and binding, so that interleaved
+/// batches issue as few switches as possible. With [`Sort::Texture`],
+/// items are grouped across depths as well, eg. when multi-texture batching
+/// isn't possible.
+#[derive(Default)]
+pub struct DrawQueue<'a> {
+    pub sort: Sort,
+
+    items: Vec<(f32, Item<'a>)>,
+}
+
+impl<'a> DrawQueue<'a> {
+    pub fn new() -> Self {
+        Self::with_sort(Sort::Depth)
+    }
+
+    /// Create a queue ordering its items by the given mode.
+    pub fn with_sort(sort: Sort) -> Self {
+        Self {
+            sort,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, z: f32, item: Item<'a>) {
+        self.items.push((z, item));
+    }
+
+    pub fn sprite(&mut self, z: f32, buf: &'a core::VertexBuffer, binding: &'a core::BindingGroup) {
+        self.push(z, Item::Sprite(buf, binding));
+    }
+
+    /// Queue a sprite batch buffer, bound with the given filter.
+    pub fn sprite_filtered(
+        &mut self,
+        z: f32,
+        buf: &'a core::VertexBuffer,
+        bindings: &'a sprite2d::FilteredBinding,
+        filter: core::Filter,
+    ) {
+        self.sprite(z, buf, bindings.get(filter));
+    }
+
+    pub fn shape(&mut self, z: f32, buf: &'a core::VertexBuffer) {
+        self.push(z, Item::Shape(buf));
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.items.clear();
+    }
+
+    /// Sort and draw every item in the queue, leaving it empty.
+    pub fn draw(
+        &mut self,
+        pass: &mut core::Pass,
+        sprites: &sprite2d::Pipeline,
+        shapes: &shape2d::Pipeline,
+    ) -> QueueStats {
+        let sort = self.sort;
+
+        self.items.sort_by(|(za, a), (zb, b)| {
+            let depth = za.partial_cmp(zb).unwrap_or(Ordering::Equal);
+            let group = a.group().cmp(&b.group());
+
+            match sort {
+                Sort::Depth => depth.then(group),
+                Sort::Texture => group.then(depth),
+            }
+        });
+
+        let mut stats = QueueStats::default();
+        let mut pipeline = None;
+        let mut binding = None;
+
+        for (_, item) in self.items.drain(..) {
+            let (kind, id) = item.group();
+
+            if pipeline != Some(kind) {
+                match item {
+                    Item::Shape(_) => pass.set_pipeline(shapes),
+                    Item::Sprite(_, _) => pass.set_pipeline(sprites),
+                }
+                pipeline = Some(kind);
+                binding = None;
+                stats.pipeline_switches += 1;
+            }
+
+            match item {
+                Item::Shape(buf) => {
+                    pass.draw_buffer(buf);
+                }
+                Item::Sprite(buf, group) => {
+                    if binding != Some(id) {
+                        pass.set_binding(group, &[]);
+                        binding = Some(id);
+                        stats.binding_switches += 1;
+                    }
+                    pass.draw_buffer(buf);
+                }
+            }
+            stats.draws += 1;
+        }
+        stats
+    }
+}
@@ -0,0 +1,340 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core::{Rect, Rgba};
+
+use crate::kit;
+use crate::kit::camera::Camera2D;
+use crate::kit::shape2d::{self, Fill, Line, Shape, Stroke};
+
+use crate::math::*;
+
+/// The orientation of a ruler or guide.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Axis {
+    /// Along the x axis. Horizontal rulers measure `x`, and horizontal
+    /// guides mark a `y` coordinate.
+    Horizontal,
+    /// Along the y axis. Vertical rulers measure `y`, and vertical guides
+    /// mark an `x` coordinate.
+    Vertical,
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Ruler
+///////////////////////////////////////////////////////////////////////////
+
+/// A tick label, to be drawn by the application with its text renderer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Label {
+    /// Position of the label, in logical pixels.
+    pub position: Point2<f32>,
+    /// The canvas coordinate at the tick.
+    pub value: f32,
+}
+
+/// A ruler along the top or left edge of the window, with tick marks at
+/// canvas coordinates, synced to a [`Camera2D`].
+///
+/// Rulers are drawn in logical pixels, so the batch should be drawn without
+/// the camera transform. The spacing between ticks adapts to the zoom level.
+///
+/// ```
+/// use rgx::kit::ruler::{Axis, Ruler};
+///
+/// let ruler = Ruler::new(Axis::Horizontal);
+///
+/// assert_eq!(ruler.step(1.), 50.);
+/// assert_eq!(ruler.step(4.), 20.);
+/// assert_eq!(ruler.step(0.1), 500.);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Ruler {
+    pub axis: Axis,
+    /// Thickness of the ruler, in logical pixels.
+    pub thickness: f32,
+    pub color: Rgba,
+    pub background: Rgba,
+    /// Minimum distance between labelled ticks, in logical pixels.
+    pub spacing: f32,
+    /// Number of minor ticks per labelled tick.
+    pub subdivisions: u32,
+}
+
+impl Ruler {
+    pub fn new(axis: Axis) -> Self {
+        Self {
+            axis,
+            thickness: 20.,
+            color: Rgba::WHITE,
+            background: Rgba::BLACK,
+            spacing: 50.,
+            subdivisions: 5,
+        }
+    }
+
+    /// The distance between labelled ticks in canvas units, at the given
+    /// zoom level: the smallest of 1, 2 or 5 times a power of ten that is at
+    /// least `spacing` pixels wide.
+    pub fn step(&self, zoom: f32) -> f32 {
+        let min = self.spacing / zoom;
+        let base = 10f32.powf(min.log10().floor());
+
+        for m in &[1., 2., 5.] {
+            if base * m >= min {
+                return base * m;
+            }
+        }
+        base * 10.
+    }
+
+    /// Whether the ruler contains `p`, in logical pixels.
+    pub fn contains(&self, p: Point2<f32>) -> bool {
+        match self.axis {
+            Axis::Horizontal => p.y >= 0. && p.y < self.thickness,
+            Axis::Vertical => p.x >= 0. && p.x < self.thickness,
+        }
+    }
+
+    /// Add the ruler to a shape batch, for a window `length` logical pixels
+    /// long along the ruler's axis. Returns the labels of the major ticks.
+    pub fn draw(&self, camera: &Camera2D, length: f32, batch: &mut shape2d::Batch) -> Vec<Label> {
+        let (zoom, scale) = (camera.zoom, camera.scale_factor);
+        let offset = match self.axis {
+            Axis::Horizontal => camera.offset.x,
+            Axis::Vertical => camera.offset.y,
+        };
+        let step = self.step(zoom);
+        let minor = step / self.subdivisions as f32;
+        let stroke = Stroke::new(1. / scale as f32, self.color);
+
+        let (first, last) = (
+            (-offset / zoom / minor).floor() as i64,
+            ((length - offset) / zoom / minor).ceil() as i64,
+        );
+        let mut labels = Vec::new();
+
+        batch.add(Shape::Rectangle(
+            self.rect(0., length),
+            Stroke::NONE,
+            Fill::Solid(self.background),
+        ));
+
+        for i in first..=last {
+            let major = i % self.subdivisions as i64 == 0;
+            let pos = kit::snap_line(i as f32 * minor * zoom + offset, scale);
+            let size = if major {
+                self.thickness
+            } else {
+                self.thickness / 4.
+            };
+            let (p, line) = match self.axis {
+                Axis::Horizontal => (
+                    Point2::new(pos + 2., 0.),
+                    Line::new(pos, self.thickness, pos, self.thickness - size),
+                ),
+                Axis::Vertical => (
+                    Point2::new(0., pos + 2.),
+                    Line::new(self.thickness, pos, self.thickness - size, pos),
+                ),
+            };
+            batch.add(Shape::Line(line, stroke));
+
+            if major {
+                labels.push(Label {
+                    position: p,
+                    value: (i / self.subdivisions as i64) as f32 * step,
+                });
+            }
+        }
+        labels
+    }
+
+    /// The area covered by the ruler between `from` and `to` along its axis.
+    fn rect(&self, from: f32, to: f32) -> Rect<f32> {
+        match self.axis {
+            Axis::Horizontal => Rect::new(from, 0., to, self.thickness),
+            Axis::Vertical => Rect::new(0., from, self.thickness, to),
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Guides
+///////////////////////////////////////////////////////////////////////////
+
+/// A guide line spanning the window, at a canvas coordinate.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Guide {
+    pub axis: Axis,
+    /// The `y` coordinate of horizontal guides, or the `x` coordinate of
+    /// vertical guides, in canvas units.
+    pub position: f32,
+}
+
+impl Guide {
+    pub fn new(axis: Axis, position: f32) -> Self {
+        Self { axis, position }
+    }
+
+    /// The guide's distance from `p`, in canvas units.
+    fn distance(&self, p: Point2<f32>) -> f32 {
+        match self.axis {
+            Axis::Horizontal => (p.y - self.position).abs(),
+            Axis::Vertical => (p.x - self.position).abs(),
+        }
+    }
+
+    /// The coordinate of `p` that the guide marks.
+    fn coordinate(axis: Axis, p: Point2<f32>) -> f32 {
+        match axis {
+            Axis::Horizontal => p.y,
+            Axis::Vertical => p.x,
+        }
+    }
+}
+
+/// A set of draggable guides. Points are in window coordinates, as found in
+/// input events, and mapped to the canvas with a [`Camera2D`].
+///
+/// ```
+/// use rgx::kit::camera::Camera2D;
+/// use rgx::kit::ruler::{Axis, Guide, Guides};
+/// use rgx::math::Point2;
+///
+/// let mut cam = Camera2D::new(1.0);
+/// cam.zoom = 2.;
+///
+/// let mut guides = Guides::new();
+/// guides.add(Guide::new(Axis::Vertical, 10.));
+///
+/// assert!(guides.grab(&cam, Point2::new(21., 5.)));
+/// guides.drag(&cam, Point2::new(30., 5.));
+/// guides.release();
+///
+/// assert_eq!(guides.guides()[0].position, 15.);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Guides {
+    /// Distance in logical pixels within which a guide can be grabbed.
+    pub tolerance: f32,
+    pub color: Rgba,
+    /// Grid cell size in canvas units. When set, dragged guides snap to
+    /// the grid.
+    pub grid: Option<f32>,
+
+    guides: Vec<Guide>,
+    dragging: Option<usize>,
+}
+
+impl Default for Guides {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Guides {
+    pub fn new() -> Self {
+        Self {
+            tolerance: 4.,
+            color: Rgba::new(0., 1., 1., 1.),
+            grid: None,
+            guides: Vec::new(),
+            dragging: None,
+        }
+    }
+
+    pub fn add(&mut self, guide: Guide) -> usize {
+        self.guides.push(guide);
+        self.guides.len() - 1
+    }
+
+    pub fn remove(&mut self, index: usize) -> Guide {
+        if self.dragging == Some(index) {
+            self.dragging = None;
+        }
+        self.guides.remove(index)
+    }
+
+    pub fn guides(&self) -> &[Guide] {
+        &self.guides
+    }
+
+    /// The index of the guide closest to `p`, within the tolerance.
+    pub fn hit(&self, camera: &Camera2D, p: Point2<f32>) -> Option<usize> {
+        let canvas = camera.to_canvas(p);
+        let max = self.tolerance / camera.zoom;
+
+        self.guides
+            .iter()
+            .enumerate()
+            .map(|(i, g)| (i, g.distance(canvas)))
+            .filter(|(_, d)| *d <= max)
+            .min_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal))
+            .map(|(i, _)| i)
+    }
+
+    /// Start dragging the guide under `p`, if any. Returns whether a guide
+    /// was grabbed.
+    pub fn grab(&mut self, camera: &Camera2D, p: Point2<f32>) -> bool {
+        self.dragging = self.hit(camera, p);
+        self.dragging.is_some()
+    }
+
+    /// Create a guide at `p` and start dragging it, eg. when dragging out of
+    /// a ruler.
+    pub fn create(&mut self, axis: Axis, camera: &Camera2D, p: Point2<f32>) -> usize {
+        let position = self.position(axis, camera, p);
+        let index = self.add(Guide::new(axis, position));
+
+        self.dragging = Some(index);
+        index
+    }
+
+    /// Move the dragged guide to `p`.
+    pub fn drag(&mut self, camera: &Camera2D, p: Point2<f32>) {
+        if let Some(i) = self.dragging {
+            let axis = self.guides[i].axis;
+            self.guides[i].position = self.position(axis, camera, p);
+        }
+    }
+
+    /// Stop dragging, returning the index of the guide that was dragged.
+    pub fn release(&mut self) -> Option<usize> {
+        self.dragging.take()
+    }
+
+    /// The guide position under `p`, snapped to the grid if set.
+    fn position(&self, axis: Axis, camera: &Camera2D, p: Point2<f32>) -> f32 {
+        let mut canvas = camera.to_canvas(p);
+
+        if let Some(cell) = self.grid {
+            canvas = kit::snap_to_grid(canvas, Vector2::new(cell, cell));
+        }
+        Guide::coordinate(axis, canvas)
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.dragging.is_some()
+    }
+
+    /// Add the guides to a shape batch, for a window of `size` logical pixels.
+    /// Like rulers, guides are drawn without the camera transform.
+    pub fn draw(&self, camera: &Camera2D, size: Vector2<f32>, batch: &mut shape2d::Batch) {
+        let scale = camera.scale_factor;
+        let stroke = Stroke::new(1. / scale as f32, self.color);
+
+        for g in self.guides.iter() {
+            let line = match g.axis {
+                Axis::Horizontal => {
+                    let y = kit::snap_line(g.position * camera.zoom + camera.offset.y, scale);
+                    Line::new(0., y, size.x, y)
+                }
+                Axis::Vertical => {
+                    let x = kit::snap_line(g.position * camera.zoom + camera.offset.x, scale);
+                    Line::new(x, 0., x, size.y)
+                }
+            };
+            batch.add(Shape::Line(line, stroke));
+        }
+    }
+}
@@ -0,0 +1,227 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core::{Rect, Rgba};
+
+use crate::kit;
+use crate::kit::shape2d::{self, Dash, Fill, Shape, Stroke};
+
+use crate::math::*;
+
+/// How selections are drawn: a dashed outline over an optional solid
+/// background line, eg. for "marching ants", and an optional fill.
+#[derive(Copy, Clone, Debug)]
+pub struct Style {
+    pub width: f32,
+    pub color: Rgba,
+    /// Color of the line under the dashes. Transparent to disable.
+    pub background: Rgba,
+    pub dash: Dash,
+    /// Color of the selected area. Transparent to disable.
+    pub fill: Rgba,
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            width: 1.,
+            color: Rgba::WHITE,
+            background: Rgba::BLACK,
+            dash: Dash::new(4., 4.),
+            fill: Rgba::TRANSPARENT,
+        }
+    }
+}
+
+impl Style {
+    /// Add the outline through `points` to a shape batch.
+    fn outline(&self, batch: &mut shape2d::Batch, points: &[Vector2<f32>]) {
+        if self.background.a > 0. {
+            let solid = Dash::new(f32::MAX, 0.);
+
+            for s in shape2d::dashed(
+                points,
+                true,
+                solid,
+                Stroke::new(self.width, self.background),
+            ) {
+                batch.add(s);
+            }
+        }
+        for s in shape2d::dashed(points, true, self.dash, Stroke::new(self.width, self.color)) {
+            batch.add(s);
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Selection Rectangle
+///////////////////////////////////////////////////////////////////////////
+
+/// A rubber-band selection rectangle, dragged from `from` to `to` in screen
+/// space.
+///
+/// ```
+/// use rgx::core::Rect;
+/// use rgx::kit::selection::SelectionRect;
+/// use rgx::math::Vector2;
+///
+/// let mut sel = SelectionRect::new(Vector2::new(10., 10.));
+/// sel.drag(Vector2::new(4., 20.));
+///
+/// assert_eq!(sel.rect(), Rect::new(4., 10., 10., 20.));
+/// assert!(sel.contains(Vector2::new(5., 15.)));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SelectionRect {
+    pub from: Vector2<f32>,
+    pub to: Vector2<f32>,
+}
+
+impl SelectionRect {
+    pub fn new(from: Vector2<f32>) -> Self {
+        Self { from, to: from }
+    }
+
+    /// Move the dragged corner of the selection.
+    pub fn drag(&mut self, to: Vector2<f32>) {
+        self.to = to;
+    }
+
+    /// Snap both corners of the selection to a grid with the given cell size,
+    /// in the same space as the selection.
+    ///
+    /// ```
+    /// use rgx::core::Rect;
+    /// use rgx::kit::selection::SelectionRect;
+    /// use rgx::math::Vector2;
+    ///
+    /// let mut sel = SelectionRect::new(Vector2::new(3., 4.));
+    /// sel.drag(Vector2::new(17., 9.));
+    /// sel.snap(Vector2::new(8., 8.));
+    ///
+    /// assert_eq!(sel.rect(), Rect::new(0., 8., 16., 16.));
+    /// ```
+    pub fn snap(&mut self, cell: Vector2<f32>) {
+        let r = kit::snap_rect(
+            Rect::new(self.from.x, self.from.y, self.to.x, self.to.y),
+            cell,
+        );
+
+        self.from = Vector2::new(r.x1, r.y1);
+        self.to = Vector2::new(r.x2, r.y2);
+    }
+
+    /// The selected area, with its corners ordered.
+    pub fn rect(&self) -> Rect<f32> {
+        Rect::new(
+            f32::min(self.from.x, self.to.x),
+            f32::min(self.from.y, self.to.y),
+            f32::max(self.from.x, self.to.x),
+            f32::max(self.from.y, self.to.y),
+        )
+    }
+
+    pub fn contains(&self, p: Vector2<f32>) -> bool {
+        let r = self.rect();
+        p.x >= r.x1 && p.x <= r.x2 && p.y >= r.y1 && p.y <= r.y2
+    }
+
+    /// Add the selection to a shape batch.
+    pub fn draw(&self, batch: &mut shape2d::Batch, style: &Style) {
+        let r = self.rect();
+
+        if style.fill.a > 0. {
+            batch.add(Shape::Rectangle(r, Stroke::NONE, Fill::Solid(style.fill)));
+        }
+        style.outline(
+            batch,
+            &[
+                Vector2::new(r.x1, r.y1),
+                Vector2::new(r.x2, r.y1),
+                Vector2::new(r.x2, r.y2),
+                Vector2::new(r.x1, r.y2),
+            ],
+        );
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Lasso
+///////////////////////////////////////////////////////////////////////////
+
+/// A freehand lasso selection, following screen space points. The lasso is
+/// always closed between its last and first point.
+///
+/// ```
+/// use rgx::kit::selection::Lasso;
+/// use rgx::math::Vector2;
+///
+/// let mut lasso = Lasso::new();
+/// lasso.push(Vector2::new(0., 0.));
+/// lasso.push(Vector2::new(10., 0.));
+/// lasso.push(Vector2::new(0., 10.));
+///
+/// assert!(lasso.contains(Vector2::new(2., 2.)));
+/// assert!(!lasso.contains(Vector2::new(8., 8.)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Lasso {
+    /// Points closer than this to the previous point are ignored.
+    pub min_distance: f32,
+
+    points: Vec<Vector2<f32>>,
+}
+
+impl Default for Lasso {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lasso {
+    pub fn new() -> Self {
+        Self {
+            min_distance: 2.,
+            points: Vec::new(),
+        }
+    }
+
+    /// Extend the lasso to `p`, eg. on cursor movement.
+    pub fn push(&mut self, p: Vector2<f32>) {
+        if let Some(last) = self.points.last() {
+            if (p - *last).magnitude() < self.min_distance {
+                return;
+            }
+        }
+        self.points.push(p);
+    }
+
+    pub fn points(&self) -> &[Vector2<f32>] {
+        &self.points
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Whether the lasso contains `p`, using the even-odd rule.
+    pub fn contains(&self, p: Vector2<f32>) -> bool {
+        let n = self.points.len();
+        let mut inside = false;
+
+        for i in 0..n {
+            let (a, b) = (self.points[i], self.points[(i + n - 1) % n]);
+
+            if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Add the lasso outline to a shape batch. Fills aren't supported, since
+    /// lassos are usually concave.
+    pub fn draw(&self, batch: &mut shape2d::Batch, style: &Style) {
+        style.outline(batch, &self.points);
+    }
+}
@@ -0,0 +1,258 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{PassOp, Rgba};
+
+use crate::kit::present;
+use crate::kit::present::Fullscreen;
+use crate::math::Vector2;
+
+///////////////////////////////////////////////////////////////////////////
+// Uniforms
+///////////////////////////////////////////////////////////////////////////
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct Uniforms {
+    /// Tint of the blurred alpha channel.
+    pub color: Rgba,
+    /// Step between blur samples, in texture coordinates.
+    pub direction: [f32; 2],
+    /// Offset of the output, in texture coordinates.
+    pub offset: [f32; 2],
+    /// Pass the texture through untouched, instead of blurring it.
+    pub keep: f32,
+    _padding: [f32; 3],
+}
+
+impl Uniforms {
+    fn new(color: Rgba, direction: [f32; 2], offset: [f32; 2], keep: bool) -> Self {
+        Self {
+            color,
+            direction,
+            offset,
+            keep: if keep { 1. } else { 0. },
+            _padding: [0.; 3],
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// Blurs and tints the alpha channel of a texture along one axis.
+pub struct Pipeline {
+    fullscreen: Fullscreen,
+}
+
+impl Pipeline {
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.fullscreen.binding(renderer, texture, sampler)
+    }
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    type PrepareContext = self::Uniforms;
+    type Uniforms = self::Uniforms;
+
+    fn description() -> core::PipelineDescription<'a> {
+        Fullscreen::description(
+            &[Fullscreen::TEXTURE, Fullscreen::UNIFORMS],
+            kit_shader!("data/shadow.frag"),
+        )
+    }
+
+    fn setup(pipeline: core::Pipeline, dev: &core::Device, width: u32, height: u32) -> Self {
+        let uniforms = self::Uniforms::new(Rgba::TRANSPARENT, [0., 0.], [0., 0.], true);
+
+        Self {
+            fullscreen: Fullscreen::setup(pipeline, dev, width, height, uniforms),
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.fullscreen.resize(w, h);
+    }
+
+    fn width(&self) -> u32 {
+        self.fullscreen.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.fullscreen.height()
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        self.fullscreen.apply(pass);
+    }
+
+    fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
+        &'a self,
+        unifs: self::Uniforms,
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
+        Some((
+            self.fullscreen.uniforms(),
+            core::SmallVec::from_buf([unifs]),
+        ))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// DropShadow
+///////////////////////////////////////////////////////////////////////////
+
+/// A drop shadow effect.
+///
+/// Drawables are rendered to the effect's [`target`](DropShadow::target).
+/// When drawn, the target's alpha channel is blurred by `radius` pixels,
+/// tinted with `color` and composited at `offset` beneath the original.
+pub struct DropShadow {
+    pub color: Rgba,
+    pub offset: Vector2<f32>,
+    pub radius: f32,
+
+    width: u32,
+    height: u32,
+
+    layer: core::Framebuffer,
+    scratch: core::Framebuffer,
+
+    horizontal: Pipeline,
+    vertical: Pipeline,
+    composite: Pipeline,
+
+    horizontal_binding: core::BindingGroup,
+    vertical_binding: core::BindingGroup,
+    composite_binding: core::BindingGroup,
+
+    sampler: core::Sampler,
+    quad: core::VertexBuffer,
+}
+
+impl DropShadow {
+    pub fn new(
+        renderer: &core::Renderer,
+        w: u32,
+        h: u32,
+        color: Rgba,
+        offset: Vector2<f32>,
+        radius: f32,
+    ) -> Self {
+        // The horizontal pass overwrites the scratch target, while the
+        // other passes blend over the output.
+        let horizontal: Pipeline = renderer.pipeline(w, h, core::Blending::constant());
+        let vertical: Pipeline = renderer.pipeline(w, h, core::Blending::default());
+        let composite: Pipeline = renderer.pipeline(w, h, core::Blending::default());
+
+        let layer = renderer.framebuffer(w, h);
+        let scratch = renderer.framebuffer(w, h);
+        let sampler = renderer.sampler(core::Filter::Linear, core::Filter::Linear);
+
+        let horizontal_binding = horizontal.binding(renderer, &layer.texture, &sampler);
+        let vertical_binding = vertical.binding(renderer, &scratch.texture, &sampler);
+        let composite_binding = composite.binding(renderer, &layer.texture, &sampler);
+
+        Self {
+            color,
+            offset,
+            radius,
+            width: w,
+            height: h,
+            layer,
+            scratch,
+            horizontal,
+            vertical,
+            composite,
+            horizontal_binding,
+            vertical_binding,
+            composite_binding,
+            sampler,
+            quad: present::quad(renderer),
+        }
+    }
+
+    /// The offscreen target drawables casting a shadow should be rendered to.
+    pub fn target(&self) -> &core::Framebuffer {
+        &self.layer
+    }
+
+    /// Recreate the offscreen targets with a new size.
+    pub fn resize(&mut self, renderer: &core::Renderer, w: u32, h: u32) {
+        use core::AbstractPipeline;
+
+        self.width = w;
+        self.height = h;
+        self.layer = renderer.framebuffer(w, h);
+        self.scratch = renderer.framebuffer(w, h);
+
+        self.horizontal_binding =
+            self.horizontal
+                .binding(renderer, &self.layer.texture, &self.sampler);
+        self.vertical_binding =
+            self.vertical
+                .binding(renderer, &self.scratch.texture, &self.sampler);
+        self.composite_binding =
+            self.composite
+                .binding(renderer, &self.layer.texture, &self.sampler);
+
+        self.horizontal.resize(w, h);
+        self.vertical.resize(w, h);
+        self.composite.resize(w, h);
+    }
+
+    /// Draw the shadow, followed by the target, over the contents of `out`.
+    pub fn draw<T: core::TextureView>(
+        &self,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+        out: &T,
+    ) {
+        let (w, h) = (self.width as f32, self.height as f32);
+        // Nine samples are taken on each axis, spanning the radius.
+        let step = self.radius / 4.;
+        let offset = [self.offset.x / w, self.offset.y / h];
+
+        renderer.update_pipeline(
+            &self.horizontal,
+            Uniforms::new(Rgba::WHITE, [step / w, 0.], [0., 0.], false),
+            frame,
+        );
+        renderer.update_pipeline(
+            &self.vertical,
+            Uniforms::new(self.color, [0., step / h], offset, false),
+            frame,
+        );
+        renderer.update_pipeline(
+            &self.composite,
+            Uniforms::new(Rgba::TRANSPARENT, [0., 0.], [0., 0.], true),
+            frame,
+        );
+
+        {
+            let mut pass = frame.pass(PassOp::Clear(Rgba::TRANSPARENT), &self.scratch);
+            pass.set_pipeline(&self.horizontal);
+            pass.draw(&self.quad, &self.horizontal_binding);
+        }
+        {
+            let mut pass = frame.pass(PassOp::Load(), out);
+            pass.set_pipeline(&self.vertical);
+            pass.draw(&self.quad, &self.vertical_binding);
+            pass.set_pipeline(&self.composite);
+            pass.draw(&self.quad, &self.composite_binding);
+        }
+    }
+}
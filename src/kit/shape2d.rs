@@ -1,7 +1,9 @@
 #![deny(clippy::all, clippy::use_self)]
 #![allow(clippy::new_without_default)]
 
+use std::collections::HashMap;
 use std::f32;
+use std::rc::Rc;
 
 use crate::math::*;
 
@@ -20,6 +22,38 @@ use crate::kit::{Model, Rgba8};
 pub struct Uniforms {
     pub ortho: Matrix4<f32>,
     pub transform: Matrix4<f32>,
+    /// Size of the target in physical pixels, used for pixel snapping.
+    pub viewport: Vector2<f32>,
+}
+
+/// Uniforms of the fragment shader.
+#[repr(C)]
+#[derive(Copy, Clone)]
+struct Shading {
+    /// Gamma applied to coverage, see [`Coverage`].
+    gamma: f32,
+    /// Width of the gradient texture, in texels.
+    gradient: f32,
+}
+
+/// How partially covered pixels, eg. on anti-aliased edges, are blended.
+#[derive(Copy, Clone, Debug, PartialEq, Default)]
+pub enum Coverage {
+    /// Coverage is blended as-is, in the space of the render target.
+    #[default]
+    Nonlinear,
+    /// Coverage is corrected so that blending approximates linear space on
+    /// sRGB targets, preventing thin lines from looking too thin or dark.
+    Linear,
+}
+
+impl Coverage {
+    fn gamma(self) -> f32 {
+        match self {
+            Self::Nonlinear => 1.0,
+            Self::Linear => 2.2,
+        }
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -31,6 +65,14 @@ pub struct Uniforms {
 pub struct Vertex {
     position: Vector2<f32>,
     color: Rgba8,
+    snap: f32,
+    /// Offset from the position in screen pixels, which is unaffected by
+    /// the view scale.
+    extrude: Vector2<f32>,
+    /// Fraction of the pixel covered by the shape, eg. on anti-aliased edges.
+    coverage: f32,
+    /// Offset into the pipeline's gradient, and how much of it is applied.
+    gradient: Vector2<f32>,
 }
 
 impl Vertex {
@@ -38,6 +80,30 @@ impl Vertex {
         Self {
             position: Vector2::new(x, y),
             color,
+            snap: 0.0,
+            extrude: Vector2::new(0., 0.),
+            coverage: 1.0,
+            gradient: Vector2::new(0., 0.),
+        }
+    }
+
+    /// Return the vertex with the given coverage, from `0` to `1`, eg. for
+    /// the outer edge of an anti-aliasing fringe in geometry added with
+    /// [`Batch::add_triangles`]. Coverage is blended according to the
+    /// pipeline's [`Coverage`], separately from the color's alpha.
+    pub fn with_coverage(self, coverage: f32) -> Self {
+        Self { coverage, ..self }
+    }
+
+    /// Interpolate two vertices of the same shape.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: self.position + (other.position - self.position) * t,
+            color: self.color.mix(other.color, t),
+            extrude: self.extrude + (other.extrude - self.extrude) * t,
+            coverage: self.coverage + (other.coverage - self.coverage) * t,
+            gradient: self.gradient + (other.gradient - self.gradient) * t,
+            ..*self
         }
     }
 }
@@ -57,8 +123,64 @@ pub struct Pipeline {
     buf: core::UniformBuffer,
     width: u32,
     height: u32,
+    scale: f64,
     ortho: Matrix4<f32>,
     model: Model,
+    shading: Shading,
+    shading_buf: core::UniformBuffer,
+    fill: core::BindingGroup,
+    /// The placeholder gradient, bound until one is set.
+    gradient: (core::Texture, core::Sampler),
+}
+
+impl Pipeline {
+    /// Set how anti-aliased coverage is blended, from the given frame on.
+    /// Dual-source blending isn't available, so this is done in the fragment
+    /// shader.
+    pub fn set_coverage(
+        &mut self,
+        coverage: Coverage,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+    ) {
+        self.shading.gamma = coverage.gamma();
+        renderer.update_uniform_buffer(&self.shading_buf, &[self.shading], frame);
+    }
+
+    /// Set the gradient sampled by shapes filled with [`Fill::Ramp`], from
+    /// the given frame on, eg. a texture created with
+    /// [`Gradient::upload`](crate::kit::gradient::Gradient::upload). The sampler
+    /// should clamp to the edge, and use the gradient's filter.
+    ///
+    /// ```ignore
+    /// let texture = gradient.upload(&mut renderer, 256);
+    /// let sampler = renderer.sampler_with_address_mode(
+    ///     gradient.filter(),
+    ///     gradient.filter(),
+    ///     AddressMode::ClampToEdge,
+    /// );
+    /// pipeline.set_gradient(&texture, &sampler, &mut renderer, &mut frame);
+    /// ```
+    pub fn set_gradient(
+        &mut self,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+    ) {
+        self.shading.gradient = texture.w as f32;
+        self.fill = renderer.binding_group(
+            &self.pipeline.layout.sets[2],
+            &[&self.shading_buf, texture, sampler],
+        );
+        renderer.update_uniform_buffer(&self.shading_buf, &[self.shading], frame);
+    }
+
+    /// Create a [`kit::ModelTransform`], to draw shapes with a model
+    /// transform.
+    pub fn model(&self, renderer: &core::Renderer, transform: Transform2D) -> kit::ModelTransform {
+        kit::ModelTransform::new(&self.pipeline.layout.sets[1], transform, &renderer.device)
+    }
 }
 
 //////////////////////////////////////////////////////////////////////////
@@ -69,7 +191,14 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
 
     fn description() -> core::PipelineDescription<'a> {
         core::PipelineDescription {
-            vertex_layout: &[core::VertexFormat::Float2, core::VertexFormat::UByte4],
+            vertex_layout: &[
+                core::VertexFormat::Float2,
+                core::VertexFormat::UByte4,
+                core::VertexFormat::Float,
+                core::VertexFormat::Float2,
+                core::VertexFormat::Float,
+                core::VertexFormat::Float2,
+            ],
             pipeline_layout: &[
                 Set(&[Binding {
                     binding: BindingType::UniformBuffer,
@@ -79,10 +208,23 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
                     binding: BindingType::UniformBuffer,
                     stage: ShaderStage::Vertex,
                 }]),
+                Set(&[
+                    Binding {
+                        binding: BindingType::UniformBuffer,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::SampledTexture,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::Sampler,
+                        stage: ShaderStage::Fragment,
+                    },
+                ]),
             ],
-            // TODO: Use `env("CARGO_MANIFEST_DIR")`
-            vertex_shader: include_bytes!("data/shape.vert.spv"),
-            fragment_shader: include_bytes!("data/shape.frag.spv"),
+            vertex_shader: kit_shader!("data/shape.vert"),
+            fragment_shader: kit_shader!("data/shape.frag"),
         }
     }
 
@@ -90,24 +232,56 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
         let ortho = kit::ortho(width, height);
         let transform = Matrix4::identity();
         let model = Model::new(&pipeline.layout.sets[1], &[Matrix4::identity()], dev);
-        let buf = dev.create_uniform_buffer(&[self::Uniforms { ortho, transform }]);
+        let viewport = Vector2::new(width as f32, height as f32);
+        let buf = dev.create_uniform_buffer(&[self::Uniforms {
+            ortho,
+            transform,
+            viewport,
+        }]);
         let bindings = dev.create_binding_group(&pipeline.layout.sets[0], &[&buf]);
+        let gradient = (
+            dev.create_texture(2, 1),
+            dev.create_sampler_with_address_mode(
+                core::Filter::Linear,
+                core::Filter::Linear,
+                core::AddressMode::ClampToEdge,
+            ),
+        );
+        let shading = Shading {
+            gamma: Coverage::default().gamma(),
+            gradient: gradient.0.w as f32,
+        };
+        let shading_buf = dev.create_uniform_buffer(&[shading]);
+        let fill = dev.create_binding_group(
+            &pipeline.layout.sets[2],
+            &[&shading_buf, &gradient.0, &gradient.1],
+        );
 
         Self {
             pipeline,
             buf,
             bindings,
             model,
+            shading,
+            shading_buf,
+            fill,
+            gradient,
             ortho,
             width,
             height,
+            scale: 1.0,
         }
     }
 
     fn resize(&mut self, w: u32, h: u32) {
         self.width = w;
         self.height = h;
-        self.ortho = kit::ortho(w, h);
+        self.ortho = kit::ortho_scaled(w, h, self.scale);
+    }
+
+    fn set_scale_factor(&mut self, factor: f64) {
+        self.scale = factor;
+        self.ortho = kit::ortho_scaled(self.width, self.height, factor);
     }
 
     fn width(&self) -> u32 {
@@ -122,18 +296,28 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
         pass.set_pipeline(&self.pipeline);
         pass.set_binding(&self.bindings, &[]);
         pass.set_binding(&self.model.binding, &[]);
+        pass.set_binding(&self.fill, &[]);
     }
 
     fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
         &'a self,
         transform: Matrix4<f32>,
-    ) -> Option<(&'a core::UniformBuffer, Vec<self::Uniforms>)> {
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
         Some((
             &self.buf,
-            vec![self::Uniforms {
+            core::SmallVec::from_buf([self::Uniforms {
                 transform,
                 ortho: self.ortho,
-            }],
+                viewport: Vector2::new(self.width as f32, self.height as f32),
+            }]),
         ))
     }
 }
@@ -146,16 +330,45 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
 pub struct Stroke {
     width: f32,
     color: Rgba,
+    screen: bool,
 }
 
 impl Stroke {
     pub const NONE: Self = Self {
         width: 0.,
         color: Rgba::TRANSPARENT,
+        screen: false,
     };
 
     pub fn new(width: f32, color: Rgba) -> Self {
-        Self { width, color }
+        Self {
+            width,
+            color,
+            screen: false,
+        }
+    }
+
+    /// A stroke with a width in screen pixels, which stays the same under
+    /// camera zoom, eg. for selection borders and guides. The stroke is
+    /// extruded from the shape's outline in the vertex shader.
+    pub fn screen(width: f32, color: Rgba) -> Self {
+        Self {
+            width,
+            color,
+            screen: true,
+        }
+    }
+
+    /// A vertex at `(x, y)`, offset by `(dx, dy)` stroke units.
+    fn vertex(&self, x: f32, y: f32, dx: f32, dy: f32, color: Rgba8) -> Vertex {
+        if self.screen {
+            Vertex {
+                extrude: Vector2::new(dx, dy),
+                ..vertex(x, y, color)
+            }
+        } else {
+            vertex(x + dx, y + dy, color)
+        }
     }
 }
 
@@ -163,7 +376,55 @@ impl Stroke {
 pub enum Fill {
     Empty(),
     Solid(Rgba),
+    /// A vertical gradient, from the first color at the lowest `y` of the
+    /// shape to the second at its highest.
     Gradient(Rgba, Rgba),
+    /// A linear gradient sampled from the gradient set with
+    /// [`Pipeline::set_gradient`], from offset `0` at the first point to
+    /// offset `1` at the second. Shapes beyond the points are filled with
+    /// the color at the nearest end. Until a gradient is set, the pipeline's
+    /// placeholder texture is left uninitialized.
+    Ramp(Point2<f32>, Point2<f32>),
+}
+
+impl Fill {
+    /// Shade a vertex of the fill of a shape with the given bounds. Fills
+    /// vary linearly across shapes, so shading vertices is exact.
+    fn shade(&self, v: Vertex, bounds: Rect<f32>) -> Vertex {
+        match *self {
+            Self::Empty() => v,
+            Self::Solid(color) => Vertex {
+                color: color.into(),
+                ..v
+            },
+            Self::Gradient(from, to) => {
+                let h = bounds.y2 - bounds.y1;
+                let t = if h != 0. {
+                    (v.position.y - bounds.y1) / h
+                } else {
+                    0.
+                };
+                Vertex {
+                    color: from.lerp(to, t.clamp(0., 1.)).into(),
+                    ..v
+                }
+            }
+            Self::Ramp(from, to) => {
+                let d = to - from;
+                let len = d.length_squared();
+                let t = if len > 0. {
+                    (v.position - Vector2::new(from.x, from.y)).dot(d) / len
+                } else {
+                    0.
+                };
+                Vertex {
+                    color: Rgba8::WHITE,
+                    gradient: Vector2::new(t, 1.),
+                    ..v
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -171,30 +432,36 @@ pub enum Shape {
     Line(Line, Stroke),
     Rectangle(Rect<f32>, Stroke, Fill),
     Circle(Point2<f32>, f32, u32, Stroke, Fill),
+    Callout(Callout, Stroke, Fill),
 }
 
 impl Shape {
     pub fn triangulate(&self) -> Vec<Vertex> {
         match *self {
-            Shape::Line(l, Stroke { width, color }) => {
+            Self::Line(l, stroke) => {
                 let v = (l.p2 - l.p1).normalize();
 
-                let wx = width / 2.0 * v.y;
-                let wy = width / 2.0 * v.x;
-                let rgba8 = color.into();
+                let wx = stroke.width / 2.0 * v.y;
+                let wy = stroke.width / 2.0 * v.x;
+                let rgba8 = stroke.color.into();
 
                 vec![
-                    vertex(l.p1.x - wx, l.p1.y + wy, rgba8),
-                    vertex(l.p1.x + wx, l.p1.y - wy, rgba8),
-                    vertex(l.p2.x - wx, l.p2.y + wy, rgba8),
-                    vertex(l.p2.x - wx, l.p2.y + wy, rgba8),
-                    vertex(l.p1.x + wx, l.p1.y - wy, rgba8),
-                    vertex(l.p2.x + wx, l.p2.y - wy, rgba8),
+                    stroke.vertex(l.p1.x, l.p1.y, -wx, wy, rgba8),
+                    stroke.vertex(l.p1.x, l.p1.y, wx, -wy, rgba8),
+                    stroke.vertex(l.p2.x, l.p2.y, -wx, wy, rgba8),
+                    stroke.vertex(l.p2.x, l.p2.y, -wx, wy, rgba8),
+                    stroke.vertex(l.p1.x, l.p1.y, wx, -wy, rgba8),
+                    stroke.vertex(l.p2.x, l.p2.y, wx, -wy, rgba8),
                 ]
             }
-            Shape::Rectangle(r, stroke, fill) => {
-                let width = stroke.width;
-                let inner = Rect::new(r.x1 + width, r.y1 + width, r.x2 - width, r.y2 - width);
+            Self::Rectangle(r, stroke, fill) => {
+                let w = stroke.width;
+
+                // Corners of the inner rectangle, inset by the stroke width.
+                let i11 = |c| stroke.vertex(r.x1, r.y1, w, w, c);
+                let i21 = |c| stroke.vertex(r.x2, r.y1, -w, w, c);
+                let i12 = |c| stroke.vertex(r.x1, r.y2, w, -w, c);
+                let i22 = |c| stroke.vertex(r.x2, r.y2, -w, -w, c);
 
                 let mut verts = if stroke != Stroke::NONE {
                     let rgba8 = stroke.color.into();
@@ -205,77 +472,82 @@ impl Shape {
                         // Bottom
                         vertex(outer.x1, outer.y1, rgba8),
                         vertex(outer.x2, outer.y1, rgba8),
-                        vertex(inner.x1, inner.y1, rgba8),
-                        vertex(inner.x1, inner.y1, rgba8),
+                        i11(rgba8),
+                        i11(rgba8),
                         vertex(outer.x2, outer.y1, rgba8),
-                        vertex(inner.x2, inner.y1, rgba8),
+                        i21(rgba8),
                         // Left
                         vertex(outer.x1, outer.y1, rgba8),
-                        vertex(inner.x1, inner.y1, rgba8),
+                        i11(rgba8),
                         vertex(outer.x1, outer.y2, rgba8),
                         vertex(outer.x1, outer.y2, rgba8),
-                        vertex(inner.x1, inner.y1, rgba8),
-                        vertex(inner.x1, inner.y2, rgba8),
+                        i11(rgba8),
+                        i12(rgba8),
                         // Right
-                        vertex(inner.x2, inner.y1, rgba8),
+                        i21(rgba8),
                         vertex(outer.x2, outer.y1, rgba8),
                         vertex(outer.x2, outer.y2, rgba8),
-                        vertex(inner.x2, inner.y1, rgba8),
-                        vertex(inner.x2, inner.y2, rgba8),
+                        i21(rgba8),
+                        i22(rgba8),
                         vertex(outer.x2, outer.y2, rgba8),
                         // Top
                         vertex(outer.x1, outer.y2, rgba8),
                         vertex(outer.x2, outer.y2, rgba8),
-                        vertex(inner.x1, inner.y2, rgba8),
-                        vertex(inner.x1, inner.y2, rgba8),
+                        i12(rgba8),
+                        i12(rgba8),
                         vertex(outer.x2, outer.y2, rgba8),
-                        vertex(inner.x2, inner.y2, rgba8),
+                        i22(rgba8),
                     ]
                 } else {
                     Vec::with_capacity(6)
                 };
 
                 match fill {
-                    Fill::Solid(color) => {
-                        let rgba8 = color.into();
+                    Fill::Empty() => {}
+                    _ => {
+                        let rgba8 = Rgba8::WHITE;
 
-                        verts.extend_from_slice(&[
-                            vertex(inner.x1, inner.y1, rgba8),
-                            vertex(inner.x2, inner.y1, rgba8),
-                            vertex(inner.x2, inner.y2, rgba8),
-                            vertex(inner.x1, inner.y1, rgba8),
-                            vertex(inner.x1, inner.y2, rgba8),
-                            vertex(inner.x2, inner.y2, rgba8),
-                        ]);
-                    }
-                    Fill::Gradient(_, _) => {
-                        unimplemented!();
+                        verts.extend(
+                            [
+                                i11(rgba8),
+                                i21(rgba8),
+                                i22(rgba8),
+                                i11(rgba8),
+                                i12(rgba8),
+                                i22(rgba8),
+                            ]
+                            .iter()
+                            .map(|v| fill.shade(*v, r)),
+                        );
                     }
-                    Fill::Empty() => {}
                 }
                 verts
             }
-            Shape::Circle(position, radius, sides, stroke, fill) => {
-                let inner = Self::circle(position, radius - stroke.width, sides);
+            Self::Circle(position, radius, sides, stroke, fill) => {
+                let unit = Self::circle(Point2::new(0., 0.), 1., sides);
+                let outer = Self::circle(position, radius, sides);
+
+                // The inner circle, inset by the stroke width.
+                let inner = |i: usize, c| {
+                    let (o, u) = (outer[i], unit[i]);
+                    stroke.vertex(o.x, o.y, -u.x * stroke.width, -u.y * stroke.width, c)
+                };
 
                 let mut verts = if stroke != Stroke::NONE {
-                    // If there is a stroke, the outer circle is larger.
-                    let outer = Self::circle(position, radius, sides);
                     let rgba8 = stroke.color.into();
 
-                    let n = inner.len() - 1;
+                    let n = outer.len() - 1;
                     let mut vs = Vec::with_capacity(n * 6);
                     for i in 0..n {
-                        let (i0, i1) = (inner[i], inner[i + 1]);
                         let (o0, o1) = (outer[i], outer[i + 1]);
 
                         vs.extend_from_slice(&[
-                            vertex(i0.x, i0.y, rgba8),
+                            inner(i, rgba8),
                             vertex(o0.x, o0.y, rgba8),
                             vertex(o1.x, o1.y, rgba8),
-                            vertex(i0.x, i0.y, rgba8),
+                            inner(i, rgba8),
                             vertex(o1.x, o1.y, rgba8),
-                            vertex(i1.x, i1.y, rgba8),
+                            inner(i + 1, rgba8),
                         ]);
                     }
                     vs
@@ -284,11 +556,19 @@ impl Shape {
                 };
 
                 match fill {
-                    Fill::Solid(color) => {
-                        let rgba8 = color.into();
-                        let center = Vertex::new(position.x, position.y, rgba8);
-                        let inner_verts: Vec<Vertex> =
-                            inner.iter().map(|p| Vertex::new(p.x, p.y, rgba8)).collect();
+                    Fill::Empty() => {}
+                    _ => {
+                        let bounds = Rect::new(
+                            position.x - radius,
+                            position.y - radius,
+                            position.x + radius,
+                            position.y + radius,
+                        );
+                        let rgba8 = Rgba8::WHITE;
+                        let center = fill.shade(Vertex::new(position.x, position.y, rgba8), bounds);
+                        let inner_verts: Vec<Vertex> = (0..outer.len())
+                            .map(|i| fill.shade(inner(i, rgba8), bounds))
+                            .collect();
                         for i in 0..sides as usize {
                             verts.extend_from_slice(&[center, inner_verts[i], inner_verts[i + 1]]);
                         }
@@ -298,13 +578,76 @@ impl Shape {
                             *inner_verts.first().unwrap(),
                         ]);
                     }
-                    Fill::Gradient(_, _) => {
-                        unimplemented!();
-                    }
-                    Fill::Empty() => {}
                 }
                 verts
             }
+            Self::Callout(callout, stroke, fill) => callout.triangulate(stroke, fill),
+        }
+    }
+
+    /// A key uniquely identifying the shape's geometry and colors.
+    fn key(&self) -> Vec<u32> {
+        let rgba = |c: Rgba| vec![c.r.to_bits(), c.g.to_bits(), c.b.to_bits(), c.a.to_bits()];
+        let stroke = |s: Stroke| [vec![s.width.to_bits(), s.screen as u32], rgba(s.color)].concat();
+        let fill = |f: Fill| match f {
+            Fill::Empty() => vec![0],
+            Fill::Solid(c) => [vec![1], rgba(c)].concat(),
+            Fill::Gradient(a, b) => [vec![2], rgba(a), rgba(b)].concat(),
+            Fill::Ramp(a, b) => vec![
+                3,
+                a.x.to_bits(),
+                a.y.to_bits(),
+                b.x.to_bits(),
+                b.y.to_bits(),
+            ],
+        };
+
+        match *self {
+            Self::Line(l, s) => [
+                vec![0, l.p1.x.to_bits(), l.p1.y.to_bits()],
+                vec![l.p2.x.to_bits(), l.p2.y.to_bits()],
+                stroke(s),
+            ]
+            .concat(),
+            Self::Rectangle(r, s, f) => [
+                vec![
+                    1,
+                    r.x1.to_bits(),
+                    r.y1.to_bits(),
+                    r.x2.to_bits(),
+                    r.y2.to_bits(),
+                ],
+                stroke(s),
+                fill(f),
+            ]
+            .concat(),
+            Self::Circle(p, r, n, s, f) => [
+                vec![2, p.x.to_bits(), p.y.to_bits(), r.to_bits(), n],
+                stroke(s),
+                fill(f),
+            ]
+            .concat(),
+            Self::Callout(c, s, f) => {
+                let r = c.rect;
+                let tail = match c.tail {
+                    Some(t) => vec![1, t.tip.x.to_bits(), t.tip.y.to_bits(), t.width.to_bits()],
+                    None => vec![0],
+                };
+                [
+                    vec![
+                        3,
+                        r.x1.to_bits(),
+                        r.y1.to_bits(),
+                        r.x2.to_bits(),
+                        r.y2.to_bits(),
+                    ],
+                    vec![c.radius.to_bits(), c.segments],
+                    tail,
+                    stroke(s),
+                    fill(f),
+                ]
+                .concat()
+            }
         }
     }
 
@@ -337,18 +680,566 @@ impl Line {
     }
 }
 
+/// Draw a segment from [`crate::math::Line`], eg. one used for collisions.
+impl From<crate::math::Line<f32>> for Line {
+    fn from(l: crate::math::Line<f32>) -> Self {
+        Self::new(l.p1.x, l.p1.y, l.p2.x, l.p2.y)
+    }
+}
+
+impl From<Line> for crate::math::Line<f32> {
+    fn from(l: Line) -> Self {
+        Self::new(l.p1.x, l.p1.y, l.p2.x, l.p2.y)
+    }
+}
+
+/// The tail of a [`Callout`], pointing at `tip`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tail {
+    pub tip: Point2<f32>,
+    /// Width of the tail where it meets the body.
+    pub width: f32,
+}
+
+/// A rounded rectangle with an optional tail, eg. for tooltips and speech
+/// bubbles. The corners keep their radius as the body is resized, and the
+/// tail leaves from the side facing its tip.
+///
+/// ```
+/// use rgx::core::Rect;
+/// use rgx::kit::shape2d::Callout;
+/// use rgx::math::{Point2, Vector2};
+///
+/// let callout = Callout::new(Rect::new(0., 0., 40., 20.), 0.)
+///     .with_tail(Point2::new(20., 30.), 8.);
+///
+/// assert_eq!(
+///     callout.outline(),
+///     vec![
+///         Vector2::new(0., 0.),
+///         Vector2::new(40., 0.),
+///         Vector2::new(40., 20.),
+///         Vector2::new(24., 20.),
+///         Vector2::new(20., 30.),
+///         Vector2::new(16., 20.),
+///         Vector2::new(0., 20.),
+///     ]
+/// );
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Callout {
+    pub rect: Rect<f32>,
+    /// Radius of the corners, clamped to half the smallest side.
+    pub radius: f32,
+    /// Number of segments per corner.
+    pub segments: u32,
+    pub tail: Option<Tail>,
+}
+
+impl Callout {
+    /// Joins sharper than this, eg. at the tip, are clamped when stroked.
+    const MITER_LIMIT: f32 = 4.;
+
+    pub fn new(rect: Rect<f32>, radius: f32) -> Self {
+        Self {
+            rect,
+            radius,
+            segments: 8,
+            tail: None,
+        }
+    }
+
+    /// Return the callout with a tail pointing at `tip`. Tips inside the body
+    /// are ignored.
+    pub fn with_tail(self, tip: Point2<f32>, width: f32) -> Self {
+        Self {
+            tail: Some(Tail { tip, width }),
+            ..self
+        }
+    }
+
+    /// The points of the callout's outline, clockwise on screen, starting at
+    /// the top-left corner. Can be passed to [`dashed`] for dashed borders.
+    pub fn outline(&self) -> Vec<Vector2<f32>> {
+        self.points().0
+    }
+
+    /// The outline, and the index of the tip in it, if any.
+    fn points(&self) -> (Vec<Vector2<f32>>, Option<usize>) {
+        let Rect { x1, y1, x2, y2 } = self.bounds();
+        let r = self.radius.clamp(0., f32::min(x2 - x1, y2 - y1) / 2.);
+
+        // Corner centers and start angles, followed by the edge leaving
+        // each corner, clockwise from the top-left.
+        let corners = [
+            (Vector2::new(x1 + r, y1 + r), f32::consts::PI),
+            (Vector2::new(x2 - r, y1 + r), f32::consts::PI * 1.5),
+            (Vector2::new(x2 - r, y2 - r), 0.),
+            (Vector2::new(x1 + r, y2 - r), f32::consts::PI * 0.5),
+        ];
+        let tail = self
+            .tail
+            .and_then(|t| Self::base(t, Rect { x1, y1, x2, y2 }, r));
+
+        let mut points: Vec<Vector2<f32>> = Vec::new();
+        let mut tip = None;
+        let push = |points: &mut Vec<Vector2<f32>>, p: Vector2<f32>| {
+            if points
+                .last()
+                .is_none_or(|l| (p - *l).magnitude() > f32::EPSILON)
+            {
+                points.push(p);
+            }
+        };
+
+        for (side, (center, start)) in corners.iter().enumerate() {
+            let steps = if r > 0. { self.segments.max(1) } else { 0 };
+
+            for i in 0..=steps {
+                let angle = start + i as f32 / steps.max(1) as f32 * f32::consts::FRAC_PI_2;
+                push(
+                    &mut points,
+                    Vector2::new(center.x + r * angle.cos(), center.y + r * angle.sin()),
+                );
+            }
+            if let Some((s, [a, t, b])) = tail {
+                if s == side {
+                    push(&mut points, a);
+                    tip = Some(points.len());
+                    points.push(t);
+                    push(&mut points, b);
+                }
+            }
+        }
+        if points.len() > 1 && (points[0] - points[points.len() - 1]).magnitude() <= f32::EPSILON {
+            points.pop();
+        }
+        (points, tip)
+    }
+
+    /// The body, with its corners ordered.
+    fn bounds(&self) -> Rect<f32> {
+        Rect::new(
+            f32::min(self.rect.x1, self.rect.x2),
+            f32::min(self.rect.y1, self.rect.y2),
+            f32::max(self.rect.x1, self.rect.x2),
+            f32::max(self.rect.y1, self.rect.y2),
+        )
+    }
+
+    /// The side the tail leaves from, counting clockwise from the top, and
+    /// the base and tip of the tail in outline order.
+    fn base(tail: Tail, bounds: Rect<f32>, r: f32) -> Option<(usize, [Vector2<f32>; 3])> {
+        let Rect { x1, y1, x2, y2 } = bounds;
+        let tip = Vector2::new(tail.tip.x, tail.tip.y);
+        let (hw, hh) = ((x2 - x1) / 2., (y2 - y1) / 2.);
+        let (dx, dy) = (tip.x - (x1 + hw), tip.y - (y1 + hh));
+
+        if dx.abs() <= hw && dy.abs() <= hh {
+            return None;
+        }
+        let horizontal = dx.abs() * hh > dy.abs() * hw;
+
+        // The straight part of the edge, between the corners.
+        let (lo, hi, along) = if horizontal {
+            (y1 + r, y2 - r, tip.y)
+        } else {
+            (x1 + r, x2 - r, tip.x)
+        };
+        let half = f32::min(tail.width, hi - lo) / 2.;
+        if half <= 0. {
+            return None;
+        }
+        let c = along.clamp(lo + half, hi - half);
+
+        Some(match (horizontal, dx > 0., dy > 0.) {
+            (false, _, false) => (
+                0,
+                [Vector2::new(c - half, y1), tip, Vector2::new(c + half, y1)],
+            ),
+            (true, true, _) => (
+                1,
+                [Vector2::new(x2, c - half), tip, Vector2::new(x2, c + half)],
+            ),
+            (false, _, true) => (
+                2,
+                [Vector2::new(c + half, y2), tip, Vector2::new(c - half, y2)],
+            ),
+            (true, false, _) => (
+                3,
+                [Vector2::new(x1, c + half), tip, Vector2::new(x1, c - half)],
+            ),
+        })
+    }
+
+    fn triangulate(&self, stroke: Stroke, fill: Fill) -> Vec<Vertex> {
+        let (outer, tip) = self.points();
+        let n = outer.len();
+
+        if n < 3 {
+            return Vec::new();
+        }
+
+        // Miter offsets from the outline to the inner outline, inset by the
+        // stroke width. The outline is clockwise on screen, so the inward
+        // normal of an edge is its direction rotated clockwise.
+        let normal = |a: Vector2<f32>, b: Vector2<f32>| {
+            let d = (b - a).normalize();
+            Vector2::new(-d.y, d.x)
+        };
+        let offsets: Vec<Vector2<f32>> = (0..n)
+            .map(|i| {
+                let (prev, p, next) = (outer[(i + n - 1) % n], outer[i], outer[(i + 1) % n]);
+                let (n0, n1) = (normal(prev, p), normal(p, next));
+                let m = (n0 + n1).normalize();
+                let k = (1. / Vector2::dot(m, n0)).clamp(1., Self::MITER_LIMIT);
+
+                m * (k * stroke.width)
+            })
+            .collect();
+        let inner = |i: usize, c| {
+            let (o, d) = (outer[i], offsets[i]);
+            stroke.vertex(o.x, o.y, d.x, d.y, c)
+        };
+
+        let mut verts = if stroke != Stroke::NONE {
+            let rgba8 = stroke.color.into();
+            let mut vs = Vec::with_capacity(n * 6);
+
+            for i in 0..n {
+                let j = (i + 1) % n;
+                let (o0, o1) = (outer[i], outer[j]);
+
+                vs.extend_from_slice(&[
+                    inner(i, rgba8),
+                    vertex(o0.x, o0.y, rgba8),
+                    vertex(o1.x, o1.y, rgba8),
+                    inner(i, rgba8),
+                    vertex(o1.x, o1.y, rgba8),
+                    inner(j, rgba8),
+                ]);
+            }
+            vs
+        } else {
+            Vec::new()
+        };
+
+        match fill {
+            Fill::Empty() => {}
+            _ => {
+                let rgba8 = Rgba8::WHITE;
+                let b = self.bounds();
+                let center = Vertex::new((b.x1 + b.x2) / 2., (b.y1 + b.y2) / 2., rgba8);
+                let inner = |i: usize| fill.shade(inner(i, rgba8), b);
+                let center = fill.shade(center, b);
+
+                // The body is convex without the tip, so it is filled as a
+                // fan around its center, and the tail as a single triangle.
+                let body: Vec<usize> = (0..n).filter(|i| Some(*i) != tip).collect();
+                for (k, i) in body.iter().enumerate() {
+                    let j = body[(k + 1) % body.len()];
+                    verts.extend_from_slice(&[center, inner(*i), inner(j)]);
+                }
+                if let Some(t) = tip {
+                    verts.extend_from_slice(&[inner(t - 1), inner(t), inner((t + 1) % n)]);
+                }
+            }
+        }
+        verts
+    }
+}
+
+/// A dash pattern: dashes of length `on`, separated by gaps of length `off`,
+/// with the pattern starting `phase` units in.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Dash {
+    pub on: f32,
+    pub off: f32,
+    pub phase: f32,
+}
+
+impl Dash {
+    pub fn new(on: f32, off: f32) -> Self {
+        assert!(
+            on > 0. && off >= 0.,
+            "fatal: invalid dash pattern {} {}",
+            on,
+            off
+        );
+        Self { on, off, phase: 0. }
+    }
+
+    /// Return the pattern offset by `phase`, eg. for marching ants.
+    pub fn offset(self, phase: f32) -> Self {
+        Self { phase, ..self }
+    }
+}
+
+/// Split the polyline going through `points` into dashed lines. If `closed`
+/// is set, the last point is joined to the first.
+///
+/// ```
+/// use rgx::kit::shape2d::{self, Dash, Stroke};
+/// use rgx::kit::Rgba;
+/// use rgx::math::Vector2;
+///
+/// let stroke = Stroke::new(1., Rgba::WHITE);
+///
+/// let line = [Vector2::new(0., 0.), Vector2::new(10., 0.)];
+/// assert_eq!(shape2d::dashed(&line, false, Dash::new(2., 2.), stroke).len(), 3);
+///
+/// let square = [
+///     Vector2::new(0., 0.),
+///     Vector2::new(10., 0.),
+///     Vector2::new(10., 10.),
+///     Vector2::new(0., 10.),
+/// ];
+/// assert_eq!(shape2d::dashed(&square, true, Dash::new(5., 5.), stroke).len(), 4);
+/// ```
+pub fn dashed(points: &[Vector2<f32>], closed: bool, dash: Dash, stroke: Stroke) -> Vec<Shape> {
+    assert!(
+        dash.on > 0. && dash.off >= 0.,
+        "fatal: invalid dash pattern {} {}",
+        dash.on,
+        dash.off
+    );
+    let mut shapes = Vec::new();
+    let period = dash.on + dash.off;
+    let mut distance = dash.phase.rem_euclid(period);
+
+    let segments = points.windows(2).map(|w| (w[0], w[1]));
+    let closing = match (points.first(), points.last()) {
+        (Some(first), Some(last)) if closed && points.len() > 2 => Some((*last, *first)),
+        _ => None,
+    };
+
+    for (a, b) in segments.chain(closing) {
+        let len = (b - a).magnitude();
+        if len <= 0. {
+            continue;
+        }
+        let dir = (b - a) * (1. / len);
+
+        // Dash positions are computed from their index rather than by
+        // accumulating lengths, so that steps too small to change the
+        // position can't stall the loop.
+        let count = ((distance + len) / period).ceil() as usize;
+
+        for k in 0..count {
+            let start = k as f32 * period - distance;
+            let (t1, t2) = (start.max(0.), (start + dash.on).min(len));
+
+            if t1 < t2 {
+                let (p1, p2) = (a + dir * t1, a + dir * t2);
+                shapes.push(Shape::Line(Line::new(p1.x, p1.y, p2.x, p2.y), stroke));
+            }
+        }
+        distance = (distance + len) % period;
+    }
+    shapes
+}
+
+/// Overdraw counted from a heat map, see [`Batch::overdraw`].
+///
+/// ```
+/// use rgx::core::TextureFormat;
+/// use rgx::kit::shape2d::Overdraw;
+///
+/// // Two texels drawn to once, one drawn to three times, and one untouched.
+/// let texels = [1, 1, 1, 1, 1, 1, 1, 1, 3, 3, 3, 3, 0, 0, 0, 0];
+/// let overdraw = Overdraw::count(&texels, TextureFormat::Bgra8Unorm);
+///
+/// assert_eq!(overdraw, Overdraw { covered: 3, fragments: 5, max: 3 });
+/// assert_eq!(overdraw.average(), 5. / 3.);
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Overdraw {
+    /// Number of texels drawn to at least once.
+    pub covered: u64,
+    /// Number of times texels were drawn to, over all texels.
+    pub fragments: u64,
+    /// Number of times the most drawn texel was drawn to.
+    pub max: u8,
+}
+
+impl Overdraw {
+    /// The heat map color counted by [`Overdraw::count`]: drawn additively,
+    /// each layer adds one to every channel, up to 255 layers.
+    pub const LAYER: Rgba = Rgba {
+        r: 1. / 255.,
+        g: 1. / 255.,
+        b: 1. / 255.,
+        a: 1. / 255.,
+    };
+
+    /// Count the overdraw of a heat map drawn with [`Overdraw::LAYER`], from
+    /// its texels, eg. as read back with [`core::Renderer::read`].
+    ///
+    /// Only linear 8-bit formats are supported, ie. `Bgra8Unorm` and
+    /// `Rgba8Unorm`, where each layer adds exactly one to the texel. Heat
+    /// maps should be drawn to a framebuffer of one of these formats.
+    pub fn count(texels: &[u8], format: core::TextureFormat) -> Self {
+        assert!(
+            format == core::TextureFormat::Bgra8Unorm || format == core::TextureFormat::Rgba8Unorm,
+            "fatal: can't count overdraw of {:?} texels",
+            format
+        );
+        let mut overdraw = Self::default();
+
+        for texel in texels.chunks_exact(4) {
+            // All channels hold the same count, so the channel order doesn't
+            // matter.
+            let layers = texel[0];
+
+            if layers > 0 {
+                overdraw.covered += 1;
+                overdraw.fragments += u64::from(layers);
+                overdraw.max = overdraw.max.max(layers);
+            }
+        }
+        overdraw
+    }
+
+    /// The average number of times covered texels were drawn to.
+    pub fn average(&self) -> f32 {
+        if self.covered == 0 {
+            return 0.;
+        }
+        self.fragments as f32 / self.covered as f32
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// Cache
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A handle to cached, tessellated shape geometry. Cloning it is cheap.
+#[derive(Clone, Debug)]
+pub struct ShapeHandle {
+    vertices: Rc<[Vertex]>,
+}
+
+impl ShapeHandle {
+    pub fn vertices(&self) -> &[Vertex] {
+        &self.vertices
+    }
+}
+
+/// A cache of tessellated shapes, keyed by shape description, so that
+/// static shapes added every frame are only tessellated once.
+///
+/// ```
+/// use rgx::core::Rgba;
+/// use rgx::kit::shape2d::{Batch, Cache, Fill, Shape, Stroke};
+/// use rgx::math::Point2;
+///
+/// let mut cache = Cache::new();
+/// let circle = Shape::Circle(
+///     Point2::new(8., 8.),
+///     4.,
+///     16,
+///     Stroke::NONE,
+///     Fill::Solid(Rgba::WHITE),
+/// );
+///
+/// let a = cache.get(&circle);
+/// let b = cache.get(&circle);
+/// assert_eq!(cache.len(), 1);
+///
+/// let mut batch = Batch::new();
+/// batch.add_cached(&a);
+/// batch.add_cached(&b);
+/// assert_eq!(batch.vertices().len(), 2 * circle.triangulate().len());
+/// ```
+#[derive(Debug, Default)]
+pub struct Cache {
+    entries: HashMap<Vec<u32>, ShapeHandle>,
+}
+
+impl Cache {
+    pub fn new() -> Self {
+        Self {
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Get a handle to the tessellated shape, tessellating it if it isn't
+    /// already cached.
+    pub fn get(&mut self, shape: &Shape) -> ShapeHandle {
+        self.entries
+            .entry(shape.key())
+            .or_insert_with(|| ShapeHandle {
+                vertices: shape.triangulate().into(),
+            })
+            .clone()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all cached shapes. Outstanding handles remain valid.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 /// Batch
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Clone, Debug)]
+enum Item {
+    Shape(Shape),
+    Cached(ShapeHandle),
+}
+
 #[derive(Debug)]
 pub struct Batch {
-    items: Vec<Shape>,
+    /// Whether vertex positions are rounded to device pixels, so that
+    /// shapes stay sharp when transformed to fractional coordinates.
+    pub snap: bool,
+
+    items: Vec<(Item, Option<Rect<f32>>)>,
+    clip: Option<Rect<f32>>,
 }
 
 impl Batch {
     pub fn new() -> Self {
-        Self { items: Vec::new() }
+        Self {
+            items: Vec::new(),
+            snap: false,
+            clip: None,
+        }
+    }
+
+    /// Clip the shapes added from now on to the given rectangle, or stop
+    /// clipping them with `None`. Clipped shapes are cut on the CPU, so that
+    /// they stay in a single batch.
+    ///
+    /// Strokes drawn with [`Stroke::screen`] are extruded by the vertex
+    /// shader, once the view scale is known, so they are clipped along their
+    /// center line: they can extend past the clip rectangle by up to half
+    /// their width.
+    ///
+    /// ```
+    /// use rgx::core::{Rect, Rgba};
+    /// use rgx::kit::shape2d::{Batch, Fill, Shape, Stroke};
+    ///
+    /// let mut batch = Batch::new();
+    /// let fill = Fill::Solid(Rgba::WHITE);
+    ///
+    /// batch.set_clip(Some(Rect::new(0., 0., 8., 8.)));
+    /// batch.add(Shape::Rectangle(Rect::new(16., 16., 32., 32.), Stroke::NONE, fill));
+    ///
+    /// assert!(batch.vertices().is_empty());
+    /// ```
+    pub fn set_clip(&mut self, clip: Option<Rect<f32>>) {
+        self.clip = clip;
     }
 
     pub fn singleton(shape: Shape) -> Self {
@@ -358,7 +1249,27 @@ impl Batch {
     }
 
     pub fn add(&mut self, shape: Shape) {
-        self.items.push(shape);
+        self.items.push((Item::Shape(shape), self.clip));
+    }
+
+    /// Add a shape tessellated by a [`Cache`].
+    pub fn add_cached(&mut self, handle: &ShapeHandle) {
+        self.items.push((Item::Cached(handle.clone()), self.clip));
+    }
+
+    /// Add custom geometry, eg. generated every frame, as a list of
+    /// triangles with three vertices each.
+    pub fn add_triangles(&mut self, vertices: Vec<Vertex>) {
+        assert!(
+            vertices.len().is_multiple_of(3),
+            "fatal: vertex count must be a multiple of three"
+        );
+        self.items.push((
+            Item::Cached(ShapeHandle {
+                vertices: vertices.into(),
+            }),
+            self.clip,
+        ));
     }
 
     pub fn vertices(&self) -> Vec<Vertex> {
@@ -366,9 +1277,30 @@ impl Batch {
         // We should get the actual numbers from the shapes.
         let mut buf = Vec::with_capacity(6 * self.items.len());
 
-        for shape in self.items.iter() {
-            let mut verts: Vec<Vertex> = shape.triangulate();
-            buf.append(&mut verts);
+        for (item, clip) in self.items.iter() {
+            match (item, clip) {
+                (Item::Shape(shape), None) => buf.append(&mut shape.triangulate()),
+                (Item::Cached(handle), None) => buf.extend_from_slice(handle.vertices()),
+                (Item::Shape(shape), Some(clip)) => kit::clip_triangles(
+                    &shape.triangulate(),
+                    *clip,
+                    |v| v.position,
+                    Vertex::lerp,
+                    &mut buf,
+                ),
+                (Item::Cached(handle), Some(clip)) => kit::clip_triangles(
+                    handle.vertices(),
+                    *clip,
+                    |v| v.position,
+                    Vertex::lerp,
+                    &mut buf,
+                ),
+            }
+        }
+        if self.snap {
+            for v in buf.iter_mut() {
+                v.snap = 1.0;
+            }
         }
         buf
     }
@@ -377,6 +1309,54 @@ impl Batch {
         self.items.is_empty()
     }
 
+    /// Return a copy of the batch with every stroke and fill set to the given
+    /// color. When drawn with [`core::Blending::additive`] into a framebuffer
+    /// cleared to black, this yields an overdraw heat map. Drawn with
+    /// [`Overdraw::LAYER`], the heat map can be read back and counted with
+    /// [`Overdraw::count`].
+    pub fn overdraw(&self, color: Rgba) -> Self {
+        let heat = |s: Stroke| {
+            if s == Stroke::NONE {
+                s
+            } else {
+                Stroke { color, ..s }
+            }
+        };
+        let fill = |f: Fill| match f {
+            Fill::Empty() => Fill::Empty(),
+            _ => Fill::Solid(color),
+        };
+        let rgba8 = color.into();
+        let items = self
+            .items
+            .iter()
+            .map(|(item, clip)| {
+                let item = match item {
+                    Item::Shape(shape) => Item::Shape(match *shape {
+                        Shape::Line(l, s) => Shape::Line(l, heat(s)),
+                        Shape::Rectangle(r, s, f) => Shape::Rectangle(r, heat(s), fill(f)),
+                        Shape::Circle(p, r, n, s, f) => Shape::Circle(p, r, n, heat(s), fill(f)),
+                        Shape::Callout(c, s, f) => Shape::Callout(c, heat(s), fill(f)),
+                    }),
+                    Item::Cached(handle) => Item::Cached(ShapeHandle {
+                        vertices: handle
+                            .vertices()
+                            .iter()
+                            .map(|v| Vertex { color: rgba8, ..*v })
+                            .collect(),
+                    }),
+                };
+                (item, *clip)
+            })
+            .collect();
+
+        Self {
+            items,
+            snap: self.snap,
+            clip: self.clip,
+        }
+    }
+
     pub fn clear(&mut self) {
         self.items.clear();
     }
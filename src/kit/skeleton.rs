@@ -0,0 +1,236 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core::{Rect, Rgba};
+
+use crate::kit::sprite2d::{self, Tint};
+
+use crate::math::*;
+
+/// A 2D affine transform, mapping bone space to the space of its
+/// skeleton.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Pose {
+    /// Position of the bone origin.
+    pub origin: Point2<f32>,
+    /// Direction and length of the bone's unit x axis.
+    pub x_axis: Vector2<f32>,
+    /// Direction and length of the bone's unit y axis.
+    pub y_axis: Vector2<f32>,
+}
+
+impl Pose {
+    pub const IDENTITY: Self = Self {
+        origin: Point2 { x: 0., y: 0. },
+        x_axis: Vector2 { x: 1., y: 0. },
+        y_axis: Vector2 { x: 0., y: 1. },
+    };
+
+    /// Transform a point from bone space.
+    pub fn apply(&self, p: Point2<f32>) -> Point2<f32> {
+        Point2::new(
+            self.origin.x + self.x_axis.x * p.x + self.y_axis.x * p.y,
+            self.origin.y + self.x_axis.y * p.x + self.y_axis.y * p.y,
+        )
+    }
+
+    /// Compose with a child transform, returning the child's pose.
+    fn then(&self, child: &Self) -> Self {
+        let vector = |v: Vector2<f32>| {
+            Vector2::new(
+                self.x_axis.x * v.x + self.y_axis.x * v.y,
+                self.x_axis.y * v.x + self.y_axis.y * v.y,
+            )
+        };
+        Self {
+            origin: self.apply(child.origin),
+            x_axis: vector(child.x_axis),
+            y_axis: vector(child.y_axis),
+        }
+    }
+}
+
+/// A bone, positioned relative to its parent.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bone {
+    pub name: String,
+    /// Index of the parent bone, which must come before this one.
+    pub parent: Option<usize>,
+    /// Position in the parent's space.
+    pub position: Vector2<f32>,
+    /// Rotation in radians, relative to the parent.
+    pub rotation: f32,
+    /// Scale along the bone's axes.
+    pub scale: Vector2<f32>,
+}
+
+impl Bone {
+    /// The transform from this bone's space to its parent's.
+    pub fn local(&self) -> Pose {
+        let (sin, cos) = self.rotation.sin_cos();
+
+        Pose {
+            origin: Point2::new(self.position.x, self.position.y),
+            x_axis: Vector2::new(cos * self.scale.x, sin * self.scale.x),
+            y_axis: Vector2::new(-sin * self.scale.y, cos * self.scale.y),
+        }
+    }
+}
+
+/// A textured quad attached to a bone.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Slot {
+    /// Index of the bone the slot is attached to.
+    pub bone: usize,
+    /// Region of the skeleton texture.
+    pub src: Rect<f32>,
+    /// Where the region is drawn, in bone space.
+    pub dst: Rect<f32>,
+    /// Color blended over the texture by its alpha, as in sprite batches.
+    pub color: Rgba,
+    pub opacity: f32,
+    /// Whether the slot is drawn.
+    pub visible: bool,
+}
+
+/// A hierarchy of bones with textured slots attached to them, eg. for an
+/// animated character drawn from a single texture.
+///
+/// Bones are animated by changing their position, rotation or scale
+/// relative to their parent. Slots are drawn in the order they were added.
+///
+/// ```
+/// use std::f32::consts::PI;
+///
+/// use rgx::core::{Rect, Rgba};
+/// use rgx::kit::skeleton::Skeleton;
+/// use rgx::kit::sprite2d;
+/// use rgx::math::{Point2, Vector2};
+///
+/// let mut skeleton = Skeleton::new();
+/// let root = skeleton.add_bone("root", None, Vector2::new(100., 100.));
+/// let arm = skeleton.add_bone("arm", Some(root), Vector2::new(10., 0.));
+///
+/// skeleton.add_slot(arm, Rect::origin(16., 4.), Rect::new(0., -2., 16., 2.));
+/// skeleton.bone_mut(root).rotation = PI / 2.;
+///
+/// let poses = skeleton.poses();
+/// let hand = poses[arm].apply(Point2::new(16., 0.));
+/// assert!((hand.x - 100.).abs() < 1e-4 && (hand.y - 126.).abs() < 1e-4);
+///
+/// let mut batch = sprite2d::Batch::new(16, 16);
+/// skeleton.draw(&mut batch);
+/// assert_eq!(batch.vertices().len(), 6);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Skeleton {
+    bones: Vec<Bone>,
+    slots: Vec<Slot>,
+}
+
+impl Skeleton {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a bone at `position` in its parent's space, returning its index.
+    pub fn add_bone(&mut self, name: &str, parent: Option<usize>, position: Vector2<f32>) -> usize {
+        if let Some(parent) = parent {
+            assert!(
+                parent < self.bones.len(),
+                "fatal: parent bone {} does not exist",
+                parent
+            );
+        }
+        self.bones.push(Bone {
+            name: name.to_owned(),
+            parent,
+            position,
+            rotation: 0.,
+            scale: Vector2::new(1., 1.),
+        });
+        self.bones.len() - 1
+    }
+
+    /// Attach the `src` region of the skeleton texture to a bone, drawn at
+    /// `dst` in bone space. Returns the slot index.
+    pub fn add_slot(&mut self, bone: usize, src: Rect<f32>, dst: Rect<f32>) -> usize {
+        assert!(
+            bone < self.bones.len(),
+            "fatal: bone {} does not exist",
+            bone
+        );
+
+        self.slots.push(Slot {
+            bone,
+            src,
+            dst,
+            color: Rgba::TRANSPARENT,
+            opacity: 1.,
+            visible: true,
+        });
+        self.slots.len() - 1
+    }
+
+    /// The index of the bone with the given name.
+    pub fn find(&self, name: &str) -> Option<usize> {
+        self.bones.iter().position(|b| b.name == name)
+    }
+
+    pub fn bone(&self, index: usize) -> &Bone {
+        &self.bones[index]
+    }
+
+    pub fn bone_mut(&mut self, index: usize) -> &mut Bone {
+        &mut self.bones[index]
+    }
+
+    pub fn bones(&self) -> &[Bone] {
+        &self.bones
+    }
+
+    pub fn slot_mut(&mut self, index: usize) -> &mut Slot {
+        &mut self.slots[index]
+    }
+
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// The pose of every bone, in skeleton space.
+    pub fn poses(&self) -> Vec<Pose> {
+        let mut poses: Vec<Pose> = Vec::with_capacity(self.bones.len());
+
+        for bone in self.bones.iter() {
+            let local = bone.local();
+            let pose = match bone.parent {
+                Some(parent) => poses[parent].then(&local),
+                None => local,
+            };
+            poses.push(pose);
+        }
+        poses
+    }
+
+    /// Add the visible slots to a sprite batch for the skeleton texture.
+    pub fn draw(&self, batch: &mut sprite2d::Batch) {
+        let poses = self.poses();
+
+        for slot in self.slots.iter().filter(|s| s.visible) {
+            let pose = &poses[slot.bone];
+            let dst = slot.dst;
+
+            batch.add_quad(
+                slot.src,
+                [
+                    pose.apply(Point2::new(dst.x1, dst.y1)),
+                    pose.apply(Point2::new(dst.x2, dst.y1)),
+                    pose.apply(Point2::new(dst.x2, dst.y2)),
+                    pose.apply(Point2::new(dst.x1, dst.y2)),
+                ],
+                slot.color,
+                Tint::NONE,
+                slot.opacity,
+            );
+        }
+    }
+}
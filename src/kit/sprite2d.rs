@@ -7,10 +7,13 @@ use crate::core::{Binding, BindingType, Rect, Rgba, Set, ShaderStage};
 use crate::math::*;
 
 use crate::kit;
-use crate::kit::{Model, Repeat, Rgba8};
+use crate::kit::shape2d::{self, Fill, Shape, Stroke};
+use crate::kit::{AlignedBuffer, Model, Repeat, Rgba8};
 
 use crate::nonempty::NonEmpty;
 
+use std::time;
+
 ///////////////////////////////////////////////////////////////////////////
 // Uniforms
 ///////////////////////////////////////////////////////////////////////////
@@ -20,6 +23,243 @@ use crate::nonempty::NonEmpty;
 pub struct Uniforms {
     pub ortho: Matrix4<f32>,
     pub transform: Matrix4<f32>,
+    /// Size of the target in physical pixels, used for pixel snapping.
+    pub viewport: Vector2<f32>,
+}
+
+///////////////////////////////////////////////////////////////////////////
+// UV Transform
+///////////////////////////////////////////////////////////////////////////
+
+/// A scale and offset applied to the texture coordinates of a batch on the
+/// GPU, eg. for scrolling water or conveyor belts.
+///
+/// Create one with [`Pipeline::uv_transform`], and bind it with
+/// [`core::Pass::set_binding`] before drawing the batch. Changes are uploaded
+/// with [`UvTransform::update`].
+pub struct UvTransform {
+    pub scale: Vector2<f32>,
+    pub offset: Vector2<f32>,
+    pub binding: core::BindingGroup,
+
+    buf: core::UniformBuffer,
+}
+
+impl UvTransform {
+    /// Move the offset by `velocity`, in texture coordinates per second.
+    /// The offset wraps around, since textures repeat.
+    pub fn scroll(&mut self, velocity: Vector2<f32>, delta: time::Duration) {
+        let t = delta.as_secs_f32();
+
+        self.offset.x = (self.offset.x + velocity.x * t).fract();
+        self.offset.y = (self.offset.y + velocity.y * t).fract();
+    }
+
+    /// Upload the transform to the GPU.
+    pub fn update(&self, renderer: &mut core::Renderer, frame: &mut core::Frame) {
+        renderer.update_uniform_buffer(&self.buf, &[self.uniforms()], frame);
+    }
+
+    fn uniforms(&self) -> Vector4<f32> {
+        Vector4::new(self.scale.x, self.scale.y, self.offset.x, self.offset.y)
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Chroma Key
+///////////////////////////////////////////////////////////////////////////
+
+/// Maps a key color to transparent, and substitutes palette entries, eg. for
+/// legacy assets without an alpha channel. Colors within `tolerance` of a key
+/// or palette entry match it.
+///
+/// ```
+/// use rgx::core::Rgba;
+/// use rgx::kit::sprite2d::ChromaKey;
+///
+/// let key = ChromaKey::new(Some(Rgba::new(1., 0., 1., 1.)), 0.01)
+///     .substitute(Rgba::BLACK, Rgba::new(0.1, 0.1, 0.2, 1.));
+/// let unifs = key.uniforms();
+///
+/// assert_eq!(unifs.color.a, 1.);
+/// assert_eq!(unifs.from[0].a, 1.);
+/// assert_eq!(unifs.from[1].a, 0.);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChromaKey {
+    pub color: Option<Rgba>,
+    pub tolerance: f32,
+    pub palette: Vec<(Rgba, Rgba)>,
+}
+
+impl ChromaKey {
+    /// Maximum number of palette substitutions.
+    pub const PALETTE_SIZE: usize = 4;
+
+    pub fn new(color: Option<Rgba>, tolerance: f32) -> Self {
+        Self {
+            color,
+            tolerance,
+            palette: Vec::new(),
+        }
+    }
+
+    /// Substitute texels matching `from` with `to`.
+    pub fn substitute(mut self, from: Rgba, to: Rgba) -> Self {
+        assert!(
+            self.palette.len() < Self::PALETTE_SIZE,
+            "fatal: chroma key palette is limited to {} entries",
+            Self::PALETTE_SIZE
+        );
+        self.palette.push((from, to));
+        self
+    }
+
+    pub fn uniforms(&self) -> KeyUniforms {
+        // Entries are enabled by setting their alpha.
+        let entry = |c: Rgba| Rgba::new(c.r, c.g, c.b, 1.);
+        let mut unifs = KeyUniforms::default();
+
+        if let Some(color) = self.color {
+            unifs.color = entry(color);
+        }
+        for (i, (from, to)) in self.palette.iter().enumerate() {
+            unifs.from[i] = entry(*from);
+            unifs.to[i] = *to;
+        }
+        unifs.tolerance = self.tolerance;
+        unifs
+    }
+
+    /// Upload the key to the GPU, for use with [`Pipeline::keyed_binding`].
+    pub fn upload(&self, renderer: &core::Renderer) -> core::UniformBuffer {
+        renderer.uniform_buffer(&[self.uniforms()])
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KeyUniforms {
+    pub color: Rgba,
+    pub from: [Rgba; ChromaKey::PALETTE_SIZE],
+    pub to: [Rgba; ChromaKey::PALETTE_SIZE],
+    pub tolerance: f32,
+    _padding: [f32; 3],
+}
+
+impl Default for KeyUniforms {
+    fn default() -> Self {
+        Self {
+            color: Rgba::TRANSPARENT,
+            from: [Rgba::TRANSPARENT; ChromaKey::PALETTE_SIZE],
+            to: [Rgba::TRANSPARENT; ChromaKey::PALETTE_SIZE],
+            tolerance: 0.,
+            _padding: [0.; 3],
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Tint
+///////////////////////////////////////////////////////////////////////////
+
+/// A per-sprite color adjustment. The sprite color is multiplied by
+/// `multiply`, after which `add` is added, scaled by its alpha.
+///
+/// ```
+/// use rgx::core::Rgba;
+/// use rgx::kit::sprite2d::Tint;
+///
+/// assert_eq!(Tint::default(), Tint::NONE);
+/// assert_eq!(Tint::flash(Rgba::WHITE).multiply, Rgba::WHITE);
+/// assert_eq!(Tint::flash(Rgba::WHITE).add, Rgba::WHITE);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Tint {
+    pub multiply: Rgba,
+    pub add: Rgba,
+}
+
+impl Tint {
+    /// A tint which leaves colors unchanged.
+    pub const NONE: Self = Self {
+        multiply: Rgba::WHITE,
+        add: Rgba::TRANSPARENT,
+    };
+
+    pub fn new(multiply: Rgba, add: Rgba) -> Self {
+        Self { multiply, add }
+    }
+
+    /// A multiplicative tint.
+    pub fn multiply(color: Rgba) -> Self {
+        Self::new(color, Rgba::TRANSPARENT)
+    }
+
+    /// An additive color, eg. for hit flashes. The alpha of the color is
+    /// its intensity.
+    pub fn flash(color: Rgba) -> Self {
+        Self::new(Rgba::WHITE, color)
+    }
+}
+
+impl Default for Tint {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Texture Region
+///////////////////////////////////////////////////////////////////////////
+
+/// A region of a texture, in texels, eg. a sprite in an atlas.
+#[derive(Copy, Clone)]
+pub struct TextureRegion<'a> {
+    pub texture: &'a core::Texture,
+    pub rect: Rect<f32>,
+}
+
+impl<'a> TextureRegion<'a> {
+    pub fn new(texture: &'a core::Texture, rect: Rect<f32>) -> Self {
+        Self { texture, rect }
+    }
+
+    /// A region covering the entire texture.
+    pub fn whole(texture: &'a core::Texture) -> Self {
+        Self::new(texture, texture.rect())
+    }
+
+    /// Shrink the region by `texels` on each side, eg. by half a texel, so
+    /// that linear filtering doesn't blend in texels of neighbouring
+    /// regions. Regions narrower than the inset collapse to their center.
+    ///
+    /// ```ignore
+    /// let tile = atlas.region("grass").unwrap().inset(0.5);
+    ///
+    /// batch.add(tile.rect, dst, Rgba::TRANSPARENT, Tint::NONE, 1., Repeat::default());
+    /// ```
+    pub fn inset(&self, texels: f32) -> Self {
+        Self::new(self.texture, self.rect.inset(texels))
+    }
+
+    /// Return the region in normalized texture coordinates, inset by
+    /// `texels` on each side. See [`TextureRegion::inset`].
+    pub fn uv_inset(&self, texels: f32) -> Rect<f32> {
+        self.inset(texels).uv()
+    }
+
+    /// Return the region in normalized texture coordinates.
+    pub fn uv(&self) -> Rect<f32> {
+        let (w, h) = (self.texture.w as f32, self.texture.h as f32);
+
+        Rect::new(
+            self.rect.x1 / w,
+            self.rect.y1 / h,
+            self.rect.x2 / w,
+            self.rect.y2 / h,
+        )
+    }
 }
 
 ///////////////////////////////////////////////////////////////////////////
@@ -33,15 +273,39 @@ pub struct Vertex {
     uv: Vector2<f32>,
     color: Rgba8,
     opacity: f32,
+    tint: Rgba8,
+    additive: Rgba8,
+    snap: f32,
 }
 
 impl Vertex {
-    fn new(x: f32, y: f32, u: f32, v: f32, color: Rgba8, opacity: f32) -> Self {
+    pub(crate) fn new(
+        x: f32,
+        y: f32,
+        u: f32,
+        v: f32,
+        color: Rgba8,
+        opacity: f32,
+        tint: Tint,
+    ) -> Self {
         Self {
             position: Vector2::new(x, y),
             uv: Vector2::new(u, v),
             color,
             opacity,
+            tint: tint.multiply.into(),
+            additive: tint.add.into(),
+            snap: 0.0,
+        }
+    }
+
+    /// Interpolate the position and texture coordinates of two vertices of
+    /// the same sprite.
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            position: self.position + (other.position - self.position) * t,
+            uv: self.uv + (other.uv - self.uv) * t,
+            ..*self
         }
     }
 }
@@ -56,20 +320,106 @@ pub struct Pipeline {
     buf: core::UniformBuffer,
     width: u32,
     height: u32,
+    scale: f64,
     ortho: Matrix4<f32>,
     model: Model,
+    uv: UvTransform,
+    key: core::UniformBuffer,
+    nearest: core::Sampler,
+    linear: core::Sampler,
 }
 
 impl Pipeline {
+    /// Create an identity [`UvTransform`], to be bound for batches with
+    /// animated texture coordinates.
+    pub fn uv_transform(&self, renderer: &core::Renderer) -> UvTransform {
+        Self::create_uv_transform(&self.pipeline, &renderer.device)
+    }
+
+    fn create_uv_transform(pipeline: &core::Pipeline, dev: &core::Device) -> UvTransform {
+        let scale = Vector2::new(1., 1.);
+        let offset = Vector2::new(0., 0.);
+        let buf = dev.create_uniform_buffer(&[Vector4::new(scale.x, scale.y, offset.x, offset.y)]);
+        let binding = dev.create_binding_group(&pipeline.layout.sets[3], &[&buf]);
+
+        UvTransform {
+            scale,
+            offset,
+            binding,
+            buf,
+        }
+    }
+
+    /// Create bindings of the texture with both nearest and linear filtering,
+    /// using samplers owned by the pipeline, so that the filtering can be
+    /// chosen per draw.
+    pub fn filtered_binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+    ) -> FilteredBinding {
+        FilteredBinding {
+            nearest: self.binding(renderer, texture, &self.nearest),
+            linear: self.binding(renderer, texture, &self.linear),
+        }
+    }
+
+    /// Create a [`kit::ModelTransform`], to draw batches with a model
+    /// transform.
+    pub fn model(&self, renderer: &core::Renderer, transform: Transform2D) -> kit::ModelTransform {
+        kit::ModelTransform::new(&self.pipeline.layout.sets[1], transform, &renderer.device)
+    }
+
     pub fn binding(
         &self,
         renderer: &core::Renderer,
         texture: &core::Texture,
         sampler: &core::Sampler,
     ) -> core::BindingGroup {
-        renderer
-            .device
-            .create_binding_group(&self.pipeline.layout.sets[2], &[texture, sampler])
+        self.keyed_binding(renderer, texture, sampler, &self.key)
+    }
+
+    /// Create a texture binding with a [`ChromaKey`], uploaded with
+    /// [`ChromaKey::upload`].
+    pub fn keyed_binding(
+        &self,
+        renderer: &core::Renderer,
+        texture: &core::Texture,
+        sampler: &core::Sampler,
+        key: &core::UniformBuffer,
+    ) -> core::BindingGroup {
+        self.pipeline
+            .binding_group(2)
+            .texture("tex", texture)
+            .sampler("sam", sampler)
+            .uniform_buffer("key", key)
+            .build(&renderer.device)
+            .expect("fatal: kit shaders declare their bindings")
+    }
+}
+
+/// Bindings of a texture for each filter mode, created with
+/// [`Pipeline::filtered_binding`]. Pixel art and smooth images can then be
+/// drawn with the same pipeline, by binding the filter each draw needs:
+///
+/// ```ignore
+/// let bindings = pipeline.filtered_binding(&renderer, &texture);
+///
+/// pass.draw(&ui, bindings.get(Filter::Nearest));
+/// pass.draw(&photo, bindings.get(Filter::Linear));
+/// ```
+pub struct FilteredBinding {
+    nearest: core::BindingGroup,
+    linear: core::BindingGroup,
+}
+
+impl FilteredBinding {
+    /// The binding sampling the texture with the given filter.
+    pub fn get(&self, filter: core::Filter) -> &core::BindingGroup {
+        match filter {
+            core::Filter::Nearest => &self.nearest,
+            core::Filter::Linear => &self.linear,
+        }
     }
 }
 
@@ -125,6 +475,9 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
                 core::VertexFormat::Float2,
                 core::VertexFormat::UByte4,
                 core::VertexFormat::Float,
+                core::VertexFormat::UByte4,
+                core::VertexFormat::UByte4,
+                core::VertexFormat::Float,
             ],
             pipeline_layout: &[
                 Set(&[Binding {
@@ -144,11 +497,18 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
                         binding: BindingType::Sampler,
                         stage: ShaderStage::Fragment,
                     },
+                    Binding {
+                        binding: BindingType::UniformBuffer,
+                        stage: ShaderStage::Fragment,
+                    },
                 ]),
+                Set(&[Binding {
+                    binding: BindingType::UniformBuffer,
+                    stage: ShaderStage::Vertex,
+                }]),
             ],
-            // TODO: Use `env("CARGO_MANIFEST_DIR")`
-            vertex_shader: include_bytes!("data/sprite.vert.spv"),
-            fragment_shader: include_bytes!("data/sprite.frag.spv"),
+            vertex_shader: kit_shader!("data/sprite.vert"),
+            fragment_shader: kit_shader!("data/sprite.frag"),
         }
     }
 
@@ -156,8 +516,17 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
         let ortho = kit::ortho(width, height);
         let transform = Matrix4::identity();
         let model = Model::new(&pipeline.layout.sets[1], &[Matrix4::identity()], dev);
-        let buf = dev.create_uniform_buffer(&[self::Uniforms { ortho, transform }]);
+        let viewport = Vector2::new(width as f32, height as f32);
+        let buf = dev.create_uniform_buffer(&[self::Uniforms {
+            ortho,
+            transform,
+            viewport,
+        }]);
         let bindings = dev.create_binding_group(&pipeline.layout.sets[0], &[&buf]);
+        let uv = Self::create_uv_transform(&pipeline, dev);
+        let key = dev.create_uniform_buffer(&[KeyUniforms::default()]);
+        let nearest = dev.create_sampler(core::Filter::Nearest, core::Filter::Nearest);
+        let linear = dev.create_sampler(core::Filter::Linear, core::Filter::Linear);
 
         Self {
             pipeline,
@@ -167,11 +536,23 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
             ortho,
             width,
             height,
+            scale: 1.0,
+            uv,
+            key,
+            nearest,
+            linear,
         }
     }
 
     fn resize(&mut self, w: u32, h: u32) {
-        self.ortho = kit::ortho(w, h);
+        self.width = w;
+        self.height = h;
+        self.ortho = kit::ortho_scaled(w, h, self.scale);
+    }
+
+    fn set_scale_factor(&mut self, factor: f64) {
+        self.scale = factor;
+        self.ortho = kit::ortho_scaled(self.width, self.height, factor);
     }
 
     fn width(&self) -> u32 {
@@ -186,18 +567,28 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
         pass.set_pipeline(&self.pipeline);
         pass.set_binding(&self.bindings, &[]);
         pass.set_binding(&self.model.binding, &[]);
+        pass.set_binding(&self.uv.binding, &[]);
     }
 
     fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
         &'a self,
         transform: Matrix4<f32>,
-    ) -> Option<(&'a core::UniformBuffer, Vec<self::Uniforms>)> {
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
         Some((
             &self.buf,
-            vec![self::Uniforms {
+            core::SmallVec::from_buf([self::Uniforms {
                 transform,
                 ortho: self.ortho,
-            }],
+                viewport: Vector2::new(self.width as f32, self.height as f32),
+            }]),
         ))
     }
 }
@@ -206,13 +597,25 @@ impl<'a> core::AbstractPipeline<'a> for Pipeline {
 /// Batch
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
+#[derive(Clone, Debug)]
+enum Item {
+    Sprite(Rect<f32>, Rect<f32>, Rgba, Tint, f32, Repeat),
+    /// A sprite with arbitrary corners, in the order top-left, top-right,
+    /// bottom-right, bottom-left.
+    Quad(Rect<f32>, [Point2<f32>; 4], Rgba, Tint, f32),
+}
+
 #[derive(Clone, Debug)]
 pub struct Batch {
     pub w: u32,
     pub h: u32,
     pub size: usize,
+    /// Whether vertex positions are rounded to device pixels, so that
+    /// sprites stay sharp when transformed to fractional coordinates.
+    pub snap: bool,
 
-    items: Vec<(Rect<f32>, Rect<f32>, Rgba, f32, Repeat)>,
+    items: Vec<(Item, Option<Rect<f32>>)>,
+    clip: Option<Rect<f32>>,
 }
 
 impl Batch {
@@ -222,24 +625,64 @@ impl Batch {
             h,
             items: Vec::new(),
             size: 0,
+            snap: false,
+            clip: None,
         }
     }
 
+    /// Clip the sprites added from now on to the given rectangle, or stop
+    /// clipping them with `None`. Clipped sprites are cut on the CPU, with
+    /// their texture coordinates adjusted, so that eg. many small widgets
+    /// can be clipped to their own bounds while staying in a single batch.
+    ///
+    /// ```
+    /// use rgx::core::{Rect, Rgba};
+    /// use rgx::kit::Repeat;
+    /// use rgx::kit::sprite2d::{Batch, Tint};
+    ///
+    /// let src = Rect::new(0., 0., 16., 16.);
+    /// let mut batch = Batch::new(16, 16);
+    ///
+    /// batch.set_clip(Some(Rect::new(0., 0., 8., 32.)));
+    /// batch.add(src, Rect::new(0., 0., 16., 16.), Rgba::WHITE, Tint::NONE, 1., Repeat::default());
+    /// batch.add(src, Rect::new(64., 0., 80., 16.), Rgba::WHITE, Tint::NONE, 1., Repeat::default());
+    ///
+    /// batch.set_clip(None);
+    /// batch.add(src, Rect::new(64., 0., 80., 16.), Rgba::WHITE, Tint::NONE, 1., Repeat::default());
+    ///
+    /// // The first sprite is cut in half, into three triangles, and the
+    /// // second one is outside of its clip rectangle.
+    /// assert_eq!(batch.vertices().len(), 9 + 6);
+    /// ```
+    pub fn set_clip(&mut self, clip: Option<Rect<f32>>) {
+        self.clip = clip;
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn singleton(
         w: u32,
         h: u32,
         src: Rect<f32>,
         dst: Rect<f32>,
         rgba: Rgba,
+        tint: Tint,
         opa: f32,
         rep: Repeat,
     ) -> Self {
         let mut view = Self::new(w, h);
-        view.add(src, dst, rgba, opa, rep);
+        view.add(src, dst, rgba, tint, opa, rep);
         view
     }
 
-    pub fn add(&mut self, src: Rect<f32>, dst: Rect<f32>, rgba: Rgba, opacity: f32, rep: Repeat) {
+    pub fn add(
+        &mut self,
+        src: Rect<f32>,
+        dst: Rect<f32>,
+        rgba: Rgba,
+        tint: Tint,
+        opacity: f32,
+        rep: Repeat,
+    ) {
         if rep != Repeat::default() {
             assert!(
                 src == Rect::origin(self.w as f32, self.h as f32),
@@ -248,31 +691,116 @@ impl Batch {
                 self.h
             );
         }
-        self.items.push((src, dst, rgba, opacity, rep));
+        self.items
+            .push((Item::Sprite(src, dst, rgba, tint, opacity, rep), self.clip));
+        self.size += 1;
+    }
+
+    /// Add a sprite drawn as an arbitrary quad, eg. a rotated or skewed
+    /// sprite. The corners map to the top-left, top-right, bottom-right and
+    /// bottom-left corners of `src`.
+    pub fn add_quad(
+        &mut self,
+        src: Rect<f32>,
+        corners: [Point2<f32>; 4],
+        rgba: Rgba,
+        tint: Tint,
+        opacity: f32,
+    ) {
+        self.items
+            .push((Item::Quad(src, corners, rgba, tint, opacity), self.clip));
         self.size += 1;
     }
 
+    /// Add a sprite sampling the given region of the batch texture.
+    pub fn add_region(
+        &mut self,
+        region: TextureRegion,
+        dst: Rect<f32>,
+        rgba: Rgba,
+        tint: Tint,
+        opacity: f32,
+    ) {
+        assert!(
+            (region.texture.w, region.texture.h) == (self.w, self.h),
+            "fatal: region of a {}x{} texture added to a batch for a {}x{} texture",
+            region.texture.w,
+            region.texture.h,
+            self.w,
+            self.h
+        );
+        self.add(region.rect, dst, rgba, tint, opacity, Repeat::default());
+    }
+
+    /// Upload the batch once, returning a [`StaticBatch`] which can be drawn
+    /// over many frames without further CPU work.
+    pub fn freeze(&self, renderer: &core::Renderer, pipeline: &Pipeline) -> StaticBatch {
+        StaticBatch {
+            buffer: renderer.vertex_buffer(self.vertices().as_slice()),
+            model: Model::new(
+                &pipeline.pipeline.layout.sets[1],
+                &[Matrix4::identity()],
+                &renderer.device,
+            ),
+            transform: Matrix4::identity(),
+            tint: Rgba::WHITE,
+        }
+    }
+
     pub fn vertices(&self) -> Vec<Vertex> {
         let mut buf = Vec::with_capacity(6 * self.items.len());
 
-        for (src, dst, rgba, o, rep) in self.items.iter() {
-            // Relative texture coordinates
-            let rx1: f32 = src.x1 / self.w as f32;
-            let ry1: f32 = src.y1 / self.h as f32;
-            let rx2: f32 = src.x2 / self.w as f32;
-            let ry2: f32 = src.y2 / self.h as f32;
-
-            let c: Rgba8 = (*rgba).into();
-
-            // TODO: Use an index buffer
-            buf.extend_from_slice(&[
-                Vertex::new(dst.x1, dst.y1, rx1 * rep.x, ry2 * rep.y, c, *o),
-                Vertex::new(dst.x2, dst.y1, rx2 * rep.x, ry2 * rep.y, c, *o),
-                Vertex::new(dst.x2, dst.y2, rx2 * rep.x, ry1 * rep.y, c, *o),
-                Vertex::new(dst.x1, dst.y1, rx1 * rep.x, ry2 * rep.y, c, *o),
-                Vertex::new(dst.x1, dst.y2, rx1 * rep.x, ry1 * rep.y, c, *o),
-                Vertex::new(dst.x2, dst.y2, rx2 * rep.x, ry1 * rep.y, c, *o),
-            ]);
+        for (item, clip) in self.items.iter() {
+            let vertices = match item {
+                Item::Sprite(src, dst, rgba, t, o, rep) => {
+                    // Relative texture coordinates
+                    let rx1: f32 = src.x1 / self.w as f32;
+                    let ry1: f32 = src.y1 / self.h as f32;
+                    let rx2: f32 = src.x2 / self.w as f32;
+                    let ry2: f32 = src.y2 / self.h as f32;
+
+                    let c: Rgba8 = (*rgba).into();
+
+                    // TODO: Use an index buffer
+                    [
+                        Vertex::new(dst.x1, dst.y1, rx1 * rep.x, ry2 * rep.y, c, *o, *t),
+                        Vertex::new(dst.x2, dst.y1, rx2 * rep.x, ry2 * rep.y, c, *o, *t),
+                        Vertex::new(dst.x2, dst.y2, rx2 * rep.x, ry1 * rep.y, c, *o, *t),
+                        Vertex::new(dst.x1, dst.y1, rx1 * rep.x, ry2 * rep.y, c, *o, *t),
+                        Vertex::new(dst.x1, dst.y2, rx1 * rep.x, ry1 * rep.y, c, *o, *t),
+                        Vertex::new(dst.x2, dst.y2, rx2 * rep.x, ry1 * rep.y, c, *o, *t),
+                    ]
+                }
+                Item::Quad(src, [p1, p2, p3, p4], rgba, t, o) => {
+                    let rx1: f32 = src.x1 / self.w as f32;
+                    let ry1: f32 = src.y1 / self.h as f32;
+                    let rx2: f32 = src.x2 / self.w as f32;
+                    let ry2: f32 = src.y2 / self.h as f32;
+
+                    let c: Rgba8 = (*rgba).into();
+
+                    [
+                        Vertex::new(p1.x, p1.y, rx1, ry2, c, *o, *t),
+                        Vertex::new(p2.x, p2.y, rx2, ry2, c, *o, *t),
+                        Vertex::new(p3.x, p3.y, rx2, ry1, c, *o, *t),
+                        Vertex::new(p1.x, p1.y, rx1, ry2, c, *o, *t),
+                        Vertex::new(p4.x, p4.y, rx1, ry1, c, *o, *t),
+                        Vertex::new(p3.x, p3.y, rx2, ry1, c, *o, *t),
+                    ]
+                }
+            };
+
+            match clip {
+                Some(clip) => {
+                    kit::clip_triangles(&vertices, *clip, |v| v.position, Vertex::lerp, &mut buf)
+                }
+                None => buf.extend_from_slice(&vertices),
+            }
+        }
+        if self.snap {
+            for v in buf.iter_mut() {
+                v.snap = 1.0;
+            }
         }
         buf
     }
@@ -287,9 +815,75 @@ impl Batch {
         self.size = 0;
     }
 
+    /// Return a shape batch covering the destination of every sprite with the
+    /// given color. When drawn with [`core::Blending::additive`] into a
+    /// framebuffer cleared to black, this yields an overdraw heat map, which
+    /// can be counted with [`shape2d::Overdraw`].
+    pub fn overdraw(&self, color: Rgba) -> shape2d::Batch {
+        let mut batch = shape2d::Batch::new();
+        for (item, clip) in self.items.iter() {
+            let dst = match item {
+                Item::Sprite(_, dst, _, _, _, _) => *dst,
+                Item::Quad(_, corners, _, _, _) => {
+                    let (xs, ys) = (corners.iter().map(|p| p.x), corners.iter().map(|p| p.y));
+                    Rect::new(
+                        xs.clone().fold(f32::INFINITY, f32::min),
+                        ys.clone().fold(f32::INFINITY, f32::min),
+                        xs.fold(f32::NEG_INFINITY, f32::max),
+                        ys.fold(f32::NEG_INFINITY, f32::max),
+                    )
+                }
+            };
+            batch.set_clip(*clip);
+            batch.add(Shape::Rectangle(dst, Stroke::NONE, Fill::Solid(color)));
+        }
+        batch
+    }
+
     pub fn offset(&mut self, x: f32, y: f32) {
-        for (_, dst, _, _, _) in self.items.iter_mut() {
-            *dst = *dst + Vector2::new(x, y);
+        for (item, clip) in self.items.iter_mut() {
+            if let Some(clip) = clip {
+                *clip += Vector2::new(x, y);
+            }
+            match item {
+                Item::Sprite(_, dst, _, _, _, _) => *dst += Vector2::new(x, y),
+                Item::Quad(_, corners, _, _, _) => {
+                    for p in corners.iter_mut() {
+                        *p = Point2::new(p.x + x, p.y + y);
+                    }
+                }
+            }
         }
     }
 }
+
+/// A sprite batch uploaded once with [`Batch::freeze`], for mostly-static
+/// backgrounds and UI. Its transform and tint are uniforms, and are cheap to
+/// change between draws.
+///
+/// Drawing a static batch binds its own model transform, so the pipeline
+/// should be set again before drawing regular batches in the same pass.
+pub struct StaticBatch {
+    pub transform: Matrix4<f32>,
+    pub tint: Rgba,
+
+    buffer: core::VertexBuffer,
+    model: Model,
+}
+
+impl StaticBatch {
+    /// Upload the transform and tint to the GPU.
+    pub fn update(&self, renderer: &mut core::Renderer, frame: &mut core::Frame) {
+        renderer.update_uniform_buffer(
+            &self.model.buf,
+            &[AlignedBuffer::with_tint(self.transform, self.tint)],
+            frame,
+        );
+    }
+
+    /// Draw the batch with the given texture binding.
+    pub fn draw(&self, pass: &mut core::Pass, binding: &core::BindingGroup) {
+        pass.set_binding(&self.model.binding, &[]);
+        pass.draw(&self.buffer, binding);
+    }
+}
@@ -0,0 +1,162 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use crate::core::Rgba;
+
+use crate::kit::shape2d::{self, Vertex};
+
+use crate::math::*;
+
+/// A ribbon following a moving point, eg. a projectile or the cursor, which
+/// fades out over time.
+///
+/// The ribbon tapers from its full width and color at the head to nothing at
+/// the tail, and every point shrinks and fades as it ages, until it expires
+/// after the trail's lifetime.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+///
+/// use rgx::core::Rgba;
+/// use rgx::kit::trail::Trail;
+/// use rgx::math::Point2;
+///
+/// let now = Instant::now();
+/// let mut trail = Trail::new(8., Rgba::WHITE, Duration::from_millis(500));
+///
+/// trail.push(Point2::new(0., 0.), now);
+/// trail.push(Point2::new(10., 0.), now);
+/// trail.push(Point2::new(20., 5.), now);
+///
+/// // Two segments, of two triangles each.
+/// assert_eq!(trail.triangulate(now).len(), 12);
+///
+/// trail.update(now + Duration::from_secs(1));
+/// assert!(trail.is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct Trail {
+    /// Width at the head of the trail.
+    pub width: f32,
+    /// Color at the head of the trail.
+    pub color: Rgba,
+    /// How long points last.
+    pub lifetime: Duration,
+    /// Points closer than this to the head move the head instead of
+    /// extending the trail.
+    pub min_distance: f32,
+
+    points: VecDeque<(Point2<f32>, Instant)>,
+}
+
+impl Trail {
+    pub fn new(width: f32, color: Rgba, lifetime: Duration) -> Self {
+        Self {
+            width,
+            color,
+            lifetime,
+            min_distance: 2.,
+            points: VecDeque::new(),
+        }
+    }
+
+    /// Extend the trail to `p`, eg. on every frame.
+    pub fn push(&mut self, p: Point2<f32>, now: Instant) {
+        if self.points.len() > 1 {
+            if let Some((head, time)) = self.points.back_mut() {
+                if (p.x - head.x).hypot(p.y - head.y) < self.min_distance {
+                    *head = p;
+                    *time = now;
+                    return;
+                }
+            }
+        }
+        self.points.push_back((p, now));
+    }
+
+    /// Remove expired points.
+    pub fn update(&mut self, now: Instant) {
+        while let Some((_, time)) = self.points.front() {
+            if now.saturating_duration_since(*time) < self.lifetime {
+                break;
+            }
+            self.points.pop_front();
+        }
+    }
+
+    /// The points of the trail, from the tail to the head.
+    pub fn points(&self) -> impl Iterator<Item = Point2<f32>> + '_ {
+        self.points.iter().map(|(p, _)| *p)
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.points.clear();
+    }
+
+    /// Tessellate the trail as it appears at `now`.
+    pub fn triangulate(&self, now: Instant) -> Vec<Vertex> {
+        let n = self.points.len();
+        if n < 2 {
+            return Vec::new();
+        }
+        let lifetime = self.lifetime.as_secs_f32().max(f32::EPSILON);
+
+        // The left and right edges of the ribbon at each point.
+        let mut normal = Vector2::new(0., 0.);
+        let edges: Vec<(Vertex, Vertex)> = (0..n)
+            .map(|i| {
+                let (p, time) = self.points[i];
+                let (prev, next) = (
+                    self.points[i.saturating_sub(1)].0,
+                    self.points[(i + 1).min(n - 1)].0,
+                );
+                let tangent = Vector2::new(next.x - prev.x, next.y - prev.y);
+
+                if tangent.magnitude() > 0. {
+                    let t = tangent.normalize();
+                    normal = Vector2::new(-t.y, t.x);
+                }
+
+                let age = now.saturating_duration_since(time).as_secs_f32() / lifetime;
+                let falloff = (1. - age).clamp(0., 1.) * i as f32 / (n - 1) as f32;
+                let w = self.width * falloff / 2.;
+                let color = Rgba {
+                    a: self.color.a * falloff,
+                    ..self.color
+                }
+                .into();
+
+                (
+                    shape2d::vertex(p.x + normal.x * w, p.y + normal.y * w, color),
+                    shape2d::vertex(p.x - normal.x * w, p.y - normal.y * w, color),
+                )
+            })
+            .collect();
+
+        let mut verts = Vec::with_capacity((n - 1) * 6);
+        for w in edges.windows(2) {
+            let ((l0, r0), (l1, r1)) = (w[0], w[1]);
+            verts.extend_from_slice(&[l0, r0, l1, l1, r0, r1]);
+        }
+        verts
+    }
+
+    /// Add the trail as it appears at `now` to a shape batch.
+    pub fn draw(&self, now: Instant, batch: &mut shape2d::Batch) {
+        let verts = self.triangulate(now);
+
+        if !verts.is_empty() {
+            batch.add_triangles(verts);
+        }
+    }
+}
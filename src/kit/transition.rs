@@ -0,0 +1,336 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use crate::core;
+use crate::core::{Binding, BindingType, PassOp, Set, ShaderStage, TextureFormat};
+
+use crate::kit;
+use crate::kit::present;
+use crate::kit::present::Fullscreen;
+
+use crate::math::noise;
+use crate::math::*;
+
+///////////////////////////////////////////////////////////////////////////
+// Uniforms
+///////////////////////////////////////////////////////////////////////////
+
+/// For each fragment, the point in the transition at which it switches to
+/// the new scene is computed from the threshold map, the position along
+/// `direction` and the distance from `center`, weighted by these uniforms.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Uniforms {
+    pub edge: f32,
+    pub sharpness: f32,
+    pub direction: Vector2<f32>,
+    pub center: Vector2<f32>,
+    pub aspect: f32,
+    pub noise: f32,
+    pub radial: f32,
+    pub offset: f32,
+    _padding: [f32; 2],
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Transition
+///////////////////////////////////////////////////////////////////////////
+
+/// How the new scene replaces the old one.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Effect {
+    /// The scenes are blended.
+    Crossfade,
+    /// Fragments switch in the order given by the threshold map, eg. noise.
+    Dissolve,
+    /// The new scene sweeps across the old one in the given direction.
+    Wipe(Vector2<f32>),
+    /// The new scene appears in a circle growing from the given point, in
+    /// texture coordinates.
+    CircleIn(Point2<f32>),
+    /// The old scene shrinks into a circle closing on the given point, in
+    /// texture coordinates.
+    CircleOut(Point2<f32>),
+}
+
+/// A transition between two scenes, eg. rendered to framebuffers, at a given
+/// progress.
+///
+/// ```
+/// use rgx::kit::transition::{Effect, Transition};
+/// use rgx::math::{Point2, Vector2};
+///
+/// let mut t = Transition::new(Effect::Wipe(Vector2::new(2., 0.)));
+/// t.progress = 0.5;
+///
+/// let u = t.uniforms(1.);
+/// assert_eq!(u.direction, Vector2::new(1., 0.));
+/// assert_eq!(u.offset, 0.5);
+///
+/// let t = Transition::new(Effect::CircleIn(Point2::new(0., 0.)));
+/// assert_eq!(t.uniforms(1.).radial, 1. / 2f32.sqrt());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub effect: Effect,
+    /// Width of the edge between the scenes, as a fraction of the
+    /// transition.
+    pub softness: f32,
+    /// From `0`, showing the old scene, to `1`, showing the new one.
+    pub progress: f32,
+}
+
+impl Transition {
+    pub fn new(effect: Effect) -> Self {
+        Self {
+            effect,
+            softness: 0.1,
+            progress: 0.,
+        }
+    }
+
+    /// Whether the new scene is fully shown.
+    pub fn is_done(&self) -> bool {
+        self.progress >= 1.
+    }
+
+    /// The shader uniforms for a target with the given aspect ratio.
+    pub fn uniforms(&self, aspect: f32) -> Uniforms {
+        let s = self.softness.max(1e-3);
+        let progress = self.progress.clamp(0., 1.);
+
+        // The furthest corner from `c`, to scale circles to cover the target.
+        let radius = |c: Point2<f32>| {
+            [(0., 0.), (1., 0.), (0., 1.), (1., 1.)]
+                .iter()
+                .map(|(x, y)| ((x - c.x) * aspect).hypot(y - c.y))
+                .fold(f32::EPSILON, f32::max)
+        };
+        let uniforms = Uniforms {
+            edge: progress * (1. + s),
+            sharpness: 1. / s,
+            direction: Vector2::new(0., 0.),
+            center: Vector2::new(0.5, 0.5),
+            aspect,
+            noise: 0.,
+            radial: 0.,
+            offset: 0.,
+            _padding: [0.; 2],
+        };
+
+        match self.effect {
+            Effect::Crossfade => Uniforms {
+                edge: progress,
+                sharpness: 1.,
+                ..uniforms
+            },
+            Effect::Dissolve => Uniforms {
+                noise: 1.,
+                ..uniforms
+            },
+            Effect::Wipe(dir) => {
+                let len = dir.x.abs() + dir.y.abs();
+                let direction = if len > 0. { dir * (1. / len) } else { dir };
+
+                Uniforms {
+                    direction,
+                    offset: 0.5,
+                    ..uniforms
+                }
+            }
+            Effect::CircleIn(c) => Uniforms {
+                center: Vector2::new(c.x, c.y),
+                radial: 1. / radius(c),
+                ..uniforms
+            },
+            Effect::CircleOut(c) => Uniforms {
+                center: Vector2::new(c.x, c.y),
+                radial: -1. / radius(c),
+                offset: 1.,
+                ..uniforms
+            },
+        }
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Pipeline
+///////////////////////////////////////////////////////////////////////////
+
+/// Blends two scene textures according to a [`Transition`], drawn with a
+/// [`present::quad`].
+pub struct Pipeline {
+    fullscreen: Fullscreen,
+}
+
+impl Pipeline {
+    /// Bind the old and new scenes, and the threshold map used by
+    /// [`Effect::Dissolve`].
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        from: &core::Texture,
+        to: &core::Texture,
+        threshold: &core::Texture,
+        sampler: &core::Sampler,
+    ) -> core::BindingGroup {
+        self.fullscreen
+            .pipeline
+            .binding_group(0)
+            .texture("from", from)
+            .texture("to", to)
+            .texture("threshold", threshold)
+            .sampler("sam", sampler)
+            .build(&renderer.device)
+            .expect("fatal: kit shaders declare their bindings")
+    }
+}
+
+impl<'a> core::AbstractPipeline<'a> for Pipeline {
+    type PrepareContext = Transition;
+    type Uniforms = self::Uniforms;
+
+    fn description() -> core::PipelineDescription<'a> {
+        Fullscreen::description(
+            &[
+                Set(&[
+                    Binding {
+                        binding: BindingType::SampledTexture,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::SampledTexture,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::SampledTexture,
+                        stage: ShaderStage::Fragment,
+                    },
+                    Binding {
+                        binding: BindingType::Sampler,
+                        stage: ShaderStage::Fragment,
+                    },
+                ]),
+                Fullscreen::UNIFORMS,
+            ],
+            kit_shader!("data/transition.frag"),
+        )
+    }
+
+    fn setup(pipeline: core::Pipeline, dev: &core::Device, width: u32, height: u32) -> Self {
+        let uniforms = Transition::new(Effect::Crossfade).uniforms(1.);
+
+        Self {
+            fullscreen: Fullscreen::setup(pipeline, dev, width, height, uniforms),
+        }
+    }
+
+    fn resize(&mut self, w: u32, h: u32) {
+        self.fullscreen.resize(w, h);
+    }
+
+    fn width(&self) -> u32 {
+        self.fullscreen.width()
+    }
+
+    fn height(&self) -> u32 {
+        self.fullscreen.height()
+    }
+
+    fn apply(&self, pass: &mut core::Pass) {
+        self.fullscreen.apply(pass);
+    }
+
+    fn prepare(
+        &'a self,
+        ctx: Self::PrepareContext,
+    ) -> Option<(&'a core::UniformBuffer, Vec<Self::Uniforms>)> {
+        self.uniforms(ctx)
+            .map(|(buf, unifs)| (buf, unifs.into_vec()))
+    }
+
+    fn uniforms(
+        &'a self,
+        transition: Transition,
+    ) -> Option<(&'a core::UniformBuffer, core::SmallVec<[self::Uniforms; 1]>)> {
+        let aspect = self.fullscreen.width() as f32 / self.fullscreen.height().max(1) as f32;
+
+        Some((
+            self.fullscreen.uniforms(),
+            core::SmallVec::from_buf([transition.uniforms(aspect)]),
+        ))
+    }
+}
+
+///////////////////////////////////////////////////////////////////////////
+// Transition Pass
+///////////////////////////////////////////////////////////////////////////
+
+/// A post pass drawing a [`Transition`] between two scenes to an output,
+/// eg. the swap chain. Both scenes are rendered by the application, eg. to
+/// framebuffers, and are sampled with linear filtering.
+pub struct TransitionPass {
+    pipeline: Pipeline,
+    sampler: core::Sampler,
+    threshold: core::Texture,
+    quad: core::VertexBuffer,
+}
+
+impl TransitionPass {
+    /// Create a pass drawing to a `w` by `h` output of the given format.
+    /// A noise threshold map is generated for dissolves.
+    pub fn new(renderer: &mut core::Renderer, w: u32, h: u32, format: TextureFormat) -> Self {
+        let pipeline = renderer.pipeline_with_format(w, h, core::Blending::replace(), format);
+        let sampler = renderer.sampler(core::Filter::Linear, core::Filter::Linear);
+        let threshold = kit::noise_texture(renderer, 256, 256, 32., |x, y| {
+            noise::fbm(noise::value, x, y, 0, 4)
+        });
+        let quad = present::quad(renderer);
+
+        Self {
+            pipeline,
+            sampler,
+            threshold,
+            quad,
+        }
+    }
+
+    /// Use another threshold map for dissolves, eg. a pattern or a shape.
+    pub fn set_threshold(&mut self, threshold: core::Texture) {
+        self.threshold = threshold;
+    }
+
+    pub fn resize(&mut self, w: u32, h: u32) {
+        use core::AbstractPipeline;
+
+        self.pipeline.resize(w, h);
+    }
+
+    /// Bind the old and new scenes, to be passed to [`TransitionPass::draw`].
+    pub fn binding(
+        &self,
+        renderer: &core::Renderer,
+        from: &core::Texture,
+        to: &core::Texture,
+    ) -> core::BindingGroup {
+        self.pipeline
+            .binding(renderer, from, to, &self.threshold, &self.sampler)
+    }
+
+    /// Draw the transition between the scenes bound in `binding` to `out`.
+    pub fn draw<T: core::TextureView>(
+        &self,
+        transition: Transition,
+        binding: &core::BindingGroup,
+        renderer: &mut core::Renderer,
+        frame: &mut core::Frame,
+        out: &T,
+    ) {
+        renderer.update_pipeline(&self.pipeline, transition, frame);
+
+        // The quad covers the whole target and replaces its texels, including
+        // alpha, so its previous contents don't matter.
+        let mut pass = frame.pass(PassOp::DontCare(), out);
+        pass.set_pipeline(&self.pipeline);
+        pass.draw(&self.quad, binding);
+    }
+}
@@ -0,0 +1,211 @@
+#![deny(clippy::all, clippy::use_self)]
+
+use std::f32::consts::PI;
+
+use crate::core;
+use crate::core::{Rect, Rgba, Rgba8};
+
+use crate::kit::sprite2d::{Tint, Vertex};
+
+use crate::math::*;
+
+/// A texture region mapped onto a grid of movable control points, drawn
+/// with the sprite pipeline, eg. for liquid, flag or page-curl effects.
+///
+/// The grid has `cols` by `rows` cells, and so `cols + 1` by `rows + 1`
+/// control points, which start out evenly spread over the destination
+/// rectangle. Each cell is drawn as two triangles, with the texture
+/// stretched between its corners.
+///
+/// ```
+/// use rgx::core::{Rect, Rgba};
+/// use rgx::kit::sprite2d::Tint;
+/// use rgx::kit::warp::WarpGrid;
+/// use rgx::math::{Point2, Vector2};
+///
+/// let mut grid = WarpGrid::new(64, 64, Rect::origin(64., 64.), Rect::origin(128., 128.), 4, 2);
+///
+/// assert_eq!(grid.point(2, 1), Point2::new(64., 64.));
+/// assert_eq!(grid.vertices(Rgba::WHITE, Tint::NONE, 1.).len(), 4 * 2 * 6);
+///
+/// grid.wave(Vector2::new(8., 0.), 256., 0.);
+/// assert_eq!(grid.point(0, 0), Point2::new(0., 0.));
+/// assert!((grid.point(0, 1).x - 8.).abs() < 1e-4);
+///
+/// grid.reset();
+/// assert_eq!(grid.point(0, 1), Point2::new(0., 64.));
+/// ```
+#[derive(Clone, Debug)]
+pub struct WarpGrid {
+    /// Number of cells across.
+    pub cols: u32,
+    /// Number of cells down.
+    pub rows: u32,
+
+    uv: Rect<f32>,
+    rest: Vec<Point2<f32>>,
+    points: Vec<Point2<f32>>,
+}
+
+impl WarpGrid {
+    /// Create a grid mapping the `src` region of a `w` by `h` texture onto
+    /// `dst`, with `cols` by `rows` cells.
+    pub fn new(w: u32, h: u32, src: Rect<f32>, dst: Rect<f32>, cols: u32, rows: u32) -> Self {
+        assert!(
+            cols > 0 && rows > 0,
+            "fatal: warp grid must have at least one cell"
+        );
+
+        let uv = Rect::new(
+            src.x1 / w as f32,
+            src.y1 / h as f32,
+            src.x2 / w as f32,
+            src.y2 / h as f32,
+        );
+        let rest: Vec<Point2<f32>> = (0..=rows)
+            .flat_map(|j| {
+                (0..=cols).map(move |i| {
+                    let (s, t) = (i as f32 / cols as f32, j as f32 / rows as f32);
+                    Point2::new(
+                        dst.x1 + (dst.x2 - dst.x1) * s,
+                        dst.y1 + (dst.y2 - dst.y1) * t,
+                    )
+                })
+            })
+            .collect();
+
+        Self {
+            cols,
+            rows,
+            uv,
+            points: rest.clone(),
+            rest,
+        }
+    }
+
+    /// The control point in column `i` and row `j`.
+    pub fn point(&self, i: u32, j: u32) -> Point2<f32> {
+        self.points[self.index(i, j)]
+    }
+
+    /// Move the control point in column `i` and row `j`.
+    pub fn set(&mut self, i: u32, j: u32, p: Point2<f32>) {
+        let ix = self.index(i, j);
+        self.points[ix] = p;
+    }
+
+    /// The undeformed position of the control point in column `i` and
+    /// row `j`.
+    pub fn rest(&self, i: u32, j: u32) -> Point2<f32> {
+        self.rest[self.index(i, j)]
+    }
+
+    /// All control points, row by row.
+    pub fn points(&self) -> &[Point2<f32>] {
+        &self.points
+    }
+
+    /// Move every control point back to its undeformed position.
+    pub fn reset(&mut self) {
+        self.points.copy_from_slice(&self.rest);
+    }
+
+    /// Deform the grid with a sine wave, eg. for water or a flag. Points are
+    /// moved horizontally by `amplitude.x` along a wave travelling down the
+    /// grid, and vertically by `amplitude.y` along one travelling across it.
+    /// The wave has the given `wavelength`, in pixels, and is shifted by
+    /// `phase`, in radians, eg. to animate it over time.
+    ///
+    /// Replaces any previous deformation.
+    pub fn wave(&mut self, amplitude: Vector2<f32>, wavelength: f32, phase: f32) {
+        let k = 2. * PI / wavelength.max(f32::EPSILON);
+
+        for (p, r) in self.points.iter_mut().zip(self.rest.iter()) {
+            *p = Point2::new(
+                r.x + amplitude.x * (r.y * k + phase).sin(),
+                r.y + amplitude.y * (r.x * k + phase).sin(),
+            );
+        }
+    }
+
+    /// Deform the grid with a jelly-like wobble around `anchor`, eg. the
+    /// bottom center of a character. The grid is alternately squashed and
+    /// stretched by up to `amount`, as a fraction of its size, with points
+    /// further from the anchor lagging behind, so the wobble ripples
+    /// outwards. `phase` is in radians, eg. to animate it over time.
+    ///
+    /// Replaces any previous deformation.
+    pub fn jelly(&mut self, anchor: Point2<f32>, amount: f32, phase: f32) {
+        let first = self.rest[0];
+        let last = self.rest[self.rest.len() - 1];
+        let size = (last.x - first.x).hypot(last.y - first.y).max(f32::EPSILON);
+
+        for (p, r) in self.points.iter_mut().zip(self.rest.iter()) {
+            let (dx, dy) = (r.x - anchor.x, r.y - anchor.y);
+            let lag = dx.hypot(dy) / size * PI / 2.;
+            let s = amount * (phase - lag).sin();
+
+            *p = Point2::new(anchor.x + dx * (1. + s), anchor.y + dy * (1. - s));
+        }
+    }
+
+    /// The triangles of the grid, for the sprite pipeline.
+    pub fn vertices(&self, color: Rgba, tint: Tint, opacity: f32) -> Vec<Vertex> {
+        let c: Rgba8 = color.into();
+        let vertex = |i: u32, j: u32| {
+            let p = self.point(i, j);
+            let (s, t) = (i as f32 / self.cols as f32, j as f32 / self.rows as f32);
+
+            // Texture coordinates are flipped vertically, as in sprite batches.
+            Vertex::new(
+                p.x,
+                p.y,
+                self.uv.x1 + (self.uv.x2 - self.uv.x1) * s,
+                self.uv.y2 + (self.uv.y1 - self.uv.y2) * t,
+                c,
+                opacity,
+                tint,
+            )
+        };
+
+        let mut buf = Vec::with_capacity((self.cols * self.rows * 6) as usize);
+        for j in 0..self.rows {
+            for i in 0..self.cols {
+                buf.extend_from_slice(&[
+                    vertex(i, j),
+                    vertex(i + 1, j),
+                    vertex(i + 1, j + 1),
+                    vertex(i, j),
+                    vertex(i, j + 1),
+                    vertex(i + 1, j + 1),
+                ]);
+            }
+        }
+        buf
+    }
+
+    /// Upload the grid, to be drawn with the sprite pipeline and a binding
+    /// for its texture.
+    pub fn finish(
+        &self,
+        r: &core::Renderer,
+        color: Rgba,
+        tint: Tint,
+        opacity: f32,
+    ) -> core::VertexBuffer {
+        r.device
+            .create_buffer(self.vertices(color, tint, opacity).as_slice())
+    }
+
+    fn index(&self, i: u32, j: u32) -> usize {
+        assert!(
+            i <= self.cols && j <= self.rows,
+            "fatal: control point ({}, {}) is outside of a {}x{} warp grid",
+            i,
+            j,
+            self.cols,
+            self.rows
+        );
+        (j * (self.cols + 1) + i) as usize
+    }
+}
@@ -1,4 +1,8 @@
+#[macro_use]
+mod macros;
+
 pub mod core;
+#[cfg(feature = "kit")]
 pub mod kit;
 pub mod math;
 pub mod nonempty;
@@ -0,0 +1,83 @@
+/// Embed a compiled shader, given the path to its GLSL source, relative to
+/// the current file.
+///
+/// The SPIR-V module is read from the same path with a `.spv` extension, eg.
+/// `data/sprite.vert.spv` for `data/sprite.vert`, and validated at compile
+/// time: a module which is malformed, or which was compiled for a different
+/// stage than its source's extension suggests, fails the build with an error
+/// naming the file. The GLSL source is tracked, so that editing it triggers a
+/// rebuild.
+///
+/// ```ignore
+/// PipelineDescription {
+///     vertex_layout: &[VertexFormat::Float2],
+///     pipeline_layout: &[],
+///     vertex_shader: rgx::include_shader!("data/quad.vert"),
+///     fragment_shader: rgx::include_shader!("data/quad.frag"),
+/// }
+/// ```
+#[macro_export]
+macro_rules! include_shader {
+    ($path:literal) => {{
+        const _: &[u8] = include_bytes!($path);
+
+        $crate::include_shader!(@validate $path, include_bytes!(concat!($path, ".spv")))
+    }};
+    (@validate $path:literal, $spirv:expr) => {{
+        const SPIRV: &[u8] = $spirv;
+        const _: () = {
+            let stage = match $crate::core::ShaderStage::from_path($path) {
+                Some(stage) => stage,
+                None => panic!(concat!(
+                    $path,
+                    ": unknown shader stage, expected a .vert, .frag or .comp file"
+                )),
+            };
+            match $crate::core::validate_spirv(SPIRV, stage) {
+                Ok(()) => {}
+                Err($crate::core::SpirvError::Magic) => {
+                    panic!(concat!($path, ".spv: not a SPIR-V module"))
+                }
+                Err($crate::core::SpirvError::Length) => panic!(concat!(
+                    $path,
+                    ".spv: module size is not a whole number of words"
+                )),
+                Err($crate::core::SpirvError::Truncated) => {
+                    panic!(concat!($path, ".spv: module is truncated"))
+                }
+                Err($crate::core::SpirvError::Stage) => panic!(concat!(
+                    $path,
+                    ".spv: module has no entry point for this shader stage, is it stale?"
+                )),
+            }
+        };
+        SPIRV
+    }};
+}
+
+/// Embed one of the kit's shaders, like [`include_shader`]. With the
+/// `glslang` feature, the module compiled by the build script into
+/// `OUT_DIR` is embedded instead of the committed one. Only the kit modules
+/// with pipelines use it.
+#[cfg(all(
+    feature = "glslang",
+    any(feature = "shape2d", feature = "sprite2d", feature = "post")
+))]
+macro_rules! kit_shader {
+    ($path:literal) => {
+        $crate::include_shader!(
+            @validate $path,
+            include_bytes!(concat!(env!("OUT_DIR"), "/", $path, ".spv"))
+        )
+    };
+}
+
+#[cfg(all(
+    not(feature = "glslang"),
+    any(feature = "shape2d", feature = "sprite2d", feature = "post")
+))]
+macro_rules! kit_shader {
+    ($path:literal) => {
+        $crate::include_shader!($path)
+    };
+}
@@ -18,8 +18,27 @@
 pub use num_traits::{cast, Float, One, Zero};
 
 /// 2D vector.
+///
+/// ```
+/// use std::f32::consts::FRAC_PI_2;
+///
+/// use rgx::math::Vector2;
+///
+/// let v = Vector2::new(3., 4.);
+///
+/// assert_eq!(v.length(), 5.);
+/// assert_eq!(v.dot(Vector2::new(1., 0.)), 3.);
+/// assert_eq!(v.perp(), Vector2::new(-4., 3.));
+/// assert_eq!(v.perp_dot(v.perp()), 25.);
+/// assert_eq!(Vector2::new(0., 0.).normalize_or_zero(), Vector2::new(0., 0.));
+///
+/// let up = Vector2::from_angle(FRAC_PI_2);
+/// assert!(up.x.abs() < 1e-6 && up.y == 1.);
+/// assert!((Vector2::new(1., 0.).angle_to(up) - FRAC_PI_2).abs() < 1e-6);
+/// ```
 #[repr(C)]
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Vector2<S> {
     pub x: S,
     pub y: S,
@@ -48,13 +67,111 @@ impl<S: Sized> Vector2<S> {
         Float::sqrt(Self::dot(self, self))
     }
 
+    /// Returns the normalized vector, or the zero vector if its length is
+    /// zero.
+    #[inline]
+    pub fn normalize_or_zero(self) -> Self
+    where
+        S: Float,
+    {
+        let len = self.magnitude();
+
+        if len > S::zero() {
+            self * (S::one() / len)
+        } else {
+            Self::new(S::zero(), S::zero())
+        }
+    }
+
+    /// The distance from the tail to the tip of the vector. Same as
+    /// [`Vector2::magnitude`].
+    #[inline]
+    pub fn length(self) -> S
+    where
+        S: Float,
+    {
+        self.magnitude()
+    }
+
+    /// The squared length of the vector, which is cheaper to compute, eg.
+    /// for comparing lengths.
+    #[inline]
+    pub fn length_squared(self) -> S
+    where
+        S: std::ops::Mul<Output = S> + std::ops::Add<Output = S> + Copy,
+    {
+        Self::dot(self, self)
+    }
+
     /// Dot product of two vectors.
     #[inline]
-    pub fn dot(a: Self, b: Self) -> <S as std::ops::Add>::Output
+    pub fn dot(self, other: Self) -> <S as std::ops::Add>::Output
     where
         S: std::ops::Mul<Output = S> + std::ops::Add,
     {
-        a.x * b.x + a.y * b.y
+        self.x * other.x + self.y * other.y
+    }
+
+    /// The vector rotated by 90 degrees, counter-clockwise in a y-up space,
+    /// eg. the normal of an edge.
+    #[inline]
+    pub fn perp(self) -> Self
+    where
+        S: std::ops::Neg<Output = S>,
+    {
+        Self::new(-self.y, self.x)
+    }
+
+    /// The dot product of the perpendicular of this vector with `other`,
+    /// ie. the z component of their cross product. It is positive when
+    /// `other` is counter-clockwise from this vector in a y-up space.
+    #[inline]
+    pub fn perp_dot(self, other: Self) -> S
+    where
+        S: std::ops::Mul<Output = S> + std::ops::Sub<Output = S>,
+    {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// A unit vector pointing in the direction of `radians`, from the x
+    /// axis.
+    #[inline]
+    pub fn from_angle(radians: S) -> Self
+    where
+        S: Float,
+    {
+        let (sin, cos) = radians.sin_cos();
+        Self::new(cos, sin)
+    }
+
+    /// The angle of the vector from the x axis, in radians, in the range
+    /// `[-π, π]`.
+    #[inline]
+    pub fn to_angle(self) -> S
+    where
+        S: Float,
+    {
+        self.y.atan2(self.x)
+    }
+
+    /// The signed angle from this vector to `other`, in radians, in the
+    /// range `[-π, π]`.
+    #[inline]
+    pub fn angle_to(self, other: Self) -> S
+    where
+        S: Float,
+    {
+        self.perp_dot(other).atan2(self.dot(other))
+    }
+
+    /// The vector rotated by `radians`.
+    #[inline]
+    pub fn rotate(self, radians: S) -> Self
+    where
+        S: Float,
+    {
+        let (sin, cos) = radians.sin_cos();
+        Self::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
     }
 
     /// Distance between two vectors.
@@ -148,6 +265,36 @@ impl<S> Vector3<S> {
     }
 }
 
+impl<S> std::ops::Mul<S> for Vector3<S>
+where
+    S: std::ops::Mul<Output = S> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, s: S) -> Self {
+        Self {
+            x: self.x * s,
+            y: self.y * s,
+            z: self.z * s,
+        }
+    }
+}
+
+impl<S> std::ops::Add<Vector3<S>> for Vector3<S>
+where
+    S: std::ops::Add<Output = S> + Copy,
+{
+    type Output = Self;
+
+    fn add(self, other: Vector3<S>) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+            z: self.z + other.z,
+        }
+    }
+}
+
 /// 4D vector.
 #[repr(C)]
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
@@ -198,6 +345,7 @@ where
 
 #[repr(C)]
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Point2<S> {
     pub x: S,
     pub y: S,
@@ -208,6 +356,38 @@ impl<S> Point2<S> {
         Point2 { x, y }
     }
 
+    /// The vector from the origin to the point.
+    pub fn to_vec(self) -> Vector2<S> {
+        Vector2::new(self.x, self.y)
+    }
+
+    /// The distance between two points.
+    ///
+    /// ```
+    /// use rgx::math::{Point2, Vector2};
+    ///
+    /// let (a, b) = (Point2::new(1., 1.), Point2::new(4., 5.));
+    ///
+    /// assert_eq!(a.distance(b), 5.);
+    /// assert_eq!(b - a, Vector2::new(3., 4.));
+    /// assert_eq!(a.midpoint(b), Point2::new(2.5, 3.));
+    /// ```
+    pub fn distance(self, other: Self) -> S
+    where
+        S: Float,
+    {
+        (other - self).magnitude()
+    }
+
+    /// The point halfway between two points.
+    pub fn midpoint(self, other: Self) -> Self
+    where
+        S: Float,
+    {
+        let two = S::one() + S::one();
+        Self::new((self.x + other.x) / two, (self.y + other.y) / two)
+    }
+
     pub fn map<F, T>(self, mut f: F) -> Point2<T>
     where
         F: FnMut(S) -> T,
@@ -244,6 +424,17 @@ where
     }
 }
 
+impl<S> std::ops::Sub<Point2<S>> for Point2<S>
+where
+    S: std::ops::Sub<Output = S> + Copy,
+{
+    type Output = Vector2<S>;
+
+    fn sub(self, other: Point2<S>) -> Vector2<S> {
+        Vector2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
 impl<S> std::ops::Sub<Vector2<S>> for Point2<S>
 where
     S: std::ops::Sub<Output = S> + Copy,
@@ -258,6 +449,161 @@ where
     }
 }
 
+/// A 3 x 3, column major matrix, eg. for 2D affine transforms.
+///
+/// This type is marked as `#[repr(C)]`.
+///
+/// ```
+/// use rgx::math::{Matrix3, Point2, Vector2};
+///
+/// let m = Matrix3::from_translation(Vector2::new(10., 0.))
+///     * Matrix3::from_angle_z(std::f32::consts::FRAC_PI_2)
+///     * Matrix3::from_nonuniform_scale(2., 1.);
+///
+/// let p = m.transform_point(Point2::new(1., 0.));
+/// assert!((p.x - 10.).abs() < 1e-6 && (p.y - 2.).abs() < 1e-6);
+/// ```
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Matrix3<S> {
+    /// The first column of the matrix.
+    pub x: Vector3<S>,
+    /// The second column of the matrix.
+    pub y: Vector3<S>,
+    /// The third column of the matrix.
+    pub z: Vector3<S>,
+}
+
+impl<S: Copy + Zero + One> Matrix3<S> {
+    /// Create a new matrix, providing values for each index.
+    #[inline]
+    #[allow(clippy::too_many_arguments)]
+    #[rustfmt::skip]
+    pub fn new(
+        c0r0: S, c0r1: S, c0r2: S,
+        c1r0: S, c1r1: S, c1r2: S,
+        c2r0: S, c2r1: S, c2r2: S,
+    ) -> Self {
+        Self {
+            x: Vector3::new(c0r0, c0r1, c0r2),
+            y: Vector3::new(c1r0, c1r1, c1r2),
+            z: Vector3::new(c2r0, c2r1, c2r2),
+        }
+    }
+
+    #[inline]
+    #[rustfmt::skip]
+    pub fn identity() -> Self {
+        Matrix3::new(
+            S::one(), S::zero(), S::zero(),
+            S::zero(), S::one(), S::zero(),
+            S::zero(), S::zero(), S::one(),
+        )
+    }
+
+    /// Create a homogeneous 2D transformation matrix from a translation
+    /// vector.
+    #[inline]
+    #[rustfmt::skip]
+    pub fn from_translation(v: Vector2<S>) -> Self {
+        Matrix3::new(
+            S::one(), S::zero(), S::zero(),
+            S::zero(), S::one(), S::zero(),
+            v.x, v.y, S::one(),
+        )
+    }
+
+    /// Create a homogeneous 2D transformation matrix from a scale value.
+    #[inline]
+    pub fn from_scale(value: S) -> Self {
+        Matrix3::from_nonuniform_scale(value, value)
+    }
+
+    /// Create a homogeneous 2D transformation matrix from a set of scale
+    /// values.
+    #[inline]
+    #[rustfmt::skip]
+    pub fn from_nonuniform_scale(x: S, y: S) -> Self {
+        Matrix3::new(
+            x, S::zero(), S::zero(),
+            S::zero(), y, S::zero(),
+            S::zero(), S::zero(), S::one(),
+        )
+    }
+
+    /// Create a homogeneous 2D transformation matrix from a rotation about
+    /// the z axis, in radians.
+    #[inline]
+    #[rustfmt::skip]
+    pub fn from_angle_z(theta: S) -> Self
+    where
+        S: Float,
+    {
+        let (s, c) = theta.sin_cos();
+
+        Matrix3::new(
+            c, s, S::zero(),
+            -s, c, S::zero(),
+            S::zero(), S::zero(), S::one(),
+        )
+    }
+
+    /// Transform a 2D point by the matrix, as a homogeneous transform.
+    pub fn transform_point(&self, p: Point2<S>) -> Point2<S>
+    where
+        S: std::ops::Mul<Output = S> + std::ops::Add<Output = S>,
+    {
+        Point2::new(
+            self.x.x * p.x + self.y.x * p.y + self.z.x,
+            self.x.y * p.x + self.y.y * p.y + self.z.y,
+        )
+    }
+
+    /// Transform a 2D vector by the matrix, ignoring translation.
+    pub fn transform_vector(&self, v: Vector2<S>) -> Vector2<S>
+    where
+        S: std::ops::Mul<Output = S> + std::ops::Add<Output = S>,
+    {
+        Vector2::new(
+            self.x.x * v.x + self.y.x * v.y,
+            self.x.y * v.x + self.y.y * v.y,
+        )
+    }
+}
+
+impl<S> std::ops::Mul<Matrix3<S>> for Matrix3<S>
+where
+    S: std::ops::Mul<Output = S> + std::ops::Add<Output = S> + Copy,
+{
+    type Output = Self;
+
+    fn mul(self, rhs: Matrix3<S>) -> Matrix3<S> {
+        let a = self.x;
+        let b = self.y;
+        let c = self.z;
+
+        Matrix3 {
+            x: a * rhs.x.x + b * rhs.x.y + c * rhs.x.z,
+            y: a * rhs.y.x + b * rhs.y.y + c * rhs.y.z,
+            z: a * rhs.z.x + b * rhs.z.y + c * rhs.z.z,
+        }
+    }
+}
+
+/// Embed a 2D transform in a 4 x 4 matrix, eg. for a uniform buffer.
+/// The z axis is left unchanged.
+impl<S: Copy + Zero + One> From<Matrix3<S>> for Matrix4<S> {
+    #[rustfmt::skip]
+    fn from(m: Matrix3<S>) -> Matrix4<S> {
+        Matrix4::new(
+            m.x.x, m.x.y, S::zero(), m.x.z,
+            m.y.x, m.y.y, S::zero(), m.y.z,
+            S::zero(), S::zero(), S::one(), S::zero(),
+            m.z.x, m.z.y, S::zero(), m.z.z,
+        )
+    }
+}
+
 /// A 4 x 4, column major matrix
 ///
 /// This type is marked as `#[repr(C)]`.
@@ -315,6 +661,24 @@ impl<S: Copy + Zero + One> Matrix4<S> {
         )
     }
 
+    /// Create a homogeneous transformation matrix from a rotation about the
+    /// z axis, in radians.
+    #[inline]
+    #[rustfmt::skip]
+    pub fn from_angle_z(theta: S) -> Matrix4<S>
+    where
+        S: Float,
+    {
+        let (s, c) = theta.sin_cos();
+
+        Matrix4::new(
+            c, s, S::zero(), S::zero(),
+            -s, c, S::zero(), S::zero(),
+            S::zero(), S::zero(), S::one(), S::zero(),
+            S::zero(), S::zero(), S::zero(), S::one(),
+        )
+    }
+
     /// Create a homogeneous transformation matrix from a scale value.
     #[inline]
     pub fn from_scale(value: S) -> Matrix4<S> {
@@ -9,3 +9,37 @@ pub use num_traits::{Float, One, Zero};
 pub use cgmath::prelude::*;
 #[cfg(feature = "cgmath")]
 pub use cgmath::*;
+
+pub mod noise;
+
+/// Return an orthographic projection for a `w` by `h` target, mapping
+/// pixel coordinates with the origin at the top-left corner to clip space.
+///
+/// ```
+/// use rgx::math::{self, Vector4};
+///
+/// let m = math::ortho(640, 480);
+///
+/// assert_eq!(m.x.x * 640. + m.w.x, 1.);
+/// assert_eq!(m.y.y * 480. + m.w.y, -1.);
+/// ```
+pub fn ortho(w: u32, h: u32) -> Matrix4<f32> {
+    Ortho::<f32> {
+        left: 0.0,
+        right: w as f32,
+        bottom: h as f32,
+        top: 0.0,
+        near: -1.0,
+        far: 1.0,
+    }
+    .into()
+}
+
+mod obb;
+pub use obb::Obb;
+
+mod primitives;
+pub use primitives::{Circle, Line, Triangle};
+
+mod transform;
+pub use transform::Transform2D;
@@ -0,0 +1,157 @@
+//! Deterministic 2D noise functions, eg. for procedural backgrounds,
+//! dissolve effects and particle variation.
+//!
+//! All functions return values in `[-1, 1]`, vary over a scale of about one
+//! unit, and produce the same pattern for the same `seed`.
+//!
+//! ```
+//! use rgx::math::noise;
+//!
+//! for i in 0..100 {
+//!     let (x, y) = (i as f32 * 0.37, i as f32 * 0.71);
+//!
+//!     for n in &[noise::value(x, y, 7), noise::perlin(x, y, 7), noise::simplex(x, y, 7)] {
+//!         assert!(*n >= -1. && *n <= 1.);
+//!     }
+//! }
+//! assert_eq!(noise::perlin(1.5, 2.5, 1), noise::perlin(1.5, 2.5, 1));
+//! assert_ne!(noise::perlin(1.5, 2.5, 1), noise::perlin(1.5, 2.5, 2));
+//! ```
+
+/// Value noise: random values at integer coordinates, smoothly
+/// interpolated. Cheap, but blocky compared to gradient noise.
+pub fn value(x: f32, y: f32, seed: u32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (i, j) = (x0 as i32, y0 as i32);
+    let (u, v) = (fade(x - x0), fade(y - y0));
+    let corner = |i, j| hash(i, j, seed) as f32 / u32::MAX as f32 * 2. - 1.;
+
+    lerp(
+        lerp(corner(i, j), corner(i + 1, j), u),
+        lerp(corner(i, j + 1), corner(i + 1, j + 1), u),
+        v,
+    )
+}
+
+/// Perlin gradient noise. Zero at integer coordinates.
+///
+/// ```
+/// use rgx::math::noise;
+///
+/// assert_eq!(noise::perlin(3., -2., 42), 0.);
+/// ```
+pub fn perlin(x: f32, y: f32, seed: u32) -> f32 {
+    let (x0, y0) = (x.floor(), y.floor());
+    let (i, j) = (x0 as i32, y0 as i32);
+    let (fx, fy) = (x - x0, y - y0);
+    let (u, v) = (fade(fx), fade(fy));
+    let corner = |di, dj| gradient(hash(i + di, j + dj, seed), fx - di as f32, fy - dj as f32);
+
+    lerp(
+        lerp(corner(0, 0), corner(1, 0), u),
+        lerp(corner(0, 1), corner(1, 1), u),
+        v,
+    )
+    .clamp(-1., 1.)
+}
+
+/// Simplex noise, which has fewer directional artifacts than Perlin noise.
+pub fn simplex(x: f32, y: f32, seed: u32) -> f32 {
+    let f2 = 0.5 * (3f32.sqrt() - 1.);
+    let g2 = (3. - 3f32.sqrt()) / 6.;
+
+    // Skew the input to find the simplex cell, and unskew its origin.
+    let s = (x + y) * f2;
+    let (i, j) = ((x + s).floor(), (y + s).floor());
+    let t = (i + j) * g2;
+    let (x0, y0) = (x - (i - t), y - (j - t));
+
+    // The middle corner of the simplex.
+    let (i1, j1) = if x0 > y0 { (1, 0) } else { (0, 1) };
+    let (i, j) = (i as i32, j as i32);
+
+    let corners = [
+        (i, j, x0, y0),
+        (i + i1, j + j1, x0 - i1 as f32 + g2, y0 - j1 as f32 + g2),
+        (i + 1, j + 1, x0 - 1. + 2. * g2, y0 - 1. + 2. * g2),
+    ];
+    let sum: f32 = corners
+        .iter()
+        .map(|&(ci, cj, dx, dy)| {
+            let t = 0.5 - dx * dx - dy * dy;
+            if t < 0. {
+                0.
+            } else {
+                t * t * t * t * gradient(hash(ci, cj, seed), dx, dy)
+            }
+        })
+        .sum();
+
+    (70. * sum).clamp(-1., 1.)
+}
+
+/// Fractal noise: `octaves` layers of `noise`, each at twice the frequency
+/// and half the amplitude of the previous one, normalized to `[-1, 1]`.
+///
+/// ```
+/// use rgx::math::noise;
+///
+/// let n = noise::fbm(noise::simplex, 0.3, 0.7, 1, 4);
+/// assert!(n >= -1. && n <= 1.);
+///
+/// // A single octave is the noise itself.
+/// assert_eq!(noise::fbm(noise::perlin, 0.3, 0.7, 1, 1), noise::perlin(0.3, 0.7, 1));
+/// ```
+pub fn fbm(noise: fn(f32, f32, u32) -> f32, x: f32, y: f32, seed: u32, octaves: u32) -> f32 {
+    let (mut sum, mut total) = (0., 0.);
+    let (mut frequency, mut amplitude) = (1., 1.);
+
+    for octave in 0..octaves {
+        sum += noise(x * frequency, y * frequency, seed.wrapping_add(octave)) * amplitude;
+        total += amplitude;
+        frequency *= 2.;
+        amplitude *= 0.5;
+    }
+    if total > 0. {
+        sum / total
+    } else {
+        0.
+    }
+}
+
+/// Hash integer coordinates and a seed into pseudo-random bits.
+fn hash(x: i32, y: i32, seed: u32) -> u32 {
+    let mix = |mut h: u32| {
+        h ^= h >> 16;
+        h = h.wrapping_mul(0x85eb_ca6b);
+        h ^= h >> 13;
+        h = h.wrapping_mul(0xc2b2_ae35);
+        h ^ (h >> 16)
+    };
+    mix(mix(mix(seed.wrapping_add(0x9e37_79b9)) ^ x as u32) ^ y as u32)
+}
+
+/// The dot product of the offset `(x, y)` with one of eight gradient
+/// directions, picked by `hash`.
+fn gradient(hash: u32, x: f32, y: f32) -> f32 {
+    match hash & 7 {
+        0 => x + y,
+        1 => -x + y,
+        2 => x - y,
+        3 => -x - y,
+        4 => x,
+        5 => -x,
+        6 => y,
+        _ => -y,
+    }
+}
+
+/// The quintic fade curve, which has zero first and second derivatives at
+/// `0` and `1`.
+fn fade(t: f32) -> f32 {
+    t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
@@ -0,0 +1,134 @@
+use crate::core::Rect;
+use crate::math::{Point2, Vector2};
+
+/// An oriented bounding box: a rectangle rotated about its center, eg. the
+/// area covered by a rotated sprite.
+///
+/// ```
+/// use std::f32::consts::FRAC_PI_4;
+///
+/// use rgx::core::Rect;
+/// use rgx::math::{Obb, Point2, Vector2};
+///
+/// let a = Obb::new(Point2::new(0., 0.), Vector2::new(4., 4.), FRAC_PI_4);
+///
+/// // The corners of the diamond are further out than the square's.
+/// assert!(a.contains(Point2::new(2.5, 0.)));
+/// assert!(!a.contains(Point2::new(1.9, 1.9)));
+///
+/// assert!(a.intersects_rect(Rect::new(2.7, -1., 4., 1.)));
+/// assert!(!a.intersects_rect(Rect::new(2., 2., 4., 4.)));
+///
+/// let b = Obb::new(Point2::new(4., 0.), Vector2::new(4., 4.), 0.);
+/// assert!(a.intersects(&b));
+/// assert!(!a.intersects(&Obb::new(Point2::new(5., 0.), Vector2::new(4., 4.), 0.)));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Obb {
+    pub center: Point2<f32>,
+    /// Half the width and height of the box, before rotation.
+    pub half: Vector2<f32>,
+    /// Rotation in radians.
+    pub angle: f32,
+}
+
+impl Obb {
+    /// Create a box of the given size, centered on `center` and rotated by
+    /// `angle`.
+    pub fn new(center: Point2<f32>, size: Vector2<f32>, angle: f32) -> Self {
+        Self {
+            center,
+            half: Vector2::new(size.x.abs() / 2., size.y.abs() / 2.),
+            angle,
+        }
+    }
+
+    /// Create a box from a rectangle rotated about its center.
+    pub fn from_rect(r: Rect<f32>, angle: f32) -> Self {
+        Self::new(
+            Point2::new((r.x1 + r.x2) / 2., (r.y1 + r.y2) / 2.),
+            Vector2::new(r.x2 - r.x1, r.y2 - r.y1),
+            angle,
+        )
+    }
+
+    /// The unit x and y axes of the box.
+    pub fn axes(&self) -> [Vector2<f32>; 2] {
+        let (sin, cos) = self.angle.sin_cos();
+
+        [Vector2::new(cos, sin), Vector2::new(-sin, cos)]
+    }
+
+    /// The corners of the box, in the order of the unrotated top-left,
+    /// top-right, bottom-right and bottom-left corners.
+    pub fn corners(&self) -> [Point2<f32>; 4] {
+        let [u, v] = self.axes();
+        let (hx, hy) = (self.half.x, self.half.y);
+        let corner = |sx: f32, sy: f32| {
+            Point2::new(
+                self.center.x + u.x * hx * sx + v.x * hy * sy,
+                self.center.y + u.y * hx * sx + v.y * hy * sy,
+            )
+        };
+        [
+            corner(-1., -1.),
+            corner(1., -1.),
+            corner(1., 1.),
+            corner(-1., 1.),
+        ]
+    }
+
+    /// The smallest axis-aligned rectangle containing the box.
+    pub fn bounds(&self) -> Rect<f32> {
+        let corners = self.corners();
+        let (xs, ys) = (corners.iter().map(|p| p.x), corners.iter().map(|p| p.y));
+
+        Rect::new(
+            xs.clone().fold(f32::INFINITY, f32::min),
+            ys.clone().fold(f32::INFINITY, f32::min),
+            xs.fold(f32::NEG_INFINITY, f32::max),
+            ys.fold(f32::NEG_INFINITY, f32::max),
+        )
+    }
+
+    /// Whether the point is inside the box or on its edge.
+    pub fn contains(&self, p: Point2<f32>) -> bool {
+        let d = Vector2::new(p.x - self.center.x, p.y - self.center.y);
+        let [u, v] = self.axes();
+
+        dot(d, u).abs() <= self.half.x && dot(d, v).abs() <= self.half.y
+    }
+
+    /// Whether the boxes overlap. Boxes which only touch don't overlap.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let (a, b) = (self.corners(), other.corners());
+        let [u1, v1] = self.axes();
+        let [u2, v2] = other.axes();
+
+        [u1, v1, u2, v2].iter().all(|axis| overlaps(&a, &b, *axis))
+    }
+
+    /// Whether the box overlaps the rectangle, which should be normalized.
+    pub fn intersects_rect(&self, r: Rect<f32>) -> bool {
+        self.intersects(&Self::from_rect(r, 0.))
+    }
+}
+
+fn dot(a: Vector2<f32>, b: Vector2<f32>) -> f32 {
+    a.x * b.x + a.y * b.y
+}
+
+/// Whether the projections of two sets of corners onto an axis overlap.
+fn overlaps(a: &[Point2<f32>; 4], b: &[Point2<f32>; 4], axis: Vector2<f32>) -> bool {
+    let project = |corners: &[Point2<f32>; 4]| {
+        corners
+            .iter()
+            .map(|p| dot(Vector2::new(p.x, p.y), axis))
+            .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), d| {
+                (lo.min(d), hi.max(d))
+            })
+    };
+    let ((a1, a2), (b1, b2)) = (project(a), project(b));
+
+    a1 < b2 && b1 < a2
+}
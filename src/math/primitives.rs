@@ -0,0 +1,223 @@
+use num_traits::Float;
+
+use crate::core::Rect;
+use crate::math::Point2;
+
+/// A line segment between two points.
+///
+/// ```
+/// use rgx::math::{Line, Point2};
+///
+/// let a = Line::new(0., 0., 4., 4.);
+/// let b = Line::new(0., 4., 4., 0.);
+///
+/// assert_eq!(a.intersection(&b), Some(Point2::new(2., 2.)));
+/// assert_eq!(a.intersection(&Line::new(5., 0., 5., 4.)), None);
+/// assert_eq!(Line::new(0., 0., 3., 4.).length(), 5.);
+/// assert_eq!(a.distance(Point2::new(4., 0.)), 8f32.sqrt());
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Line<T> {
+    pub p1: Point2<T>,
+    pub p2: Point2<T>,
+}
+
+impl<T: Float> Line<T> {
+    pub fn new(x1: T, y1: T, x2: T, y2: T) -> Self {
+        Self {
+            p1: Point2::new(x1, y1),
+            p2: Point2::new(x2, y2),
+        }
+    }
+
+    pub fn length(&self) -> T {
+        (self.p2.x - self.p1.x).hypot(self.p2.y - self.p1.y)
+    }
+
+    pub fn midpoint(&self) -> Point2<T> {
+        let two = T::one() + T::one();
+        Point2::new((self.p1.x + self.p2.x) / two, (self.p1.y + self.p2.y) / two)
+    }
+
+    /// The point on the segment closest to `p`.
+    pub fn closest_point(&self, p: Point2<T>) -> Point2<T> {
+        let (dx, dy) = (self.p2.x - self.p1.x, self.p2.y - self.p1.y);
+        let len2 = dx * dx + dy * dy;
+
+        if len2 == T::zero() {
+            return self.p1;
+        }
+        let t = ((p.x - self.p1.x) * dx + (p.y - self.p1.y) * dy) / len2;
+        let t = t.max(T::zero()).min(T::one());
+
+        Point2::new(self.p1.x + dx * t, self.p1.y + dy * t)
+    }
+
+    /// The distance from `p` to the closest point on the segment.
+    pub fn distance(&self, p: Point2<T>) -> T {
+        let c = self.closest_point(p);
+        (p.x - c.x).hypot(p.y - c.y)
+    }
+
+    /// The point where the segments cross, if they do. Parallel segments
+    /// never cross.
+    pub fn intersection(&self, other: &Self) -> Option<Point2<T>> {
+        let (rx, ry) = (self.p2.x - self.p1.x, self.p2.y - self.p1.y);
+        let (sx, sy) = (other.p2.x - other.p1.x, other.p2.y - other.p1.y);
+        let denom = rx * sy - ry * sx;
+
+        if denom == T::zero() {
+            return None;
+        }
+        let (qx, qy) = (other.p1.x - self.p1.x, other.p1.y - self.p1.y);
+        let t = (qx * sy - qy * sx) / denom;
+        let u = (qx * ry - qy * rx) / denom;
+        let unit = |v: T| v >= T::zero() && v <= T::one();
+
+        if unit(t) && unit(u) {
+            Some(Point2::new(self.p1.x + rx * t, self.p1.y + ry * t))
+        } else {
+            None
+        }
+    }
+}
+
+/// A circle.
+///
+/// ```
+/// use rgx::core::Rect;
+/// use rgx::math::{Circle, Line, Point2};
+///
+/// let c = Circle::new(Point2::new(0., 0.), 2.);
+///
+/// assert!(c.contains(Point2::new(1., 1.)));
+/// assert!(!c.contains(Point2::new(2., 2.)));
+/// assert!(c.intersects(&Circle::new(Point2::new(3., 0.), 1.5)));
+/// assert!(c.intersects_rect(Rect::new(1., 1., 3., 3.)));
+/// assert!(!c.intersects_rect(Rect::new(1.5, 1.5, 3., 3.)));
+/// assert!(c.intersects_line(&Line::new(-4., 1., 4., 1.)));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Circle<T> {
+    pub center: Point2<T>,
+    pub radius: T,
+}
+
+impl<T: Float> Circle<T> {
+    pub fn new(center: Point2<T>, radius: T) -> Self {
+        Self { center, radius }
+    }
+
+    pub fn area(&self) -> T {
+        T::from(std::f64::consts::PI).unwrap() * self.radius * self.radius
+    }
+
+    pub fn circumference(&self) -> T {
+        T::from(2. * std::f64::consts::PI).unwrap() * self.radius
+    }
+
+    /// Whether the point is inside the circle or on its edge.
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        (p.x - self.center.x).hypot(p.y - self.center.y) <= self.radius
+    }
+
+    /// Whether the circles overlap. Circles which only touch don't overlap.
+    pub fn intersects(&self, other: &Self) -> bool {
+        let d = (other.center.x - self.center.x).hypot(other.center.y - self.center.y);
+        d < self.radius + other.radius
+    }
+
+    /// Whether the circle overlaps the rectangle, which should be
+    /// normalized.
+    pub fn intersects_rect(&self, r: Rect<T>) -> bool {
+        let x = self.center.x.max(r.x1).min(r.x2);
+        let y = self.center.y.max(r.y1).min(r.y2);
+
+        (self.center.x - x).hypot(self.center.y - y) < self.radius
+    }
+
+    /// Whether the segment crosses or lies inside the circle.
+    pub fn intersects_line(&self, l: &Line<T>) -> bool {
+        l.distance(self.center) < self.radius
+    }
+}
+
+/// A triangle.
+///
+/// ```
+/// use rgx::math::{Point2, Triangle};
+///
+/// let t = Triangle::new(Point2::new(0., 0.), Point2::new(4., 0.), Point2::new(0., 4.));
+///
+/// assert_eq!(t.area(), 8.);
+/// assert!(t.contains(Point2::new(1., 1.)));
+/// assert!(!t.contains(Point2::new(3., 3.)));
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Triangle<T> {
+    pub a: Point2<T>,
+    pub b: Point2<T>,
+    pub c: Point2<T>,
+}
+
+impl<T: Float> Triangle<T> {
+    pub fn new(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> Self {
+        Self { a, b, c }
+    }
+
+    pub fn area(&self) -> T {
+        self.signed_area().abs()
+    }
+
+    /// Half the cross product of two edges: positive when the corners wind
+    /// counter-clockwise in a y-up space.
+    pub fn signed_area(&self) -> T {
+        let two = T::one() + T::one();
+        cross(self.a, self.b, self.c) / two
+    }
+
+    pub fn centroid(&self) -> Point2<T> {
+        let three = T::one() + T::one() + T::one();
+        Point2::new(
+            (self.a.x + self.b.x + self.c.x) / three,
+            (self.a.y + self.b.y + self.c.y) / three,
+        )
+    }
+
+    /// Whether the point is inside the triangle or on its edge, whatever
+    /// the winding of its corners.
+    pub fn contains(&self, p: Point2<T>) -> bool {
+        let (d1, d2, d3) = (
+            cross(self.a, self.b, p),
+            cross(self.b, self.c, p),
+            cross(self.c, self.a, p),
+        );
+        let negative = d1 < T::zero() || d2 < T::zero() || d3 < T::zero();
+        let positive = d1 > T::zero() || d2 > T::zero() || d3 > T::zero();
+
+        !(negative && positive)
+    }
+
+    /// The edges of the triangle.
+    pub fn edges(&self) -> [Line<T>; 3] {
+        [
+            Line {
+                p1: self.a,
+                p2: self.b,
+            },
+            Line {
+                p1: self.b,
+                p2: self.c,
+            },
+            Line {
+                p1: self.c,
+                p2: self.a,
+            },
+        ]
+    }
+}
+
+/// The cross product of `b - a` and `c - a`.
+fn cross<T: Float>(a: Point2<T>, b: Point2<T>, c: Point2<T>) -> T {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
@@ -0,0 +1,167 @@
+use std::ops::Mul;
+
+use crate::math::{Matrix4, Point2, Vector2};
+
+/// A 2D model transform: a scale and rotation about `origin`, followed by
+/// a translation. Useful for drawing sprites and shapes built around their
+/// own origin, without transforming their vertices on the CPU.
+///
+/// Transforms compose into matrices, since a rotation of a non-uniform
+/// scale can't be expressed as a single transform.
+///
+/// ```
+/// use std::f32::consts::FRAC_PI_2;
+///
+/// use rgx::math::{Point2, Transform2D, Vector2};
+///
+/// let t = Transform2D {
+///     translation: Vector2::new(100., 0.),
+///     rotation: FRAC_PI_2,
+///     scale: Vector2::new(2., 2.),
+///     origin: Point2::new(8., 8.),
+/// };
+///
+/// // The origin stays in place, before being translated.
+/// assert_eq!(t.transform_point(Point2::new(8., 8.)), Point2::new(108., 8.));
+///
+/// let p = t.transform_point(Point2::new(9., 8.));
+/// assert!((p.x - 108.).abs() < 1e-5 && (p.y - 10.).abs() < 1e-5);
+///
+/// let m = t.to_matrix();
+/// assert!((m.w.x - t.transform_point(Point2::new(0., 0.)).x).abs() < 1e-5);
+///
+/// let parent = Transform2D::from_translation(Vector2::new(0., 50.));
+/// let m = parent * t;
+/// assert!((m.w.y - (t.to_matrix().w.y + 50.)).abs() < 1e-5);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform2D {
+    pub translation: Vector2<f32>,
+    /// Rotation in radians, clockwise in a y-down space.
+    pub rotation: f32,
+    pub scale: Vector2<f32>,
+    /// The point scaled and rotated about, in model space.
+    pub origin: Point2<f32>,
+}
+
+impl Transform2D {
+    /// A transform which leaves points unchanged.
+    pub const IDENTITY: Self = Self {
+        translation: Vector2 { x: 0., y: 0. },
+        rotation: 0.,
+        scale: Vector2 { x: 1., y: 1. },
+        origin: Point2 { x: 0., y: 0. },
+    };
+
+    pub fn new(
+        translation: Vector2<f32>,
+        rotation: f32,
+        scale: Vector2<f32>,
+        origin: Point2<f32>,
+    ) -> Self {
+        Self {
+            translation,
+            rotation,
+            scale,
+            origin,
+        }
+    }
+
+    pub fn from_translation(translation: Vector2<f32>) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_rotation(rotation: f32, origin: Point2<f32>) -> Self {
+        Self {
+            rotation,
+            origin,
+            ..Self::IDENTITY
+        }
+    }
+
+    pub fn from_scale(scale: Vector2<f32>, origin: Point2<f32>) -> Self {
+        Self {
+            scale,
+            origin,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// The transform as a column major matrix, eg. for a model uniform.
+    #[rustfmt::skip]
+    pub fn to_matrix(&self) -> Matrix4<f32> {
+        let (a, b, c, d) = self.linear();
+        let t = self.offset(a, b, c, d);
+
+        Matrix4::new(
+            a, b, 0., 0.,
+            c, d, 0., 0.,
+            0., 0., 1., 0.,
+            t.x, t.y, 0., 1.,
+        )
+    }
+
+    /// Transform a point in model space.
+    pub fn transform_point(&self, p: Point2<f32>) -> Point2<f32> {
+        let (a, b, c, d) = self.linear();
+        let t = self.offset(a, b, c, d);
+
+        Point2::new(a * p.x + c * p.y + t.x, b * p.x + d * p.y + t.y)
+    }
+
+    /// The rotation and scale, as the columns `(a, b)` and `(c, d)` of a
+    /// 2 x 2 matrix.
+    fn linear(&self) -> (f32, f32, f32, f32) {
+        let (sin, cos) = self.rotation.sin_cos();
+
+        (
+            cos * self.scale.x,
+            sin * self.scale.x,
+            -sin * self.scale.y,
+            cos * self.scale.y,
+        )
+    }
+
+    /// The translation of the matrix, keeping the origin in place.
+    fn offset(&self, a: f32, b: f32, c: f32, d: f32) -> Vector2<f32> {
+        let o = self.origin;
+
+        Vector2::new(
+            self.translation.x + o.x - (a * o.x + c * o.y),
+            self.translation.y + o.y - (b * o.x + d * o.y),
+        )
+    }
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl From<Transform2D> for Matrix4<f32> {
+    fn from(t: Transform2D) -> Self {
+        t.to_matrix()
+    }
+}
+
+/// Apply `rhs` first, then `self`.
+impl Mul<Transform2D> for Transform2D {
+    type Output = Matrix4<f32>;
+
+    fn mul(self, rhs: Self) -> Matrix4<f32> {
+        self.to_matrix() * rhs.to_matrix()
+    }
+}
+
+/// Apply `rhs` first, then `self`.
+impl Mul<Transform2D> for Matrix4<f32> {
+    type Output = Self;
+
+    fn mul(self, rhs: Transform2D) -> Self {
+        self * rhs.to_matrix()
+    }
+}